@@ -93,6 +93,18 @@
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 //!
+//! ### Gundam Frame Sets
+//! A Gundam-tiled frame carries its tiles and full-resolution global view as
+//! a [`GundamFrameSet`] in [`FrameMeta::gundam`]. Consumers that understand
+//! Gundam input read the views from there; the frame's own pixels are just a
+//! rendering of them for video output, such as a [`GundamCompositor`]
+//! mosaic with the global view after, before or beside the tiles.
+//!
+//! ### Headless Testing
+//! [`test_pattern::TestPatternSource`] generates `videotestsrc` frames (SMPTE
+//! bars, bouncing ball, snow) with a running-time overlay, for exercising
+//! streams on machines without a display.
+//!
 //! ## Future Optimizations
 //!
 //! TODO: Add support for multiple simultaneous streams with different encodings.
@@ -112,11 +124,17 @@ use gstreamer_app as gst_app;
 use gstreamer_rtsp_server::prelude::*;
 use gstreamer_rtsp_server::{RTSPMediaFactory, RTSPServer};
 use once_cell::sync::OnceCell;
+use std::collections::BTreeMap;
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+pub mod test_pattern;
+
+pub use cap_scale::presets::Point;
+pub use cap_scale::transform::{MosaicView, Transform, ViewKind};
+
 /// A raw BGRA frame ready for RTSP streaming.
 ///
 /// This structure represents a complete video frame with all necessary metadata.
@@ -143,6 +161,108 @@ pub struct BgraFrame {
     pub stride: usize, // bytes per row
     /// Optional presentation timestamp in nanoseconds
     pub pts_ns: Option<u64>, // nanoseconds; if None, appsrc/do-timestamp will stamp
+    /// Pointer state at capture time, if the source reports it.
+    ///
+    /// The cursor is not part of `data` unless the source draws it in; use it
+    /// to composite the cursor later or to record where the pointer was.
+    pub cursor: Option<CursorInfo>,
+    /// Where the frame came from and how it was transformed since capture.
+    pub meta: FrameMeta,
+}
+
+/// Metadata travelling with a frame through processing and streaming.
+///
+/// Sources fill in where the frame came from; every processor that changes
+/// geometry appends a [`Transform`] so consumers can map coordinates in the
+/// final frame back to captured pixels. Cloning is cheap: the transform list
+/// and attributes are shared until a processor appends to them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameMeta {
+    /// Capture source that produced the frame, e.g. `"x11-shm"`
+    pub source: Option<Arc<str>>,
+    /// Position of the frame in the capture, counting from 0
+    pub frame_index: Option<u64>,
+    /// Frame size as delivered by the capture source, before processing
+    pub capture_size: Option<(u32, u32)>,
+    /// Geometry transforms applied since capture, oldest first
+    pub transforms: Arc<Vec<Transform>>,
+    /// Free-form key/value pairs for data without a dedicated field
+    pub attributes: Arc<BTreeMap<String, String>>,
+    /// Tiles and global view of the last Gundam tiling, whatever the frame
+    /// itself shows
+    pub gundam: Option<Arc<GundamFrameSet>>,
+}
+
+impl FrameMeta {
+    /// Metadata for a frame produced by `source`.
+    pub fn from_source(source: &str) -> Self {
+        Self {
+            source: Some(source.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Appends `transform`, copying the list only if it is shared.
+    pub fn push_transform(&mut self, transform: Transform) {
+        Arc::make_mut(&mut self.transforms).push(transform);
+    }
+
+    /// Sets attribute `key` to `value`, copying the map only if it is shared.
+    pub fn set_attribute(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        Arc::make_mut(&mut self.attributes).insert(key.into(), value.into());
+    }
+
+    /// Value of attribute `key`, if set.
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.get(key).map(String::as_str)
+    }
+
+    /// Maps `p` in frame pixels back through every recorded transform to the
+    /// captured screen or window, or `None` if it lies in padding.
+    ///
+    /// See [`map_chain_to_input`](cap_scale::transform::map_chain_to_input).
+    pub fn map_to_screen(&self, p: Point) -> Option<Point> {
+        cap_scale::transform::map_chain_to_input(&self.transforms, p)
+    }
+
+    /// Maps `p` in captured screen or window pixels to frame pixels, or
+    /// `None` if the frame does not show it.
+    pub fn map_from_screen(&self, p: Point) -> Option<Point> {
+        cap_scale::transform::map_chain_to_output(&self.transforms, p)
+    }
+}
+
+/// Pointer state when a frame was captured.
+///
+/// Coordinates are in frame pixels and give the cursor hotspot; they may lie
+/// outside the frame when the pointer is elsewhere on the desktop.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CursorInfo {
+    /// Hotspot x coordinate in frame pixels
+    pub x: i32,
+    /// Hotspot y coordinate in frame pixels
+    pub y: i32,
+    /// Whether the pointer is over the captured area
+    pub visible: bool,
+    /// Whether a mouse button was held down
+    pub pressed: bool,
+    /// Current cursor shape, if the source can read it
+    pub image: Option<Arc<CursorImage>>,
+}
+
+/// Cursor bitmap with its hotspot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CursorImage {
+    /// Image width in pixels
+    pub width: u32,
+    /// Image height in pixels
+    pub height: u32,
+    /// Hotspot offset from the left edge
+    pub hot_x: u32,
+    /// Hotspot offset from the top edge
+    pub hot_y: u32,
+    /// Premultiplied BGRA pixels, tightly packed (`width * 4` bytes per row)
+    pub data: Vec<u8>,
 }
 
 /// Handle for publishing frames to the RTSP server.
@@ -217,6 +337,8 @@ struct Shared {
     next_pts: u64,
     /// Frame duration in nanoseconds (calculated from framerate)
     frame_duration: u64,
+    /// Frame size the appsrc caps currently declare
+    caps_size: (u32, u32),
 }
 
 /// Global shared state instance (once_cell ensures single server per process)
@@ -237,7 +359,8 @@ static SHARED: OnceCell<Arc<Mutex<Shared>>> = OnceCell::new();
 ///
 /// - **appsrc**: Receives BGRA frames from application
 /// - **videoconvert**: Converts BGRA to I420 colorspace
-/// - **videoscale**: Handles any resolution adjustments
+/// - **videoscale**: Scales frames to the configured width and height, so
+///   the encoder keeps one resolution when the frame size changes
 /// - **encoder**: H.264 encoding (configurable)
 /// - **rtph264pay**: RTP packetization for network streaming
 ///
@@ -247,6 +370,13 @@ static SHARED: OnceCell<Arc<Mutex<Shared>>> = OnceCell::new();
 /// - **Push worker**: Async task polls for frames and feeds appsrc
 /// - **Caller threads**: Can call `send()` concurrently without blocking
 ///
+/// # Frame Size Changes
+///
+/// `cfg.width` and `cfg.height` are the size of the first frames and of the
+/// encoded stream. When a frame of another size arrives, the push worker
+/// updates the appsrc caps before pushing it, so the caps always describe the
+/// frames; `videoscale` then scales them to the encoded size.
+///
 /// # Resource Management
 ///
 /// - Bounded crossbeam channel prevents memory ballooning
@@ -281,11 +411,15 @@ pub fn start_server(cfg: RtspConfig) -> Result<(RtspPublisher, thread::JoinHandl
     });
 
     // Launch string with named appsrc so we can grab it in media-configure.
-    // We declare caps for BGRA with fixed width/height/framerate.
+    // We declare caps for BGRA with fixed width/height/framerate; the encoder
+    // size is pinned so frame size changes stop at videoscale.
     let launch = format!(
-        "appsrc name=src is-live=true format=time do-timestamp=true block=true caps=video/x-raw,format=BGRA,width={},height={},framerate={}/1 \
-         ! videoconvert ! videoscale ! video/x-raw,format=I420 ! {} ! rtph264pay name=pay0 pt=96 config-interval=1",
-        cfg.width, cfg.height, cfg.framerate, enc
+        "appsrc name=src is-live=true format=time do-timestamp=true block=true caps=video/x-raw,format=BGRA,width={w},height={h},framerate={fps}/1 \
+         ! videoconvert ! videoscale ! video/x-raw,format=I420,width={w},height={h} ! {enc} ! rtph264pay name=pay0 pt=96 config-interval=1",
+        w = cfg.width,
+        h = cfg.height,
+        fps = cfg.framerate,
+        enc = enc
     );
 
     // Shared state: appsrc handle + pts stepping
@@ -293,11 +427,14 @@ pub fn start_server(cfg: RtspConfig) -> Result<(RtspPublisher, thread::JoinHandl
         appsrc: None,
         next_pts: 0,
         frame_duration: (1_000_000_000u64) / (cfg.framerate.max(1) as u64),
+        caps_size: (cfg.width, cfg.height),
     }));
     SHARED.set(shared.clone()).ok();
 
     let port = cfg.port;
     let mount = cfg.mount.clone();
+    let launch_size = (cfg.width, cfg.height);
+    let framerate = cfg.framerate;
     let max_bytes = cfg.appsrc_max_bytes;
 
     let handle = thread::spawn(move || {
@@ -337,10 +474,12 @@ pub fn start_server(cfg: RtspConfig) -> Result<(RtspPublisher, thread::JoinHandl
                         appsrc.set_max_bytes(bytes as u64);
                     }
 
-                    // Save handle for push loop as an AppSrc (clone the object)
+                    // Save handle for push loop as an AppSrc (clone the object);
+                    // a new media starts with the caps from the launch string
                     if let Some(shared) = SHARED.get() {
                         let mut s = shared.lock().unwrap();
                         s.appsrc = Some(appsrc.clone());
+                        s.caps_size = launch_size;
                     }
                 }
             }
@@ -355,7 +494,7 @@ pub fn start_server(cfg: RtspConfig) -> Result<(RtspPublisher, thread::JoinHandl
         // we can gracefully handle back-pressure without starving mainloop.
         let shared_clone = shared.clone();
         ctx.spawn_local(async move {
-            push_worker(rx, shared_clone).await;
+            push_worker(rx, shared_clone, framerate).await;
         });
 
         eprintln!(
@@ -391,7 +530,12 @@ pub fn start_server(cfg: RtspConfig) -> Result<(RtspPublisher, thread::JoinHandl
 ///
 /// Uses mpsc channel + GLib timeout to bridge sync crossbeam channel
 /// with async GLib context, ensuring proper integration with GStreamer.
-async fn push_worker(rx: Receiver<BgraFrame>, shared: Arc<Mutex<Shared>>) {
+///
+/// # Size Changes
+///
+/// Frames are pushed in order, so setting new appsrc caps right before the
+/// first frame of a new size keeps caps and buffers in step.
+async fn push_worker(rx: Receiver<BgraFrame>, shared: Arc<Mutex<Shared>>, framerate: u32) {
     // Use mpsc channel and GLib timeout to poll for frames
     let (glib_tx, glib_rx) = mpsc::channel::<BgraFrame>();
 
@@ -408,12 +552,19 @@ async fn push_worker(rx: Receiver<BgraFrame>, shared: Arc<Mutex<Shared>>) {
         match glib_rx.try_recv() {
             Ok(frame) => {
                 // Get current appsrc (None if no client is connected yet)
-                let (maybe_appsrc, next_pts, frame_dur) = {
+                let (maybe_appsrc, next_pts, frame_dur, caps_size) = {
                     let s = shared.lock().unwrap();
-                    (s.appsrc.clone(), s.next_pts, s.frame_duration)
+                    (s.appsrc.clone(), s.next_pts, s.frame_duration, s.caps_size)
                 };
 
                 if let Some(appsrc) = maybe_appsrc {
+                    // Renegotiate before the first frame of a new size
+                    let size = (frame.width, frame.height);
+                    if size != caps_size {
+                        appsrc.set_caps(Some(&bgra_caps(size.0, size.1, framerate)));
+                        shared.lock().unwrap().caps_size = size;
+                    }
+
                     // Allocate buffer and copy data
                     let mut buffer = match gst::Buffer::with_size(frame.data.len()) {
                         Ok(b) => b,
@@ -456,6 +607,16 @@ async fn push_worker(rx: Receiver<BgraFrame>, shared: Arc<Mutex<Shared>>) {
     });
 }
 
+/// Caps for BGRA frames of `width` × `height` at `framerate`.
+fn bgra_caps(width: u32, height: u32, framerate: u32) -> gst::Caps {
+    gst::Caps::builder("video/x-raw")
+        .field("format", "BGRA")
+        .field("width", width as i32)
+        .field("height", height as i32)
+        .field("framerate", gst::Fraction::new(framerate as i32, 1))
+        .build()
+}
+
 impl RtspPublisher {
     /// Send a frame to the RTSP stream with back-pressure handling.
     ///
@@ -540,108 +701,515 @@ pub fn frame_from_bgra(bytes: Vec<u8>, width: u32, height: u32, fps: u32, idx: u
         height,
         stride,
         pts_ns,
+        cursor: None,
+        meta: FrameMeta {
+            frame_index: Some(idx),
+            ..FrameMeta::default()
+        },
     }
 }
 
-/// Arrange Gundam tiles and global view into a single composite frame for RTSP streaming.
-///
-/// Creates a grid layout where tiles are arranged in rows, with the global view
-/// placed at the end. For example, with 4 tiles + global:
-/// [Tile1][Tile2][Global]
-/// [Tile3][Tile4][Empty ]
-///
-/// Returns the composite frame data and its dimensions.
-///
-/// # Performance Characteristics
-///
-/// **Time complexity**: O(num_tiles × tile_side² + global_side²) - Nested loops iterate
-/// over all pixels in tiles and global view. For typical values (4 tiles × 640×640 + 1024×1024),
-/// this represents O(10^7) operations per frame, which is acceptable for preprocessing.
-///
-/// **Missing functionality**: None - fully implements Gundam composite arrangement
-/// with proper grid layout and scaling.
+/// One tile of a [`GundamFrameSet`].
+#[derive(Clone, PartialEq)]
+pub struct GundamTile {
+    /// `tile_side` × `tile_side` BGRA pixels with packed rows
+    pub data: Arc<Vec<u8>>,
+    /// Region of the input frame the tile shows
+    pub source: cap_scale::gundam::Rect,
+    /// Content score of that region
+    pub score: cap_scale::content::TileScore,
+}
+
+impl std::fmt::Debug for GundamTile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GundamTile")
+            .field("data_len", &self.data.len())
+            .field("source", &self.source)
+            .field("score", &self.score)
+            .finish()
+    }
+}
+
+/// Gundam views of one frame as separate buffers: the tiles at
+/// `tile_side`² and the global view at its full `global_side`².
 ///
-pub fn arrange_gundam_composite(
-    tiles: &[Vec<u8>],
-    global: &[u8],
-    tile_side: u32,
-    global_side: u32,
-) -> (Vec<u8>, u32, u32) {
-    let num_tiles = tiles.len();
-    if num_tiles == 0 {
-        // Fallback: just return global view
-        return (global.to_vec(), global_side, global_side);
+/// This is what a vision model consumes; a mosaic from
+/// [`render_mosaic`](Self::render_mosaic) is only a way to fit the views
+/// into one video frame. Buffers are shared, so cloning a set is cheap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GundamFrameSet {
+    /// Size of the frame the views were taken from
+    pub input: cap_scale::presets::Size,
+    /// Grid the input was divided into, as `(cols, rows)`; with content
+    /// selection some cells have no tile
+    pub grid: (u32, u32),
+    /// Tiling configuration, including `tile_side` and `global_side`
+    pub cfg: cap_scale::gundam::GundamCfg,
+    /// Chosen tiles in reading order
+    pub tiles: Vec<GundamTile>,
+    /// `global_side` × `global_side` BGRA view of the whole input, packed rows
+    pub global: Arc<Vec<u8>>,
+}
+
+impl GundamFrameSet {
+    /// Source rectangles of the tiles, in tile order.
+    pub fn rects(&self) -> Vec<cap_scale::gundam::Rect> {
+        self.tiles.iter().map(|tile| tile.source).collect()
     }
 
-    // Calculate grid dimensions
-    // Try to make it roughly square, with global view as a separate element
-    let total_elements = num_tiles + 1;
-    let cols = ((total_elements as f32).sqrt().ceil() as u32).max(1);
-    let rows = (((total_elements as u32 + cols - 1) / cols) as usize).max(1) as u32;
+    /// Draws the views into a mosaic with `slots` tile cells using
+    /// `compositor`, as `(data, width, height)`; cells without a tile show
+    /// the compositor's background.
+    ///
+    /// Passing the most tiles the grid can produce keeps the mosaic size
+    /// constant when content selection drops tiles.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`GundamCompositor::compose`].
+    ///
+    /// **Time complexity**: O(slots × tile_side² + global_side²).
+    pub fn render_mosaic(
+        &self,
+        compositor: &mut GundamCompositor,
+        slots: usize,
+    ) -> Result<(Arc<Vec<u8>>, u32, u32)> {
+        let tiles: Vec<&[u8]> = self.tiles.iter().map(|tile| &tile.data[..]).collect();
+        compositor.compose(
+            &tiles,
+            slots,
+            &self.global,
+            self.cfg.tile_side,
+            self.cfg.global_side,
+        )
+    }
 
-    // Frame dimensions
-    let frame_width = cols * tile_side;
-    let frame_height = rows * tile_side;
+    /// Transform describing the mosaic [`render_mosaic`](Self::render_mosaic)
+    /// draws with `slots` cells and a compositor using `layout`.
+    ///
+    /// **Time complexity**: O(tiles).
+    pub fn mosaic_transform(&self, layout: &CompositeLayout, slots: usize) -> Transform {
+        layout.transform(self.input, &self.rects(), slots, &self.cfg)
+    }
 
-    // Create composite frame
-    let mut composite = vec![255u8; (frame_width * frame_height * 4) as usize]; // White background
+    /// Transform describing a frame of just the global view.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn global_transform(&self) -> Transform {
+        gundam_composite_transform(self.input, &[], 0, &self.cfg)
+    }
+}
 
-    // Place tiles in grid
-    for (i, tile) in tiles.iter().enumerate() {
-        let row = (i as u32) / cols;
-        let col = (i as u32) % cols;
+/// Where a [`GundamCompositor`] puts the global view.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GlobalPlacement {
+    /// In the grid cell after the last tile, shrunk to a tile
+    #[default]
+    Last,
+    /// In the first grid cell, before the tiles, shrunk to a tile
+    First,
+    /// At its full `global_side` in a column right of the tile grid
+    Beside,
+}
 
-        if row >= rows {
-            break; // Shouldn't happen, but defensive
+/// Arrangement of a Gundam mosaic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompositeLayout {
+    /// Where the global view goes
+    pub global: GlobalPlacement,
+    /// RGBA color of cells and areas without a view
+    pub bg_rgba: [u8; 4],
+}
+
+impl Default for CompositeLayout {
+    fn default() -> Self {
+        Self {
+            global: GlobalPlacement::Last,
+            bg_rgba: [255, 255, 255, 255], // White, like the tile padding
         }
+    }
+}
+
+/// Positions of the views in a mosaic.
+struct MosaicGeometry {
+    width: u32,
+    height: u32,
+    /// Top-left corner of each tile cell, `tile_side` square
+    cells: Vec<(u32, u32)>,
+    /// Top-left corner and side of the global view
+    global: (u32, u32, u32),
+}
 
-        let dst_x = col * tile_side;
-        let dst_y = row * tile_side;
+impl CompositeLayout {
+    /// Size of the mosaic for `slots` tile cells, as `(width, height)`.
+    ///
+    /// **Time complexity**: O(slots).
+    pub fn size(&self, slots: usize, tile_side: u32, global_side: u32) -> (u32, u32) {
+        let geometry = self.geometry(slots, tile_side, global_side);
+        (geometry.width, geometry.height)
+    }
+
+    /// Describes where a [`GundamCompositor`] with this layout puts each tile
+    /// and the global view of an `input`-sized frame packed with `cfg`.
+    ///
+    /// `rects` are the tile source rectangles from
+    /// [`tile_rects`](cap_scale::gundam::tile_rects) or
+    /// [`select_tiles`](cap_scale::gundam::select_tiles) and `slots` the
+    /// number of tile cells; cells beyond `rects` hold no tile. The returned
+    /// [`Transform::Mosaic`] excludes tile and global padding from each view.
+    ///
+    /// **Time complexity**: O(tiles).
+    pub fn transform(
+        &self,
+        input: cap_scale::presets::Size,
+        rects: &[cap_scale::gundam::Rect],
+        slots: usize,
+        cfg: &cap_scale::gundam::GundamCfg,
+    ) -> Transform {
+        use cap_scale::gundam::{global_plan, tile_plan, Rect};
+        use cap_scale::presets::Size;
+
+        let side = cfg.tile_side;
+        let geometry = self.geometry(slots, side, cfg.global_side);
+        let mut views: Vec<MosaicView> = rects
+            .iter()
+            .zip(&geometry.cells)
+            .enumerate()
+            .map(|(i, (rect, &(cx, cy)))| {
+                let (x, y, w, h) = tile_plan(rect.w, rect.h, cfg)
+                    .dst_roi
+                    .unwrap_or((0, 0, side, side));
+                MosaicView {
+                    kind: ViewKind::Tile(i),
+                    source: *rect,
+                    dest: Rect {
+                        x: cx + x,
+                        y: cy + y,
+                        w,
+                        h,
+                    },
+                }
+            })
+            .collect();
+
+        // The global view may be shrunk from global_side to its cell
+        let (gx, gy, g_side) = geometry.global;
+        let shrink = |v: u32| (v as u64 * g_side as u64 / cfg.global_side.max(1) as u64) as u32;
+        let (x, y, w, h) =
+            global_plan(input, cfg)
+                .dst_roi
+                .unwrap_or((0, 0, cfg.global_side, cfg.global_side));
+        views.push(MosaicView {
+            kind: ViewKind::Global,
+            source: Rect {
+                x: 0,
+                y: 0,
+                w: input.w,
+                h: input.h,
+            },
+            dest: Rect {
+                x: gx + shrink(x),
+                y: gy + shrink(y),
+                w: shrink(w).max(1),
+                h: shrink(h).max(1),
+            },
+        });
+
+        Transform::Mosaic {
+            input,
+            output: Size {
+                w: geometry.width,
+                h: geometry.height,
+            },
+            views,
+        }
+    }
 
-        // Copy tile data (640x640 BGRA)
-        for y in 0..tile_side {
-            for x in 0..tile_side {
-                let src_idx = ((y * tile_side + x) * 4) as usize;
-                let dst_idx = (((dst_y + y) * frame_width + (dst_x + x)) * 4) as usize;
+    /// Positions of the views for `slots` tile cells.
+    fn geometry(&self, slots: usize, tile_side: u32, global_side: u32) -> MosaicGeometry {
+        if slots == 0 {
+            // Nothing to arrange: the global view alone
+            return MosaicGeometry {
+                width: global_side,
+                height: global_side,
+                cells: Vec::new(),
+                global: (0, 0, global_side),
+            };
+        }
 
-                if src_idx + 3 < tile.len() && dst_idx + 3 < composite.len() {
-                    composite[dst_idx..dst_idx + 4].copy_from_slice(&tile[src_idx..src_idx + 4]);
+        let at =
+            |i: usize, cols: u32| ((i as u32 % cols) * tile_side, (i as u32 / cols) * tile_side);
+        match self.global {
+            GlobalPlacement::Last | GlobalPlacement::First => {
+                let (cols, rows) = gundam_composite_grid(slots);
+                // Grid cell of the global view, tiles fill the others in order
+                let global_cell = if self.global == GlobalPlacement::First {
+                    0
+                } else {
+                    slots
+                };
+                let (gx, gy) = at(global_cell, cols);
+                MosaicGeometry {
+                    width: cols * tile_side,
+                    height: rows * tile_side,
+                    cells: (0..=slots)
+                        .filter(|&i| i != global_cell)
+                        .map(|i| at(i, cols))
+                        .collect(),
+                    global: (gx, gy, tile_side),
+                }
+            }
+            GlobalPlacement::Beside => {
+                let cols = ((slots as f32).sqrt().ceil() as u32).max(1);
+                let rows = (slots as u32).div_ceil(cols);
+                MosaicGeometry {
+                    width: cols * tile_side + global_side,
+                    height: (rows * tile_side).max(global_side),
+                    cells: (0..slots).map(|i| at(i, cols)).collect(),
+                    global: (cols * tile_side, 0, global_side),
                 }
             }
         }
     }
+}
 
-    // Place global view (1024x1024) - scale it down to tile_side x tile_side
-    let global_row = (num_tiles as u32) / cols;
-    let global_col = (num_tiles as u32) % cols;
+/// Reusable writer of Gundam mosaics: tiles and the global view arranged in
+/// one BGRA frame for video streams.
+///
+/// Tiles are copied row by row, the global view is scaled with
+/// `fast_image_resize` when its cell is smaller than the view, and output
+/// frames come from a small pool of buffers that are reused once consumers
+/// drop them, so steady-state streaming does not allocate.
+pub struct GundamCompositor {
+    layout: CompositeLayout,
+    resizer: fast_image_resize::Resizer,
+    /// Global view scaled to a tile cell
+    scaled_global: Vec<u8>,
+    /// Output buffers, reused when no frame holds them any more
+    outputs: Vec<Arc<Vec<u8>>>,
+}
 
-    if global_row < rows && global_col < cols {
-        let dst_x = global_col * tile_side;
-        let dst_y = global_row * tile_side;
+/// Output buffers a [`GundamCompositor`] keeps for reuse.
+const OUTPUT_POOL: usize = 4;
 
-        // Scale global view down to tile_side x tile_side
-        let scale_factor = tile_side as f32 / global_side as f32;
+impl Default for GundamCompositor {
+    fn default() -> Self {
+        Self::new(CompositeLayout::default())
+    }
+}
 
-        for y in 0..tile_side {
-            for x in 0..tile_side {
-                // Nearest neighbor sampling from global view
-                let src_x = (x as f32 / scale_factor) as u32;
-                let src_y = (y as f32 / scale_factor) as u32;
+impl std::fmt::Debug for GundamCompositor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GundamCompositor")
+            .field("layout", &self.layout)
+            .field("pooled_outputs", &self.outputs.len())
+            .finish()
+    }
+}
 
-                if src_x < global_side && src_y < global_side {
-                    let src_idx = ((src_y * global_side + src_x) * 4) as usize;
-                    let dst_idx = (((dst_y + y) * frame_width + (dst_x + x)) * 4) as usize;
+impl GundamCompositor {
+    /// Compositor arranging views as `layout` describes.
+    ///
+    /// **Time complexity**: O(1); buffers are allocated on first use.
+    pub fn new(layout: CompositeLayout) -> Self {
+        Self {
+            layout,
+            resizer: fast_image_resize::Resizer::new(),
+            scaled_global: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
 
-                    if src_idx + 3 < global.len() && dst_idx + 3 < composite.len() {
-                        composite[dst_idx..dst_idx + 4]
-                            .copy_from_slice(&global[src_idx..src_idx + 4]);
-                    }
-                }
+    /// Layout the compositor arranges views in.
+    pub fn layout(&self) -> CompositeLayout {
+        self.layout
+    }
+
+    /// Arranges `tiles` in the first of `slots` tile cells together with the
+    /// `global` view, as `(data, width, height)` with packed rows.
+    ///
+    /// Tiles are `tile_side`² and the global view `global_side`² BGRA with
+    /// packed rows. Cells past the tiles show the background. At most `slots`
+    /// tiles are drawn.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a view is shorter than its size requires or the
+    /// global view cannot be scaled to its cell.
+    ///
+    /// **Time complexity**: O(width × height) to fill the background and copy
+    /// the views, plus O(global_side²) when the global view is scaled.
+    pub fn compose(
+        &mut self,
+        tiles: &[impl AsRef<[u8]>],
+        slots: usize,
+        global: &[u8],
+        tile_side: u32,
+        global_side: u32,
+    ) -> Result<(Arc<Vec<u8>>, u32, u32)> {
+        use cap_scale::presets::{build_plan, AspectMode, ScaleTarget, Size};
+
+        let tile_len = (tile_side as usize).pow(2) * 4;
+        let global_len = (global_side as usize).pow(2) * 4;
+        if let Some(tile) = tiles.iter().find(|tile| tile.as_ref().len() < tile_len) {
+            return Err(anyhow!(
+                "Gundam tile has {} bytes, expected {} for {}x{} BGRA",
+                tile.as_ref().len(),
+                tile_len,
+                tile_side,
+                tile_side
+            ));
+        }
+        if global.len() < global_len {
+            return Err(anyhow!(
+                "Gundam global view has {} bytes, expected {} for {}x{} BGRA",
+                global.len(),
+                global_len,
+                global_side,
+                global_side
+            ));
+        }
+
+        let geometry = self.layout.geometry(slots, tile_side, global_side);
+        let stride = geometry.width as usize * 4;
+
+        // Shrink the global view to its cell, if it is not shown as is
+        let (gx, gy, g_side) = geometry.global;
+        let global_view = if g_side == global_side {
+            &global[..global_len]
+        } else {
+            let plan = build_plan(
+                Size {
+                    w: global_side,
+                    h: global_side,
+                },
+                ScaleTarget::Exact(Size {
+                    w: g_side,
+                    h: g_side,
+                }),
+                AspectMode::Distort,
+            );
+            self.scaled_global.resize((g_side as usize).pow(2) * 4, 0);
+            cap_scale::cpu::scale_bgra_cpu(
+                &mut self.resizer,
+                global,
+                plan.input,
+                None,
+                &plan,
+                &mut self.scaled_global,
+                None,
+            )?;
+            &self.scaled_global[..]
+        };
+
+        let output = pooled_buffer(&mut self.outputs, stride * geometry.height as usize);
+        let data = Arc::get_mut(output).expect("pooled buffer is unshared");
+        let [r, g, b, a] = self.layout.bg_rgba;
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&[b, g, r, a]);
+        }
+
+        for (tile, &(x, y)) in tiles.iter().zip(&geometry.cells) {
+            blit(data, stride, x, y, &tile.as_ref()[..tile_len], tile_side);
+        }
+        blit(data, stride, gx, gy, global_view, g_side);
+
+        Ok((Arc::clone(output), geometry.width, geometry.height))
+    }
+}
+
+/// Pooled buffer of `len` bytes that no frame holds, replacing the oldest
+/// pooled buffer when all are in use.
+fn pooled_buffer(pool: &mut Vec<Arc<Vec<u8>>>, len: usize) -> &mut Arc<Vec<u8>> {
+    let index = match pool.iter_mut().position(|b| Arc::get_mut(b).is_some()) {
+        Some(index) => index,
+        None => {
+            if pool.len() >= OUTPUT_POOL {
+                pool.remove(0);
             }
+            pool.push(Arc::default());
+            pool.len() - 1
         }
+    };
+    let buffer = &mut pool[index];
+    Arc::get_mut(buffer)
+        .expect("pooled buffer is unshared")
+        .resize(len, 0);
+    buffer
+}
+
+/// Copies a `side` square of packed BGRA rows into `dst` at `(x, y)`.
+fn blit(dst: &mut [u8], dst_stride: usize, x: u32, y: u32, src: &[u8], side: u32) {
+    let row = side as usize * 4;
+    for (i, src_row) in src.chunks_exact(row).take(side as usize).enumerate() {
+        let start = (y as usize + i) * dst_stride + x as usize * 4;
+        dst[start..start + row].copy_from_slice(src_row);
     }
+}
+
+/// Arrange Gundam tiles and global view into a single composite frame for RTSP streaming.
+///
+/// Creates a grid layout where tiles are arranged in rows, with the global view
+/// placed at the end. For example, with 4 tiles + global:
+/// [Tile1][Tile2][Global]
+/// [Tile3][Tile4][Empty ]
+///
+/// Returns the composite frame data and its dimensions, or the error of
+/// [`GundamCompositor::compose`] for views shorter than their size.
+///
+/// This builds a fresh [`GundamCompositor`] with the default layout on every
+/// call; keep one across frames to reuse its buffers and pick a layout.
+///
+/// # Performance Characteristics
+///
+/// **Time complexity**: O(num_tiles × tile_side² + global_side²) - Tiles are
+/// copied row by row and the global view is scaled to one cell.
+///
+/// **Missing functionality**: None - fully implements Gundam composite arrangement
+/// with proper grid layout and scaling.
+///
+pub fn arrange_gundam_composite(
+    tiles: &[impl AsRef<[u8]>],
+    global: &[u8],
+    tile_side: u32,
+    global_side: u32,
+) -> Result<(Vec<u8>, u32, u32)> {
+    let (data, width, height) =
+        GundamCompositor::default().compose(tiles, tiles.len(), global, tile_side, global_side)?;
+    let data = Arc::try_unwrap(data).unwrap_or_else(|shared| (*shared).clone());
+    Ok((data, width, height))
+}
+
+/// Grid of cells used by [`arrange_gundam_composite`] for `num_tiles` tiles
+/// plus the global view, as `(cols, rows)`.
+///
+/// The grid is roughly square, with the global view as a separate element.
+pub fn gundam_composite_grid(num_tiles: usize) -> (u32, u32) {
+    let total_elements = num_tiles + 1;
+    let cols = ((total_elements as f32).sqrt().ceil() as u32).max(1);
+    let rows = (((total_elements as u32 + cols - 1) / cols) as usize).max(1) as u32;
+    (cols, rows)
+}
 
-    (composite, frame_width, frame_height)
+/// Describes where [`arrange_gundam_composite`] puts each tile and the global
+/// view of an `input`-sized frame packed with `cfg`.
+///
+/// `rects` are the tile source rectangles from
+/// [`tile_rects`](cap_scale::gundam::tile_rects) and `slots` the number of
+/// tile buffers passed to [`arrange_gundam_composite`], which sets the grid;
+/// slots beyond `rects` hold no tile. The returned [`Transform::Mosaic`]
+/// excludes tile and global padding from each view.
+///
+/// See [`CompositeLayout::transform`] for other layouts.
+///
+/// **Time complexity**: O(tiles).
+pub fn gundam_composite_transform(
+    input: cap_scale::presets::Size,
+    rects: &[cap_scale::gundam::Rect],
+    slots: usize,
+    cfg: &cap_scale::gundam::GundamCfg,
+) -> Transform {
+    CompositeLayout::default().transform(input, rects, slots, cfg)
 }
 
 /// Trait for processing frames before RTSP streaming.
@@ -737,6 +1305,8 @@ impl RtspStreamer {
             height,
             stride,
             pts_ns,
+            cursor: None,
+            meta: FrameMeta::default(),
         };
 
         self.publisher.send(frame)
@@ -0,0 +1,275 @@
+// SPDX-License-Identifier: MIT
+//! # Synthetic Test-Pattern Frames
+//!
+//! Generates BGRA frames from GStreamer's `videotestsrc`, so RTSP and file
+//! outputs can be exercised on headless machines without a display:
+//!
+//! ```text
+//! videotestsrc pattern=<smpte|ball|snow> ! video/x-raw,width=W,height=H,framerate=F/1
+//!   ! timeoverlay ! videoconvert ! video/x-raw,format=BGRA ! appsink
+//! ```
+//!
+//! The optional `timeoverlay` burns the running time into every frame, so
+//! even static patterns change from frame to frame and dropped or repeated
+//! frames are visible in the output.
+//!
+//! ## Pacing
+//!
+//! Live sources (the default) produce frames at the configured framerate,
+//! like a screen capture. Non-live sources produce them as fast as they are
+//! pulled, which suits tests and benchmarks. Either way, timestamps are the
+//! pattern's running time: `n * 1e9 / framerate` for frame `n`.
+//!
+//! ```rust,no_run
+//! use cap_rtsp::test_pattern::{TestPattern, TestPatternConfig, TestPatternSource};
+//!
+//! let source = TestPatternSource::start(TestPatternConfig {
+//!     pattern: TestPattern::Ball,
+//!     ..Default::default()
+//! })?;
+//! let frame = source.next_frame()?;
+//! assert_eq!((frame.width, frame.height), (1280, 720));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use crate::{BgraFrame, FrameMeta};
+use anyhow::{anyhow, Result};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use gstreamer_video as gst_video;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// `videotestsrc` pattern to generate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TestPattern {
+    /// SMPTE color bars
+    #[default]
+    Smpte,
+    /// A ball bouncing over a black background
+    Ball,
+    /// Random noise; worst case for encoders and change detection
+    Snow,
+}
+
+impl TestPattern {
+    /// All patterns, in CLI order.
+    pub const ALL: [TestPattern; 3] = [TestPattern::Smpte, TestPattern::Ball, TestPattern::Snow];
+
+    /// Name used by `videotestsrc` and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            TestPattern::Smpte => "smpte",
+            TestPattern::Ball => "ball",
+            TestPattern::Snow => "snow",
+        }
+    }
+}
+
+impl fmt::Display for TestPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for TestPattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|pattern| pattern.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                anyhow!(
+                    "unknown test pattern '{}' (expected smpte, ball or snow)",
+                    s
+                )
+            })
+    }
+}
+
+/// Configuration for a [`TestPatternSource`].
+#[derive(Debug, Clone)]
+pub struct TestPatternConfig {
+    /// Frame width in pixels
+    pub width: u32,
+    /// Frame height in pixels
+    pub height: u32,
+    /// Frames per second
+    pub framerate: u32,
+    /// Pattern to draw
+    pub pattern: TestPattern,
+    /// Burn the running time into each frame
+    pub timestamp_overlay: bool,
+    /// Produce frames in real time at `framerate` instead of as fast as pulled
+    pub live: bool,
+}
+
+impl Default for TestPatternConfig {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            framerate: 30,
+            pattern: TestPattern::default(),
+            timestamp_overlay: true,
+            live: true,
+        }
+    }
+}
+
+/// Running `videotestsrc` pipeline delivering BGRA frames.
+///
+/// The pipeline is stopped when the source is dropped. Pulling is blocking;
+/// async callers should share the source through an `Arc` and pull from a
+/// blocking task.
+pub struct TestPatternSource {
+    pipeline: gst::Pipeline,
+    appsink: gst_app::AppSink,
+    config: TestPatternConfig,
+}
+
+impl TestPatternSource {
+    /// How long to wait for a frame before reporting the pipeline as stalled.
+    const FRAME_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Builds and starts the pattern pipeline.
+    ///
+    /// If the `timeoverlay` element is unavailable (it needs the GStreamer
+    /// pango plugin), frames are generated without the overlay.
+    pub fn start(config: TestPatternConfig) -> Result<Self> {
+        if config.width == 0 || config.height == 0 || config.framerate == 0 {
+            return Err(anyhow!(
+                "test pattern size and framerate must be non-zero (got {}x{} @ {} fps)",
+                config.width,
+                config.height,
+                config.framerate
+            ));
+        }
+        gst::init()?;
+
+        let src = gst::ElementFactory::make("videotestsrc")
+            .property_from_str("pattern", config.pattern.name())
+            .property("is-live", config.live)
+            .build()
+            .map_err(|_| anyhow!("videotestsrc not available (install gst-plugins-base)"))?;
+        let size_caps = gst::Caps::builder("video/x-raw")
+            .field("width", config.width as i32)
+            .field("height", config.height as i32)
+            .field("framerate", gst::Fraction::new(config.framerate as i32, 1))
+            .build();
+        let capsfilter = gst::ElementFactory::make("capsfilter")
+            .property("caps", &size_caps)
+            .build()?;
+        let overlay = if config.timestamp_overlay {
+            match gst::ElementFactory::make("timeoverlay")
+                .property_from_str("halignment", "center")
+                .property_from_str("valignment", "center")
+                .property("font-desc", "Monospace Bold 32")
+                .build()
+            {
+                Ok(overlay) => Some(overlay),
+                Err(_) => {
+                    eprintln!("timeoverlay not available; test pattern has no timestamp overlay");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let convert = gst::ElementFactory::make("videoconvert").build()?;
+        let appsink = gst_app::AppSink::builder()
+            .caps(
+                &gst::Caps::builder("video/x-raw")
+                    .field("format", "BGRA")
+                    .build(),
+            )
+            .max_buffers(2)
+            .drop(config.live)
+            .sync(false)
+            .build();
+
+        let mut chain = vec![src, capsfilter];
+        chain.extend(overlay);
+        chain.push(convert);
+        chain.push(appsink.clone().upcast());
+
+        let pipeline = gst::Pipeline::new();
+        pipeline.add_many(&chain)?;
+        gst::Element::link_many(&chain)?;
+        pipeline.set_state(gst::State::Playing)?;
+
+        Ok(Self {
+            pipeline,
+            appsink,
+            config,
+        })
+    }
+
+    /// Configuration the source was started with.
+    pub fn config(&self) -> &TestPatternConfig {
+        &self.config
+    }
+
+    /// Blocks until the next frame is available.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline failed or produced no frame within a
+    /// few seconds.
+    pub fn next_frame(&self) -> Result<BgraFrame> {
+        let timeout = gst::ClockTime::from_nseconds(Self::FRAME_TIMEOUT.as_nanos() as u64);
+        let Some(sample) = self.appsink.try_pull_sample(timeout) else {
+            let error = self
+                .pipeline
+                .bus()
+                .and_then(|bus| bus.pop_filtered(&[gst::MessageType::Error]));
+            return Err(match error.as_ref().map(|msg| msg.view()) {
+                Some(gst::MessageView::Error(err)) => {
+                    anyhow!("test pattern pipeline failed: {}", err.error())
+                }
+                _ => anyhow!(
+                    "test pattern produced no frame within {:?}",
+                    Self::FRAME_TIMEOUT
+                ),
+            });
+        };
+
+        let caps = sample
+            .caps()
+            .ok_or_else(|| anyhow!("sample without caps"))?;
+        let info = gst_video::VideoInfo::from_caps(caps)?;
+        let buffer = sample
+            .buffer()
+            .ok_or_else(|| anyhow!("sample without buffer"))?;
+        let map = buffer.map_readable()?;
+        let stride = info.stride()[0] as usize;
+        let len = stride * info.height() as usize;
+
+        Ok(BgraFrame {
+            data: Arc::new(map[..len].to_vec()),
+            width: info.width(),
+            height: info.height(),
+            stride,
+            pts_ns: buffer.pts().map(|pts| pts.nseconds()),
+            cursor: None,
+            meta: FrameMeta::from_source("videotestsrc"),
+        })
+    }
+}
+
+impl fmt::Debug for TestPatternSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestPatternSource")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl Drop for TestPatternSource {
+    fn drop(&mut self) {
+        let _ = self.pipeline.set_state(gst::State::Null);
+    }
+}
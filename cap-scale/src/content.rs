@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+//! # Tile Content Scoring
+//!
+//! Estimates how much information a region of a BGRA frame holds, so Gundam
+//! tiling can skip blank regions and spend its tile budget where the text is.
+//!
+//! ## Measures
+//!
+//! Both measures work on the luma of a sparse sample grid, every
+//! [`SAMPLE_STEP`] pixels in each direction:
+//!
+//! - **Edge density**: fraction of samples whose horizontal or vertical luma
+//!   step to the next sample exceeds [`EDGE_THRESHOLD`]. Text, UI borders and
+//!   line art score high; flat backgrounds and smooth gradients score zero
+//! - **Entropy**: Shannon entropy of the sampled luma histogram in bits.
+//!   Blank regions score zero, photos and dense text high
+//!
+//! [`TileScore::information`] combines them into one value in `0.0..=1.0`.
+//!
+//! ## Performance Characteristics
+//!
+//! - **Subsampled**: reads one pixel in `SAMPLE_STEP²`, so scoring every tile
+//!   of a 4K frame costs a fraction of scaling it
+//! - **Allocation-free**: the histogram lives on the stack
+//!
+//! ## Future Optimizations
+//!
+//! TODO: Add a text-likeness measure (stroke width, horizontal run structure).
+
+use crate::gundam::Rect;
+
+/// Distance in pixels between sampled pixels, in both directions.
+pub const SAMPLE_STEP: u32 = 2;
+
+/// Smallest luma difference between neighbouring samples counted as an edge.
+pub const EDGE_THRESHOLD: u8 = 32;
+
+/// Information measures of one region.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TileScore {
+    /// Fraction of samples on a luma edge, `0.0..=1.0`
+    pub edge_density: f32,
+    /// Entropy of the sampled luma in bits, `0.0..=8.0`
+    pub entropy: f32,
+}
+
+impl TileScore {
+    /// Combined score in `0.0..=1.0`: the mean of the edge density and the
+    /// entropy relative to its 8-bit maximum.
+    ///
+    /// A blank region scores 0.0; a region of dense text typically scores
+    /// above 0.3.
+    pub fn information(&self) -> f32 {
+        (self.edge_density + self.entropy / 8.0) / 2.0
+    }
+}
+
+/// Scores the `rect` region of a BGRA image with `stride` bytes per row.
+///
+/// # Panics
+/// If `rect` reaches outside `src_bgra`.
+///
+/// **Time complexity**: O(rect.w × rect.h / SAMPLE_STEP²).
+pub fn score_region(src_bgra: &[u8], stride: usize, rect: Rect) -> TileScore {
+    let luma = |x: u32, y: u32| {
+        let i = y as usize * stride + x as usize * 4;
+        let [b, g, r] = [src_bgra[i], src_bgra[i + 1], src_bgra[i + 2]];
+        // BT.601 weights in 8-bit fixed point
+        ((b as u32 * 29 + g as u32 * 150 + r as u32 * 77) >> 8) as u8
+    };
+
+    let mut histogram = [0u32; 256];
+    let (mut samples, mut edges) = (0u32, 0u32);
+    for y in (rect.y..rect.y + rect.h).step_by(SAMPLE_STEP as usize) {
+        for x in (rect.x..rect.x + rect.w).step_by(SAMPLE_STEP as usize) {
+            let l = luma(x, y);
+            histogram[l as usize] += 1;
+            samples += 1;
+
+            let (right, below) = (x + SAMPLE_STEP, y + SAMPLE_STEP);
+            let dx = (right < rect.x + rect.w).then(|| l.abs_diff(luma(right, y)));
+            let dy = (below < rect.y + rect.h).then(|| l.abs_diff(luma(x, below)));
+            if dx.max(dy).is_some_and(|d| d > EDGE_THRESHOLD) {
+                edges += 1;
+            }
+        }
+    }
+    if samples == 0 {
+        return TileScore::default();
+    }
+
+    let total = samples as f32;
+    let entropy = histogram
+        .iter()
+        .filter(|&&n| n > 0)
+        .map(|&n| {
+            let p = n as f32 / total;
+            -p * p.log2()
+        })
+        .sum::<f32>();
+    TileScore {
+        edge_density: edges as f32 / total,
+        entropy: entropy.max(0.0),
+    }
+}
@@ -16,17 +16,35 @@
 //!
 //! ## Grid Selection Algorithm
 //!
-//! The system automatically chooses a grid layout based on input dimensions:
+//! [`GundamCfg::grid`] picks the grid layout with a [`GridStrategy`]. The
+//! default heuristic chooses it from the input dimensions:
 //! - **Base calculation**: `cols = ceil(width/1024)`, `rows = ceil(height/1024)`
 //! - **Clamping**: Limited to 1-3 rows/columns (max 9 tiles total)
 //! - **Minimum enforcement**: Ensures at least 2 tiles for multi-resolution benefit
 //!
+//! The other strategies use a fixed grid, match the input's aspect ratio the
+//! way DeepSeek-OCR's dynamic resolution does, or size cells so tiles keep a
+//! target resolution. Only a fixed grid can have more cells than `max_tiles`.
+//!
+//! ## Tile Selection
+//!
+//! A grid can have more cells than `max_tiles`. [`TileSelection::RowMajor`]
+//! keeps the first cells in reading order, which drops the bottom-right of
+//! the frame. [`TileSelection::ByContent`] scores every cell with
+//! [`score_region`] instead, drops near-empty cells and keeps the
+//! highest-scoring ones, so the tile budget goes to the regions with text.
+//! The packing functions return the chosen rectangles with their scores.
+//!
 //! ## Performance Characteristics
 //!
 //! - **Memory efficient**: Reuses caller-provided output buffers
 //! - **Zero allocations**: All buffers pre-allocated by caller
 //! - **SIMD accelerated**: Uses same scaling engine as single-image processing
 //! - **Configurable overlap**: Tunable for different content types
+//! - **Parallel packing**: [`gundam_pack_cpu_parallel`] scales the tiles and
+//!   the global view on several threads with a [`GundamPool`] of per-thread
+//!   resizers and staging buffers, producing the same bytes as
+//!   [`gundam_pack_cpu`]
 //!
 //! ## Use Cases
 //!
@@ -39,18 +57,20 @@
 //!
 //! TODO: Consider adaptive overlap based on content analysis (text density, layout complexity).
 //! TODO: Add support for non-square tiles if DeepSeek updates their format.
-//! TODO: Investigate tile prioritization based on text density for better token efficiency.
 //! TODO: Add support for progressive tile generation to reduce initial latency.
 
 use anyhow::Result;
 use fast_image_resize::Resizer;
+use rayon::prelude::*;
 
+use crate::content::{score_region, TileScore};
 use crate::cpu::{scale_bgra_cpu, Staging};
-use crate::presets::{build_plan, AspectMode, ScaleTarget, Size};
+use crate::presets::{build_plan, AspectMode, Point, ResizeQuality, ScalePlan, ScaleTarget, Size};
+use crate::transform::ViewKind;
 
 /// Configuration for Gundam tiling matching DeepSeek-OCR input requirements.
 /// All parameters tuned for optimal OCR accuracy vs token efficiency.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct GundamCfg {
     /// Size of individual tiles (640px square for DeepSeek compatibility)
     pub tile_side: u32,
@@ -60,12 +80,17 @@ pub struct GundamCfg {
     pub min_tiles: u32,
     /// Maximum number of tiles (DeepSeek limit)
     pub max_tiles: u32,
-    /// Automatically choose grid based on input dimensions
-    pub auto_grid: bool,
+    /// How the input is divided into tile cells
+    pub grid: GridStrategy,
     /// Overlap fraction between adjacent tiles (0.0 = no overlap)
     pub overlap_frac: f32,
     /// Background color for padding (white for document processing)
     pub pad_bg: [u8; 4],
+    /// Resampling used for tiles and the global view; [`ResizeQuality::ocr`]
+    /// keeps small text crisper
+    pub quality: ResizeQuality,
+    /// Which grid cells become tiles when not all of them are worth one
+    pub selection: TileSelection,
 }
 
 impl Default for GundamCfg {
@@ -75,16 +100,64 @@ impl Default for GundamCfg {
             global_side: 1024,
             min_tiles: 2,
             max_tiles: 9,
-            auto_grid: true,
+            grid: GridStrategy::Heuristic,
             overlap_frac: 0.0, // No overlap matches public DeepSeek examples
             pad_bg: [255, 255, 255, 255], // White background for documents
+            quality: ResizeQuality::default(),
+            selection: TileSelection::RowMajor,
         }
     }
 }
 
+/// How grid cells are chosen as tiles.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TileSelection {
+    /// The first `max_tiles` cells in row-major order
+    #[default]
+    RowMajor,
+    /// Cells whose [`TileScore::information`] is at least `min_score`, the
+    /// `max_tiles` highest-scoring ones, kept in row-major order
+    ByContent { min_score: f32 },
+}
+
+impl TileSelection {
+    /// Content selection dropping cells that are blank or nearly so.
+    pub const SKIP_BLANK: TileSelection = TileSelection::ByContent { min_score: 0.05 };
+}
+
+/// A tile chosen by [`select_tiles`] with its content score.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScoredTile {
+    /// Region of the input the tile shows
+    pub rect: Rect,
+    /// Information measures of that region
+    pub score: TileScore,
+}
+
+/// How the input is divided into a grid of tile cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GridStrategy {
+    /// `ceil(side / 1024)` cells per side, 1-3 each, at least two cells
+    /// ([`choose_grid`])
+    #[default]
+    Heuristic,
+    /// `cols` × `rows` cells; beyond `max_tiles` only the first (or, with
+    /// content selection, the best) cells become tiles
+    Fixed { cols: u32, rows: u32 },
+    /// DeepSeek-OCR dynamic resolution: among the grids with `min_tiles` to
+    /// `max_tiles` cells, the one whose aspect ratio is closest to the
+    /// input's, preferring more cells on ties when the input has the pixels
+    /// to fill them
+    AspectRatio,
+    /// Enough cells that tiles show the input at `scale` tile pixels per
+    /// input pixel (1.0 = native resolution), within `min_tiles` to
+    /// `max_tiles` cells
+    Density { scale: f32 },
+}
+
 /// Rectangle definition in source pixel coordinates.
 /// Used for defining tile boundaries with potential overlap.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Rect {
     pub x: u32,
     pub y: u32,
@@ -149,7 +222,7 @@ pub fn choose_grid(in_w: u32, in_h: u32) -> (u32, u32) {
 ///
 /// # Arguments
 /// * `in_w`, `in_h` - Input image dimensions
-/// * `cols`, `rows` - Grid dimensions from choose_grid()
+/// * `cols`, `rows` - Grid dimensions from grid_for()
 /// * `overlap_frac` - Fraction of tile size to overlap (0.0 = no overlap)
 ///
 /// # Returns
@@ -191,6 +264,241 @@ fn mk_grid(in_w: u32, in_h: u32, cols: u32, rows: u32, overlap_frac: f32) -> Vec
     rects
 }
 
+/// Source rectangles of the tiles [`gundam_pack_cpu`] produces for an
+/// `in_w` × `in_h` input with [`TileSelection::RowMajor`], in row-major
+/// order.
+///
+/// With [`TileSelection::ByContent`] the tiles depend on the pixels; these
+/// are the most tiles there can be.
+///
+/// **Time complexity**: O(tiles).
+pub fn tile_rects(in_w: u32, in_h: u32, cfg: &GundamCfg) -> Vec<Rect> {
+    let mut rects = grid_cells(in_w, in_h, cfg);
+    rects.truncate(cfg.max_tiles as usize); // Limit to max tiles
+    rects
+}
+
+/// Every cell of the grid for an `in_w` × `in_h` input, in row-major order.
+fn grid_cells(in_w: u32, in_h: u32, cfg: &GundamCfg) -> Vec<Rect> {
+    // Choose grid layout based on input dimensions
+    let (cols, rows) = grid_for(in_w, in_h, cfg);
+    mk_grid(in_w, in_h, cols, rows, cfg.overlap_frac)
+}
+
+/// Grid dimensions `cfg.grid` chooses for an `in_w` × `in_h` input, as
+/// (columns, rows).
+///
+/// # Examples
+///
+/// ```rust
+/// use cap_scale::gundam::{grid_for, GridStrategy, GundamCfg};
+///
+/// let cfg = GundamCfg {
+///     grid: GridStrategy::AspectRatio,
+///     ..GundamCfg::default()
+/// };
+/// // A tall page gets more rows than columns
+/// assert_eq!(grid_for(1240, 1754, &cfg), (2, 3));
+/// ```
+///
+/// **Time complexity**: O(max_tiles²) for [`GridStrategy::AspectRatio`],
+/// O(max_tiles) for [`GridStrategy::Density`], O(1) otherwise.
+pub fn grid_for(in_w: u32, in_h: u32, cfg: &GundamCfg) -> (u32, u32) {
+    // Cells actually covered when a side is split into `cells` equal steps,
+    // so tiny inputs never get empty cells past their edge
+    let fit = |side: u32, cells: u32| side.max(1).div_ceil(side.max(1).div_ceil(cells.max(1)));
+    let (cols, rows) = match cfg.grid {
+        GridStrategy::Heuristic => choose_grid(in_w, in_h),
+        GridStrategy::Fixed { cols, rows } => (cols, rows),
+        GridStrategy::AspectRatio => closest_aspect_grid(in_w, in_h, cfg),
+        GridStrategy::Density { scale } => {
+            let cells = |side: u32| {
+                let tiles = side as f64 * scale.max(0.0) as f64 / cfg.tile_side.max(1) as f64;
+                (tiles.ceil() as u32).max(1)
+            };
+            clamp_cells(in_w, in_h, cells(in_w), cells(in_h), cfg)
+        }
+    };
+    (fit(in_w, cols), fit(in_h, rows))
+}
+
+/// Grid of `min_tiles..=max_tiles` cells closest to the input's aspect
+/// ratio, following DeepSeek-OCR's `find_closest_aspect_ratio`.
+///
+/// Candidates are visited by cell count, then columns. A later candidate
+/// with the same aspect error wins if the input has more than half the
+/// pixels of its tiles at native resolution.
+fn closest_aspect_grid(in_w: u32, in_h: u32, cfg: &GundamCfg) -> (u32, u32) {
+    let (min, max) = (cfg.min_tiles.max(1), cfg.max_tiles.max(1));
+    let mut candidates: Vec<(u32, u32)> = (1..=max)
+        .flat_map(|cols| (1..=max).map(move |rows| (cols, rows)))
+        .filter(|&(cols, rows)| (min.min(max)..=max).contains(&(cols * rows)))
+        .collect();
+    candidates.sort_by_key(|&(cols, rows)| (cols * rows, cols));
+
+    let aspect = in_w.max(1) as f64 / in_h.max(1) as f64;
+    let area = in_w as f64 * in_h as f64;
+    let tile_area = cfg.tile_side as f64 * cfg.tile_side as f64;
+    let mut best = (1, 1);
+    let mut best_diff = f64::INFINITY;
+    for (cols, rows) in candidates {
+        let diff = (aspect - cols as f64 / rows as f64).abs();
+        if diff < best_diff {
+            (best, best_diff) = ((cols, rows), diff);
+        } else if diff == best_diff && area > 0.5 * tile_area * (cols * rows) as f64 {
+            best = (cols, rows);
+        }
+    }
+    best
+}
+
+/// Brings a `cols` × `rows` grid within `min_tiles..=max_tiles` cells by
+/// removing cells along the side with the smallest cells, or adding them
+/// along the side with the largest.
+fn clamp_cells(in_w: u32, in_h: u32, mut cols: u32, mut rows: u32, cfg: &GundamCfg) -> (u32, u32) {
+    let (w, h) = (in_w.max(1) as f64, in_h.max(1) as f64);
+    let max = cfg.max_tiles.max(1);
+    while cols * rows > max {
+        if rows == 1 || (cols > 1 && w / cols as f64 <= h / rows as f64) {
+            cols -= 1;
+        } else {
+            rows -= 1;
+        }
+    }
+    while cols * rows < cfg.min_tiles.min(max) {
+        let wider = w / cols as f64 >= h / rows as f64;
+        let (next_cols, next_rows) = if wider {
+            (cols + 1, rows)
+        } else {
+            (cols, rows + 1)
+        };
+        if next_cols * next_rows > max {
+            break;
+        }
+        (cols, rows) = (next_cols, next_rows);
+    }
+    (cols, rows)
+}
+
+/// Tiles [`gundam_pack_cpu`] produces for a BGRA image, chosen by
+/// `cfg.selection`, in row-major order with their scores.
+///
+/// **Time complexity**: O(in_w × in_h / [`SAMPLE_STEP`]²) to score the
+/// cells.
+///
+/// [`SAMPLE_STEP`]: crate::content::SAMPLE_STEP
+pub fn select_tiles(
+    src_bgra: &[u8],
+    src_w: u32,
+    src_h: u32,
+    src_stride_bytes: usize,
+    cfg: &GundamCfg,
+) -> Vec<ScoredTile> {
+    let score = |rect: Rect| ScoredTile {
+        rect,
+        score: score_region(src_bgra, src_stride_bytes, rect),
+    };
+    match cfg.selection {
+        TileSelection::RowMajor => tile_rects(src_w, src_h, cfg)
+            .into_iter()
+            .map(score)
+            .collect(),
+        TileSelection::ByContent { min_score } => {
+            let mut ranked: Vec<(usize, ScoredTile)> = grid_cells(src_w, src_h, cfg)
+                .into_iter()
+                .map(score)
+                .enumerate()
+                .filter(|(_, tile)| tile.score.information() >= min_score)
+                .collect();
+            // Stable: equal scores keep reading order
+            ranked.sort_by(|a, b| b.1.score.information().total_cmp(&a.1.score.information()));
+            ranked.truncate(cfg.max_tiles as usize);
+            ranked.sort_by_key(|&(i, _)| i);
+            ranked.into_iter().map(|(_, tile)| tile).collect()
+        }
+    }
+}
+
+/// Scaling plan turning a `w` × `h` tile region into a padded
+/// `tile_side` square.
+pub fn tile_plan(w: u32, h: u32, cfg: &GundamCfg) -> ScalePlan {
+    build_plan(
+        Size { w, h },
+        ScaleTarget::Exact(Size {
+            w: cfg.tile_side,
+            h: cfg.tile_side,
+        }),
+        AspectMode::Pad {
+            bg_rgba: cfg.pad_bg,
+        },
+    )
+    .with_quality(cfg.quality)
+}
+
+/// Scaling plan turning the whole input into the padded `global_side`
+/// square.
+pub fn global_plan(input: Size, cfg: &GundamCfg) -> ScalePlan {
+    build_plan(
+        input,
+        ScaleTarget::Exact(Size {
+            w: cfg.global_side,
+            h: cfg.global_side,
+        }),
+        AspectMode::Pad {
+            bg_rgba: cfg.pad_bg,
+        },
+    )
+    .with_quality(cfg.quality)
+}
+
+/// Maps `p` from the pixels of Gundam output `view` back to input pixels of
+/// an `input`-sized image packed with `cfg`.
+///
+/// `view` selects a `tile_side` tile by its index in [`tile_rects`] order or
+/// the `global_side` global image. Returns `None` if the tile does not exist,
+/// `p` lies in its padding, or `view` is not a Gundam view.
+///
+/// Tiles chosen by [`TileSelection::ByContent`] depend on the frame; map
+/// through the [`Transform::Mosaic`](crate::transform::Transform::Mosaic)
+/// recorded for it instead.
+///
+/// **Time complexity**: O(tiles) to recompute the tile grid.
+pub fn map_view_to_input(input: Size, cfg: &GundamCfg, view: ViewKind, p: Point) -> Option<Point> {
+    match view {
+        ViewKind::Tile(i) => {
+            let rect = *tile_rects(input.w, input.h, cfg).get(i)?;
+            let local = tile_plan(rect.w, rect.h, cfg).map_to_input(p)?;
+            Some(Point {
+                x: local.x + rect.x as f64,
+                y: local.y + rect.y as f64,
+            })
+        }
+        ViewKind::Global => global_plan(input, cfg).map_to_input(p),
+        ViewKind::Input(_) => None,
+    }
+}
+
+/// Maps `p` from input pixels to the pixels of Gundam output `view`; the
+/// inverse of [`map_view_to_input`].
+///
+/// Returns `None` if the tile does not exist or does not cover `p`.
+///
+/// **Time complexity**: O(tiles) to recompute the tile grid.
+pub fn map_input_to_view(input: Size, cfg: &GundamCfg, view: ViewKind, p: Point) -> Option<Point> {
+    match view {
+        ViewKind::Tile(i) => {
+            let rect = *tile_rects(input.w, input.h, cfg).get(i)?;
+            let local = Point {
+                x: p.x - rect.x as f64,
+                y: p.y - rect.y as f64,
+            };
+            tile_plan(rect.w, rect.h, cfg).map_to_output(local)
+        }
+        ViewKind::Global => global_plan(input, cfg).map_to_output(p),
+        ViewKind::Input(_) => None,
+    }
+}
+
 /// Output buffer container for Gundam processing.
 ///
 /// All buffers must be pre-allocated by the caller with correct sizes:
@@ -209,7 +517,7 @@ pub struct GundamOutputs<'a> {
 ///
 /// This function implements the complete DeepSeek OCR preprocessing pipeline:
 /// 1. Analyze input dimensions and choose optimal grid
-/// 2. Generate overlapping tile regions
+/// 2. Generate overlapping tile regions and select them ([`select_tiles`])
 /// 3. Scale each tile to 640×640 with padding
 /// 4. Scale global view to 1024×1024 with padding
 /// 5. Write results to caller-provided buffers
@@ -228,9 +536,10 @@ pub struct GundamOutputs<'a> {
 /// - Processes tiles sequentially to minimize memory pressure
 /// - Zero allocations during processing (all buffers pre-allocated)
 ///
-/// # Future Optimizations
-/// TODO: Consider parallel tile processing for very large inputs.
-/// TODO: Add tile content analysis to prioritize high-information tiles.
+/// Returns the chosen tiles; tile buffer `i` holds tile `i`. Buffers beyond
+/// the chosen tiles are filled with `pad_bg`.
+///
+/// [`gundam_pack_cpu_parallel`] produces the same output on several threads.
 ///
 /// # Performance Characteristics
 ///
@@ -251,73 +560,216 @@ pub fn gundam_pack_cpu(
     cfg: GundamCfg,
     staging: &mut Staging,
     mut out: GundamOutputs,
-) -> Result<()> {
+) -> Result<Vec<ScoredTile>> {
     let input = Size { w: src_w, h: src_h };
+    let tiles = select_tiles(src_bgra, src_w, src_h, src_stride_bytes, &cfg);
+    clear_unused_tiles(&mut out, tiles.len(), &cfg);
 
-    // Choose grid layout based on input dimensions
-    let (cols, rows) = choose_grid(src_w, src_h);
-    let mut rects = mk_grid(src_w, src_h, cols, rows, cfg.overlap_frac);
-    rects.truncate(cfg.max_tiles as usize); // Limit to max tiles
-    if rects.len() < cfg.min_tiles as usize && cols * rows >= cfg.min_tiles {
-        // Grid selection should prevent this, but defensive check
-    }
-
-    // Create scaling plan for tiles (always pad to square)
-    let tile_plan = |w: u32, h: u32| {
-        build_plan(
-            Size { w, h },
-            ScaleTarget::Exact(Size {
-                w: cfg.tile_side,
-                h: cfg.tile_side,
-            }),
-            AspectMode::Pad {
-                bg_rgba: cfg.pad_bg,
-            },
-        )
-    };
-
-    let need_tile = (cfg.tile_side as usize) * (cfg.tile_side as usize) * 4;
-    for (i, r) in rects.iter().enumerate() {
-        let plan = tile_plan(r.w, r.h);
+    for (i, tile) in tiles.iter().enumerate() {
         let dst = out.tiles.get_mut(i).expect("insufficient tile buffers");
-        assert!(dst.len() >= need_tile, "tile buffer too small");
-
-        // Extract tile region into staging buffer (handles strided input)
-        compact_crop_to_staging(src_bgra, src_stride_bytes, *r, staging);
-
-        // Scale tile to final size
-        scale_bgra_cpu(
+        pack_tile(
             resizer,
-            staging.as_slice(),
-            Size { w: r.w, h: r.h },
-            Some((r.w as usize) * 4), // staging is always tightly packed
-            &plan,
-            *dst,
-            None, // staging already compacted
+            src_bgra,
+            src_stride_bytes,
+            tile.rect,
+            &cfg,
+            staging,
+            dst,
         )?;
     }
 
     // Process global view
-    let global_plan = build_plan(
+    pack_global(
+        resizer,
+        src_bgra,
         input,
-        ScaleTarget::Exact(Size {
-            w: cfg.global_side,
-            h: cfg.global_side,
-        }),
-        AspectMode::Pad {
-            bg_rgba: cfg.pad_bg,
-        },
-    );
+        src_stride_bytes,
+        &cfg,
+        staging,
+        out.global,
+    )?;
+    Ok(tiles)
+}
+
+/// Worker threads, resizers and staging buffers for
+/// [`gundam_pack_cpu_parallel`].
+///
+/// The pool owns a dedicated rayon thread pool, so packing runs on exactly
+/// [`threads`](Self::threads) threads regardless of rayon's global pool.
+///
+/// Keep one pool per stream and reuse it across frames: every worker's
+/// staging buffer grows to the largest region it scales once and is reused
+/// afterwards.
+pub struct GundamPool {
+    threads: rayon::ThreadPool,
+    workers: Vec<(Resizer, Staging)>,
+}
+
+impl GundamPool {
+    /// Pool of `threads` workers, at least one.
+    ///
+    /// A frame has at most `max_tiles + 1` scaling jobs, so more workers than
+    /// that are never busy.
+    ///
+    /// # Panics
+    /// If the operating system refuses to spawn the worker threads.
+    pub fn new(threads: usize) -> Self {
+        let threads = threads.max(1);
+        Self {
+            threads: rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|i| format!("gundam-{}", i))
+                .build()
+                .expect("failed to spawn Gundam worker threads"),
+            workers: (0..threads)
+                .map(|_| (Resizer::new(), Staging::with_capacity(0)))
+                .collect(),
+        }
+    }
+
+    /// Pool with one worker per available CPU.
+    pub fn with_available_parallelism() -> Self {
+        Self::new(std::thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+
+    /// Number of workers.
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+}
+
+impl std::fmt::Debug for GundamPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GundamPool")
+            .field("threads", &self.workers.len())
+            .finish()
+    }
+}
+
+/// [`gundam_pack_cpu`] with the tiles and the global view scaled in
+/// parallel on the threads of `pool`, one worker per thread.
+///
+/// Jobs are dealt to the workers round-robin with the global view first, as
+/// it is the largest. Every job runs the same scaling code as the sequential
+/// path, so the output is byte-identical to [`gundam_pack_cpu`].
+///
+/// # Panics
+/// Like [`gundam_pack_cpu`], if `out` has too few or too small tile buffers.
+///
+/// **Time complexity**: O(num_tiles × tile_size² + global_size²) work, spread
+/// over `min(pool.threads(), num_tiles + 1)` threads.
+pub fn gundam_pack_cpu_parallel(
+    pool: &mut GundamPool,
+    src_bgra: &[u8],
+    src_w: u32,
+    src_h: u32,
+    src_stride_bytes: usize,
+    cfg: GundamCfg,
+    mut out: GundamOutputs,
+) -> Result<Vec<ScoredTile>> {
+    let input = Size { w: src_w, h: src_h };
+    let tiles = select_tiles(src_bgra, src_w, src_h, src_stride_bytes, &cfg);
+    assert!(out.tiles.len() >= tiles.len(), "insufficient tile buffers");
+    clear_unused_tiles(&mut out, tiles.len(), &cfg);
+
+    // `None` is the global view
+    let GundamPool {
+        threads: thread_pool,
+        workers,
+    } = pool;
+    let threads = workers.len();
+    let mut jobs: Vec<Vec<(Option<Rect>, &mut [u8])>> = (0..threads).map(|_| Vec::new()).collect();
+    let all = std::iter::once((None, out.global))
+        .chain(tiles.iter().map(|tile| Some(tile.rect)).zip(out.tiles));
+    for (i, job) in all.enumerate() {
+        jobs[i % threads].push(job);
+    }
+
+    thread_pool.install(|| {
+        jobs.into_par_iter()
+            .zip(workers.par_iter_mut())
+            .try_for_each(|(jobs, (resizer, staging))| {
+                for (rect, dst) in jobs {
+                    match rect {
+                        Some(r) => {
+                            pack_tile(resizer, src_bgra, src_stride_bytes, r, &cfg, staging, dst)?
+                        }
+                        None => pack_global(
+                            resizer,
+                            src_bgra,
+                            input,
+                            src_stride_bytes,
+                            &cfg,
+                            staging,
+                            dst,
+                        )?,
+                    }
+                }
+                Ok::<_, anyhow::Error>(())
+            })
+    })?;
+    Ok(tiles)
+}
+
+/// Fills the tile buffers after the first `used` with `cfg.pad_bg`, so no
+/// stale tile from an earlier frame shows.
+fn clear_unused_tiles(out: &mut GundamOutputs, used: usize, cfg: &GundamCfg) {
+    let [r, g, b, a] = cfg.pad_bg;
+    for dst in out.tiles.iter_mut().skip(used) {
+        for px in dst.chunks_exact_mut(4) {
+            px.copy_from_slice(&[b, g, r, a]);
+        }
+    }
+}
+
+/// Crops tile `r` out of the source and scales it into `dst`.
+fn pack_tile(
+    resizer: &mut Resizer,
+    src_bgra: &[u8],
+    src_stride_bytes: usize,
+    r: Rect,
+    cfg: &GundamCfg,
+    staging: &mut Staging,
+    dst: &mut [u8],
+) -> Result<()> {
+    let need_tile = (cfg.tile_side as usize) * (cfg.tile_side as usize) * 4;
+    assert!(dst.len() >= need_tile, "tile buffer too small");
+
+    // Extract tile region into staging buffer (handles strided input)
+    compact_crop_to_staging(src_bgra, src_stride_bytes, r, staging);
+
+    // Scale tile to final size
+    scale_bgra_cpu(
+        resizer,
+        staging.as_slice(),
+        Size { w: r.w, h: r.h },
+        Some((r.w as usize) * 4), // staging is always tightly packed
+        &tile_plan(r.w, r.h, cfg),
+        dst,
+        None, // staging already compacted
+    )?;
+    Ok(())
+}
+
+/// Scales the whole source into the global view `dst`.
+fn pack_global(
+    resizer: &mut Resizer,
+    src_bgra: &[u8],
+    input: Size,
+    src_stride_bytes: usize,
+    cfg: &GundamCfg,
+    staging: &mut Staging,
+    dst: &mut [u8],
+) -> Result<()> {
     scale_bgra_cpu(
         resizer,
         src_bgra,
         input,
         Some(src_stride_bytes),
-        &global_plan,
-        out.global,
+        &global_plan(input, cfg),
+        dst,
         Some(staging),
     )?;
-
     Ok(())
 }
 
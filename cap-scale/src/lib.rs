@@ -16,6 +16,9 @@
 //! - [`presets`]: Scaling plan computation and token-efficient preset definitions
 //! - [`cpu`]: CPU-based scaling implementation using SIMD acceleration
 //! - [`gundam`]: DeepSeek OCR "Gundam" tiling for complex document layouts
+//! - [`content`]: Information scores that let Gundam tiling skip blank regions
+//! - [`transform`]: Geometry transforms recorded by processing steps
+//! - [`tokens`]: Vision token estimates per model family for plans, Gundam layouts and streams
 //!
 //! ## Performance Characteristics
 //!
@@ -33,6 +36,9 @@
 //! - P9: 9x token reduction (1920px → 640px longest side)
 //! - P10_24: ~10.24x token reduction (2048px → 640px longest side)
 //!
+//! For models with patch-grid rules, [`presets::ModelPreset`] scales to whole
+//! patches within each model family's pixel budget (Qwen, Claude, OpenAI).
+//!
 //! ## Usage Example
 //!
 //! ```rust
@@ -67,6 +73,9 @@
 //! TODO: Investigate zero-copy GPU buffer sharing with Vulkan/DirectX capture backends.
 //! TODO: Add support for progressive JPEG encoding to reduce network bandwidth.
 
+pub mod content;
 pub mod cpu;
 pub mod gundam;
 pub mod presets;
+pub mod tokens;
+pub mod transform;
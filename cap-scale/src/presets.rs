@@ -11,6 +11,18 @@
 //! 2. **AspectMode**: How to handle aspect ratio differences (preserve, distort, or pad)
 //! 3. **ScalePlan**: The computed output parameters and ROI for actual scaling
 //!
+//! [`ScaleSpec`] bundles a target and aspect mode with the [`ResizeQuality`]
+//! used to resample, so a scaler can be configured with one value.
+//!
+//! ## Resize Quality
+//!
+//! [`ResizeQuality`] picks the [`ResizeFilter`], whether to resample the
+//! stored sRGB values or linear light ([`GammaMode`]), and an optional
+//! [`UnsharpMask`] applied after downscaling. Resampling sRGB values directly
+//! is fastest but darkens thin light-on-dark strokes; [`ResizeQuality::ocr`]
+//! resamples in linear light and sharpens slightly, which keeps small text
+//! crisper at the same output size.
+//!
 //! ## Token Efficiency Strategy
 //!
 //! VLM token usage scales with image pixel count, but OCR accuracy depends more on longest dimension.
@@ -19,6 +31,19 @@
 //! - **640px max**: Good balance for most content (P2_56, P4, P9, P10_24)
 //! - **512px max**: Higher compression for dense text (P6_9)
 //!
+//! ## Model-Aware Presets
+//!
+//! Many VLMs cut images into fixed-size patches or tiles and bill per patch, so
+//! a partial patch at the edge costs as much as a full one. [`ScaleTarget::PatchAligned`]
+//! produces dimensions that are exact multiples of the patch size within a
+//! pixel budget, and [`ModelPreset`] names the budgets of common model families:
+//!
+//! | Preset | Patch | Pixel budget |
+//! |--------|-------|--------------|
+//! | Qwen2.5-VL / Qwen3-VL | 28px | 4-1280 patches |
+//! | Claude | 28px | ~1.15 MP, long side ~1568px |
+//! | OpenAI (high detail) | 512px tiles | 2048×768 px |
+//!
 //! ## Performance Considerations
 //!
 //! - All computations use floating-point for precision but round to integers
@@ -30,13 +55,25 @@
 //! TODO: Consider caching ScalePlan objects for repeated same-size inputs to avoid recomputation.
 //! TODO: Add support for non-square padding (letterbox vs pillarbox based on content analysis).
 
+use crate::transform::Transform;
+
 /// Represents a 2D size with width and height in pixels.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Size {
     pub w: u32,
     pub h: u32,
 }
 
+/// A point in pixel coordinates.
+///
+/// Coordinates are continuous: `(0.0, 0.0)` is the top-left corner of the
+/// first pixel and the center of pixel `(x, y)` is `(x + 0.5, y + 0.5)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
 /// Defines how aspect ratio differences are handled during scaling.
 #[derive(Clone, Copy, Debug)]
 pub enum AspectMode {
@@ -51,6 +88,91 @@ pub enum AspectMode {
     Pad { bg_rgba: [u8; 4] },
 }
 
+/// Resampling filter used when scaling.
+///
+/// Sharper filters keep small text legible at the cost of some ringing and
+/// speed; `Nearest` is only useful for pixel art or debugging.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ResizeFilter {
+    /// Nearest neighbour, no interpolation
+    Nearest,
+    /// Bilinear, fastest interpolating filter
+    Bilinear,
+    /// Catmull-Rom bicubic
+    #[clap(name = "catmull-rom")]
+    CatmullRom,
+    /// Mitchell-Netravali bicubic, softer than Catmull-Rom
+    Mitchell,
+    /// Lanczos with a 3-lobe window, sharpest
+    #[default]
+    Lanczos3,
+    /// Box filter averaging every covered input pixel (area resampling),
+    /// alias-free for large downscales but soft
+    #[clap(name = "box")]
+    Box,
+}
+
+/// Color space the resampling filter works in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GammaMode {
+    /// Filter the stored sRGB values directly; fastest, but averaging
+    /// gamma-encoded values darkens fine bright detail on dark backgrounds
+    #[default]
+    Srgb,
+    /// Convert to linear light with 16 bits per channel, filter, and encode
+    /// back to sRGB (gamma-correct resampling)
+    Linear,
+}
+
+/// Unsharp mask applied to the scaled content after downscaling.
+///
+/// Each color channel moves away from its 3×3 Gaussian-blurred value by
+/// `amount` times the difference, unless the difference is at most
+/// `threshold`, which keeps flat areas and noise untouched. Alpha is left
+/// as is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UnsharpMask {
+    /// Strength; 0.0 disables the mask, 1.0 doubles local contrast
+    pub amount: f32,
+    /// Largest difference from the blurred value that is left unsharpened
+    pub threshold: u8,
+}
+
+impl UnsharpMask {
+    /// Mild sharpening that restores edges softened by downscaling text
+    /// without visible halos.
+    pub const MILD: UnsharpMask = UnsharpMask {
+        amount: 0.5,
+        threshold: 2,
+    };
+}
+
+/// How pixels are resampled: filter, gamma handling and post-sharpening.
+///
+/// The default is [`ResizeFilter::Lanczos3`] on sRGB values without
+/// sharpening.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ResizeQuality {
+    /// Resampling filter
+    pub filter: ResizeFilter,
+    /// Color space the filter works in
+    pub gamma: GammaMode,
+    /// Sharpening applied when the content was downscaled
+    pub sharpen: Option<UnsharpMask>,
+}
+
+impl ResizeQuality {
+    /// Quality for OCR-heavy content: Lanczos3 in linear light followed by
+    /// [`UnsharpMask::MILD`].
+    pub fn ocr() -> Self {
+        Self {
+            filter: ResizeFilter::Lanczos3,
+            gamma: GammaMode::Linear,
+            sharpen: Some(UnsharpMask::MILD),
+        }
+    }
+}
+
 /// Defines the target size constraint for scaling operations.
 #[derive(Clone, Copy, Debug)]
 pub enum ScaleTarget {
@@ -60,6 +182,18 @@ pub enum ScaleTarget {
     /// Force output to exact dimensions (used with AspectMode::Distort/Pad).
     /// Less common for VLM input but useful for fixed-size model requirements.
     Exact(Size),
+    /// Round both sides to multiples of `patch` pixels, keeping the area
+    /// within `min_pixels..=max_pixels` (Qwen2-VL `smart_resize` rules).
+    ///
+    /// The aligned size may upscale small inputs to reach `min_pixels`. With
+    /// [`AspectMode::Pad`] the input keeps its aspect ratio and is padded to
+    /// the aligned size without upscaling; otherwise it is stretched to it,
+    /// which changes the aspect ratio by at most half a patch per side.
+    PatchAligned {
+        patch: u32,
+        min_pixels: u32,
+        max_pixels: u32,
+    },
 }
 
 /// Complete scaling plan computed from input parameters.
@@ -77,6 +211,138 @@ pub struct ScalePlan {
     /// If padding is used, specifies the sub-rectangle where scaled content is placed.
     /// Format: (x, y, width, height) in output coordinate space.
     pub dst_roi: Option<(u32, u32, u32, u32)>,
+    /// How pixels are resampled; [`build_plan`] uses the default
+    pub quality: ResizeQuality,
+}
+
+impl ScalePlan {
+    /// Replaces the resize quality.
+    pub fn with_quality(mut self, quality: ResizeQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Whether the content is scaled down along either axis, which is when
+    /// [`ResizeQuality::sharpen`] applies.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn downscales(&self) -> bool {
+        let (_, _, w, h) = self.dst_roi.unwrap_or((0, 0, self.out.w, self.out.h));
+        w < self.input.w || h < self.input.h
+    }
+
+    /// Maps `p` from output pixels back to input pixels, undoing the scale
+    /// and any [`AspectMode::Pad`] offset.
+    ///
+    /// Returns `None` if `p` lies in the padding or outside the output.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn map_to_input(&self, p: Point) -> Option<Point> {
+        Transform::from_plan(self).map_to_input(p)
+    }
+
+    /// Maps `p` from input pixels to output pixels; the inverse of
+    /// [`ScalePlan::map_to_input`].
+    ///
+    /// Returns `None` if `p` lies outside the input.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn map_to_output(&self, p: Point) -> Option<Point> {
+        Transform::from_plan(self).map_to_output(p)
+    }
+}
+
+/// How to scale frames: target size, aspect handling and resize quality.
+///
+/// Converts from a [`TokenPreset`] or a bare [`ScaleTarget`], so APIs taking
+/// `impl Into<ScaleSpec>` accept either.
+///
+/// # Examples
+///
+/// ```rust
+/// use cap_scale::presets::{ResizeFilter, ResizeQuality, ScaleSpec, Size};
+///
+/// // 1024px square with grey bars, for a model with a fixed input size
+/// let spec = ScaleSpec::letterbox(1024, [128, 128, 128, 255]).with_filter(ResizeFilter::CatmullRom);
+/// let plan = spec.plan(Size { w: 1920, h: 1080 });
+/// assert_eq!(plan.out, Size { w: 1024, h: 1024 });
+/// assert_eq!(plan.dst_roi, Some((0, 224, 1024, 576)));
+///
+/// // Gamma-correct and sharpened for screenshots full of small text
+/// let spec = spec.with_quality(ResizeQuality::ocr());
+/// assert_eq!(spec.plan(Size { w: 1920, h: 1080 }).quality, ResizeQuality::ocr());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ScaleSpec {
+    /// Size constraint
+    pub target: ScaleTarget,
+    /// Aspect ratio handling, including the padding color
+    pub aspect: AspectMode,
+    /// Resampling filter, gamma handling and sharpening
+    pub quality: ResizeQuality,
+}
+
+impl ScaleSpec {
+    /// Scale to `target` keeping the aspect ratio, with the default quality.
+    pub fn new(target: ScaleTarget) -> Self {
+        Self {
+            target,
+            aspect: AspectMode::Preserve,
+            quality: ResizeQuality::default(),
+        }
+    }
+
+    /// Letterbox into a `side` × `side` square padded with `bg_rgba`, for
+    /// models that only accept fixed square inputs.
+    pub fn letterbox(side: u32, bg_rgba: [u8; 4]) -> Self {
+        Self::new(ScaleTarget::Exact(Size { w: side, h: side }))
+            .with_aspect(AspectMode::Pad { bg_rgba })
+    }
+
+    /// Replaces the aspect handling.
+    pub fn with_aspect(mut self, aspect: AspectMode) -> Self {
+        self.aspect = aspect;
+        self
+    }
+
+    /// Replaces the resampling filter.
+    pub fn with_filter(mut self, filter: ResizeFilter) -> Self {
+        self.quality.filter = filter;
+        self
+    }
+
+    /// Replaces the whole resize quality, including the filter.
+    pub fn with_quality(mut self, quality: ResizeQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Sets the padding color; only used with [`AspectMode::Pad`].
+    pub fn with_pad_color(mut self, bg_rgba: [u8; 4]) -> Self {
+        if let AspectMode::Pad { bg_rgba: color } = &mut self.aspect {
+            *color = bg_rgba;
+        }
+        self
+    }
+
+    /// Scaling plan for an `input`-sized image.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn plan(&self, input: Size) -> ScalePlan {
+        build_plan(input, self.target, self.aspect).with_quality(self.quality)
+    }
+}
+
+impl From<ScaleTarget> for ScaleSpec {
+    fn from(target: ScaleTarget) -> Self {
+        Self::new(target)
+    }
+}
+
+impl From<TokenPreset> for ScaleSpec {
+    fn from(preset: TokenPreset) -> Self {
+        Self::new(preset.to_target())
+    }
 }
 
 /// Compute a complete scaling plan from input parameters.
@@ -104,29 +370,17 @@ pub struct ScalePlan {
 /// for all supported target and aspect combinations.
 ///
 pub fn build_plan(input: Size, target: ScaleTarget, aspect: AspectMode) -> ScalePlan {
-    match (target, aspect) {
+    let (out, dst_roi) = match (target, aspect) {
         (ScaleTarget::MaxLongSide(max_side), AspectMode::Preserve) => {
             let (w, h) = fit_preserve(input, max_side);
-            ScalePlan {
-                input,
-                target,
-                aspect,
-                out: Size { w, h },
-                dst_roi: None,
-            }
+            (Size { w, h }, None)
         }
         (ScaleTarget::MaxLongSide(max_side), AspectMode::Distort) => {
             let out = Size {
                 w: max_side,
                 h: max_side,
             };
-            ScalePlan {
-                input,
-                target,
-                aspect,
-                out,
-                dst_roi: None,
-            }
+            (out, None)
         }
         (ScaleTarget::MaxLongSide(max_side), AspectMode::Pad { .. }) => {
             let out = Size {
@@ -136,44 +390,83 @@ pub fn build_plan(input: Size, target: ScaleTarget, aspect: AspectMode) -> Scale
             let (rw, rh) = fit_preserve(input, max_side);
             let x = (out.w - rw) / 2;
             let y = (out.h - rh) / 2;
-            ScalePlan {
-                input,
-                target,
-                aspect,
-                out,
-                dst_roi: Some((x, y, rw, rh)),
-            }
+            (out, Some((x, y, rw, rh)))
         }
-        (ScaleTarget::Exact(out), AspectMode::Distort) => ScalePlan {
-            input,
-            target,
-            aspect,
-            out,
-            dst_roi: None,
-        },
+        (ScaleTarget::Exact(out), AspectMode::Distort) => (out, None),
         (ScaleTarget::Exact(out), AspectMode::Preserve) => {
             let (rw, rh) = fit_within(input, out);
-            ScalePlan {
-                input,
-                target,
-                aspect,
-                out: Size { w: rw, h: rh },
-                dst_roi: None,
-            }
+            (Size { w: rw, h: rh }, None)
         }
         (ScaleTarget::Exact(out), AspectMode::Pad { .. }) => {
             let (rw, rh) = fit_within(input, out);
             let x = (out.w - rw) / 2;
             let y = (out.h - rh) / 2;
-            ScalePlan {
-                input,
-                target,
-                aspect,
-                out,
-                dst_roi: Some((x, y, rw, rh)),
+            (out, Some((x, y, rw, rh)))
+        }
+        (
+            ScaleTarget::PatchAligned {
+                patch,
+                min_pixels,
+                max_pixels,
+            },
+            AspectMode::Pad { .. },
+        ) => {
+            let out = patch_align(input, patch, min_pixels, max_pixels);
+            let (rw, rh) = fit_within(input, out);
+            let x = (out.w - rw) / 2;
+            let y = (out.h - rh) / 2;
+            (out, Some((x, y, rw, rh)))
+        }
+        (
+            ScaleTarget::PatchAligned {
+                patch,
+                min_pixels,
+                max_pixels,
+            },
+            AspectMode::Preserve | AspectMode::Distort,
+        ) => (patch_align(input, patch, min_pixels, max_pixels), None),
+    };
+    ScalePlan {
+        input,
+        target,
+        aspect,
+        out,
+        dst_roi,
+        quality: ResizeQuality::default(),
+    }
+}
+
+/// Round `input` to multiples of `patch` with an area within
+/// `min_pixels..=max_pixels`, following Qwen2-VL's `smart_resize`.
+///
+/// Each side is rounded to the nearest multiple; if the area is then out of
+/// budget both sides are rescaled by the same factor and rounded down (too
+/// large) or up (too small). Every side is at least one patch, so a budget
+/// below one patch still yields one patch.
+fn patch_align(input: Size, patch: u32, min_pixels: u32, max_pixels: u32) -> Size {
+    let f = patch.max(1) as f64;
+    let (w, h) = (input.w.max(1) as f64, input.h.max(1) as f64);
+    let align = |v: f64, round: fn(f64) -> f64| (round(v / f).max(1.0) * f) as u32;
+
+    let (mut out_w, mut out_h) = (align(w, f64::round), align(h, f64::round));
+    let area = out_w as f64 * out_h as f64;
+    if area > max_pixels as f64 {
+        let beta = (w * h / max_pixels.max(1) as f64).sqrt();
+        (out_w, out_h) = (align(w / beta, f64::floor), align(h / beta, f64::floor));
+        // Very thin inputs keep one patch on the short side; shorten the long one
+        let max_pixels = max_pixels as f64;
+        if out_w as f64 * out_h as f64 > max_pixels {
+            if out_w >= out_h {
+                out_w = align(max_pixels / out_h as f64, f64::floor);
+            } else {
+                out_h = align(max_pixels / out_w as f64, f64::floor);
             }
         }
+    } else if area < min_pixels as f64 {
+        let beta = (min_pixels as f64 / (w * h)).sqrt();
+        (out_w, out_h) = (align(w * beta, f64::ceil), align(h * beta, f64::ceil));
     }
+    Size { w: out_w, h: out_h }
 }
 
 /// Fit image within max_side constraint while preserving aspect ratio.
@@ -255,3 +548,128 @@ impl TokenPreset {
         }
     }
 }
+
+/// Scaling presets matching the image preprocessing of VLM families.
+///
+/// Each preset scales to whole patches or tiles of the model within its pixel
+/// budget, so no tokens are spent on partial patches and the model does not
+/// resize the image again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ModelPreset {
+    /// Qwen2.5-VL / Qwen3-VL: 28px patches (14px ViT patches merged 2×2),
+    /// 4 to 1280 patches per image
+    #[clap(name = "qwen")]
+    Qwen,
+    /// Claude: about 1.15 megapixels with the long side up to ~1568px,
+    /// aligned to 28px (1568 = 56 × 28)
+    #[clap(name = "claude")]
+    Claude,
+    /// OpenAI high detail: fitted within 2048px with the short side at most
+    /// 768px, then padded up to whole 512px tiles (at most 4×2)
+    #[clap(name = "openai")]
+    OpenAi,
+}
+
+impl ModelPreset {
+    /// Patch or tile edge in pixels.
+    pub fn patch(self) -> u32 {
+        match self {
+            ModelPreset::Qwen | ModelPreset::Claude => 28,
+            ModelPreset::OpenAi => 512,
+        }
+    }
+
+    /// Longest side the model accepts without resizing, if limited
+    /// separately from the pixel budget.
+    pub fn max_long_side(self) -> Option<u32> {
+        match self {
+            ModelPreset::Claude => Some(1568),
+            ModelPreset::Qwen | ModelPreset::OpenAi => None,
+        }
+    }
+
+    /// Convert preset to the corresponding ScaleTarget for plan computation.
+    ///
+    /// The long-side limit of [`ModelPreset::max_long_side`] depends on the
+    /// input's aspect ratio and is only applied by [`ModelPreset::plan`], as
+    /// is OpenAI's fit-then-pad tiling; for OpenAI the budget is the largest
+    /// canvas of whole tiles.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn to_target(self) -> ScaleTarget {
+        let patch = self.patch();
+        let (min_pixels, max_pixels) = match self {
+            ModelPreset::Qwen => (4 * 28 * 28, 1280 * 28 * 28),
+            ModelPreset::Claude => (28 * 28, 1_150_000),
+            ModelPreset::OpenAi => (512 * 512, 2048 * 1024),
+        };
+        ScaleTarget::PatchAligned {
+            patch,
+            min_pixels,
+            max_pixels,
+        }
+    }
+
+    /// Aspect handling the model expects.
+    pub fn aspect(self) -> AspectMode {
+        match self {
+            ModelPreset::Qwen | ModelPreset::Claude => AspectMode::Preserve,
+            // Rounding to 512px tiles would distort too much; pad instead
+            ModelPreset::OpenAi => AspectMode::Pad {
+                bg_rgba: [0, 0, 0, 255],
+            },
+        }
+    }
+
+    /// Scaling plan for an `input`-sized image.
+    ///
+    /// Like `build_plan(input, self.to_target(), self.aspect())`, with the
+    /// pixel budget lowered so the long side stays within
+    /// [`ModelPreset::max_long_side`].
+    ///
+    /// OpenAI instead follows its high-detail resizing: the content is
+    /// fitted within 2048×2048, its short side shrunk to 768px, and the
+    /// canvas rounded up to whole 512px tiles with only the remainder padded.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn plan(self, input: Size) -> ScalePlan {
+        if self == ModelPreset::OpenAi {
+            return self.tile_plan(input, 2048, 768);
+        }
+        let mut target = self.to_target();
+        if let (Some(limit), ScaleTarget::PatchAligned { max_pixels, .. }) =
+            (self.max_long_side(), &mut target)
+        {
+            let long = input.w.max(input.h).max(1) as f64;
+            let short = input.w.min(input.h).max(1) as f64;
+            let at_limit = limit as f64 * (limit as f64 * short / long);
+            *max_pixels = (*max_pixels).min(at_limit as u32);
+        }
+        build_plan(input, target, self.aspect())
+    }
+
+    /// Plan that fits `input` within `max_side`, shrinks its short side to
+    /// `short_side` (never upscaling) and pads it to whole tiles, centered.
+    fn tile_plan(self, input: Size, max_side: u32, short_side: u32) -> ScalePlan {
+        let (w, h) = (input.w.max(1) as f64, input.h.max(1) as f64);
+        let fit = (max_side as f64 / w.max(h)).min(1.0);
+        let shrink = (short_side as f64 / (w.min(h) * fit)).min(1.0);
+        let scale = fit * shrink;
+        let rw = ((w * scale).round() as u32).max(1);
+        let rh = ((h * scale).round() as u32).max(1);
+
+        let tile = self.patch();
+        let out = Size {
+            w: rw.div_ceil(tile) * tile,
+            h: rh.div_ceil(tile) * tile,
+        };
+        ScalePlan {
+            input,
+            target: self.to_target(),
+            aspect: self.aspect(),
+            out,
+            dst_roi: Some(((out.w - rw) / 2, (out.h - rh) / 2, rw, rh)),
+            quality: ResizeQuality::default(),
+        }
+    }
+}
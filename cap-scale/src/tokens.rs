@@ -0,0 +1,394 @@
+// SPDX-License-Identifier: MIT
+//! # Vision Token Estimation
+//!
+//! Estimates how many tokens a vision language model bills for the images this
+//! crate produces, so scaling presets can be compared by cost instead of by
+//! pixel count alone.
+//!
+//! ## Model Families
+//!
+//! Models turn images into tokens in one of a few ways, each implemented as a
+//! [`TokenModel`]:
+//!
+//! | Model | Rule | Constant |
+//! |-------|------|----------|
+//! | [`PatchTokens`] | One token per patch after aligning to the patch grid | [`QWEN`], [`DEEPSEEK_OCR`] |
+//! | [`AreaTokens`] | Pixel area divided by a fixed area per token | [`CLAUDE`] |
+//! | [`TileTokens`] | Fixed cost per tile plus a base cost | [`OPENAI_HIGH`] |
+//! | [`FixedTokens`] | Same cost for every image | [`OPENAI_LOW`] |
+//!
+//! Every model first applies its own resizing rules (pixel budgets, long-side
+//! limits), so sending an image larger than the model accepts does not raise
+//! the estimate; it only wastes bandwidth.
+//!
+//! ## Budgets
+//!
+//! A [`TokenBudget`] caps the tokens per frame or per minute of streaming.
+//! [`BudgetTracker`] turns it into an allowance for the next frame and
+//! [`fit_plan`] picks the largest candidate plan within that allowance, which
+//! is how adaptive scaling chooses an output size frame by frame.
+//!
+//! ## Accuracy
+//!
+//! The formulas follow each vendor's published preprocessing. Chat templates
+//! add a few tokens per image that are not counted; estimates are meant for
+//! budgeting, not billing.
+//!
+//! ## Future Optimizations
+//!
+//! TODO: Account for skipped unchanged frames in stream estimates.
+
+use std::time::Duration;
+
+use crate::gundam::{tile_rects, GundamCfg};
+use crate::presets::{build_plan, AspectMode, ScalePlan, ScaleTarget, Size};
+
+/// Long sides tried by adaptive scaling, largest first.
+pub const LONG_SIDE_LADDER: [u32; 10] = [2048, 1568, 1280, 1024, 896, 768, 640, 512, 384, 256];
+
+/// Converts images to a vision token count for one model family.
+pub trait TokenModel {
+    /// Short name of the model family, e.g. `"qwen"`.
+    fn name(&self) -> &str;
+
+    /// Tokens billed for one image of `size` pixels.
+    fn image_tokens(&self, size: Size) -> u64;
+
+    /// Tokens billed for the output of `plan`.
+    ///
+    /// **Time complexity**: O(1).
+    fn plan_tokens(&self, plan: &ScalePlan) -> u64 {
+        self.image_tokens(plan.out)
+    }
+
+    /// Tokens billed for a Gundam layout of an `input`-sized image, with each
+    /// tile and the global view sent as separate images.
+    ///
+    /// With [`TileSelection::ByContent`](crate::gundam::TileSelection) blank
+    /// tiles are dropped, so this is the most a frame can cost.
+    ///
+    /// **Time complexity**: O(tiles) to compute the tile grid.
+    fn gundam_tokens(&self, input: Size, cfg: &GundamCfg) -> u64 {
+        let tiles = tile_rects(input.w, input.h, cfg).len() as u64;
+        let tile = Size {
+            w: cfg.tile_side,
+            h: cfg.tile_side,
+        };
+        let global = Size {
+            w: cfg.global_side,
+            h: cfg.global_side,
+        };
+        tiles * self.image_tokens(tile) + self.image_tokens(global)
+    }
+}
+
+impl<T: TokenModel + ?Sized> TokenModel for &T {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn image_tokens(&self, size: Size) -> u64 {
+        (**self).image_tokens(size)
+    }
+}
+
+/// One token per `patch` × `patch` cell, after the model resizes the image to
+/// whole patches within `min_pixels..=max_pixels` (Qwen2-VL `smart_resize`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatchTokens {
+    /// Model family name
+    pub name: &'static str,
+    /// Edge of the pixel area that becomes one token
+    pub patch: u32,
+    /// Smallest image area the model resizes to
+    pub min_pixels: u32,
+    /// Largest image area the model resizes to
+    pub max_pixels: u32,
+    /// Tokens added per image, e.g. image start/end markers
+    pub overhead: u64,
+}
+
+impl TokenModel for PatchTokens {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn image_tokens(&self, size: Size) -> u64 {
+        let target = ScaleTarget::PatchAligned {
+            patch: self.patch,
+            min_pixels: self.min_pixels,
+            max_pixels: self.max_pixels,
+        };
+        let out = build_plan(size, target, AspectMode::Distort).out;
+        let patch = self.patch.max(1);
+        (out.w / patch) as u64 * (out.h / patch) as u64 + self.overhead
+    }
+}
+
+/// Pixel area divided by `pixels_per_token`, after the model shrinks the image
+/// to at most `max_long_side` and `max_pixels` (Claude).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AreaTokens {
+    /// Model family name
+    pub name: &'static str,
+    /// Pixel area billed as one token
+    pub pixels_per_token: u32,
+    /// Longest side the model keeps before downscaling
+    pub max_long_side: u32,
+    /// Largest area the model keeps before downscaling
+    pub max_pixels: u32,
+}
+
+impl TokenModel for AreaTokens {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn image_tokens(&self, size: Size) -> u64 {
+        let (w, h) = (size.w as f64, size.h as f64);
+        let scale = 1.0f64
+            .min(self.max_long_side as f64 / w.max(h).max(1.0))
+            .min((self.max_pixels as f64 / (w * h).max(1.0)).sqrt());
+        let area = (w * scale).round() * (h * scale).round();
+        (area / self.pixels_per_token.max(1) as f64).ceil() as u64
+    }
+}
+
+/// `per_tile` tokens per `tile` × `tile` tile plus `base`, after the model fits
+/// the image within `max_side` and shrinks its short side to `short_side`
+/// (OpenAI high detail).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileTokens {
+    /// Model family name
+    pub name: &'static str,
+    /// Tile edge in pixels
+    pub tile: u32,
+    /// Tokens per tile
+    pub per_tile: u64,
+    /// Tokens per image on top of the tiles
+    pub base: u64,
+    /// Square the image is first fitted into
+    pub max_side: u32,
+    /// Short side the image is then shrunk to
+    pub short_side: u32,
+}
+
+impl TokenModel for TileTokens {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn image_tokens(&self, size: Size) -> u64 {
+        let (w, h) = (size.w.max(1) as f64, size.h.max(1) as f64);
+        let fit = 1.0f64.min(self.max_side as f64 / w.max(h));
+        let (w, h) = (w * fit, h * fit);
+        let shrink = 1.0f64.min(self.short_side as f64 / w.min(h));
+        let tile = self.tile.max(1) as f64;
+        let tiles = ((w * shrink / tile).ceil() * (h * shrink / tile).ceil()) as u64;
+        tiles * self.per_tile + self.base
+    }
+}
+
+/// The same token count for every image (low-detail modes).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedTokens {
+    /// Model family name
+    pub name: &'static str,
+    /// Tokens per image
+    pub tokens: u64,
+}
+
+impl TokenModel for FixedTokens {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn image_tokens(&self, _size: Size) -> u64 {
+        self.tokens
+    }
+}
+
+/// Qwen2.5-VL / Qwen3-VL: 28px per token, 4 to 1280 tokens per image.
+pub const QWEN: PatchTokens = PatchTokens {
+    name: "qwen",
+    patch: 28,
+    min_pixels: 4 * 28 * 28,
+    max_pixels: 1280 * 28 * 28,
+    overhead: 2,
+};
+
+/// DeepSeek-OCR: 16px patches compressed 16×, so 64px per token (100 tokens
+/// per 640px Gundam tile, 256 for the 1024px global view).
+pub const DEEPSEEK_OCR: PatchTokens = PatchTokens {
+    name: "deepseek-ocr",
+    patch: 64,
+    min_pixels: 64 * 64,
+    max_pixels: u32::MAX,
+    overhead: 0,
+};
+
+/// Claude: width × height / 750, at most 1568px on the long side and about
+/// 1.15 megapixels.
+pub const CLAUDE: AreaTokens = AreaTokens {
+    name: "claude",
+    pixels_per_token: 750,
+    max_long_side: 1568,
+    max_pixels: 1_150_000,
+};
+
+/// OpenAI high detail: 170 tokens per 512px tile plus 85, after fitting into
+/// 2048px and shrinking the short side to 768px.
+pub const OPENAI_HIGH: TileTokens = TileTokens {
+    name: "openai",
+    tile: 512,
+    per_tile: 170,
+    base: 85,
+    max_side: 2048,
+    short_side: 768,
+};
+
+/// OpenAI low detail: 85 tokens per image.
+pub const OPENAI_LOW: FixedTokens = FixedTokens {
+    name: "openai-low",
+    tokens: 85,
+};
+
+/// Token cost of a stream of frames sent to a model at a fixed rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StreamEstimate {
+    /// Tokens billed per frame
+    pub tokens_per_frame: u64,
+    /// Frames sent per second
+    pub frames_per_second: f64,
+}
+
+impl StreamEstimate {
+    /// Estimate for sending every frame at `fps`.
+    pub fn new(tokens_per_frame: u64, fps: f64) -> Self {
+        Self {
+            tokens_per_frame,
+            frames_per_second: fps,
+        }
+    }
+
+    /// Tokens billed per minute of streaming.
+    pub fn tokens_per_minute(&self) -> f64 {
+        self.tokens_per_frame as f64 * self.frames_per_second * 60.0
+    }
+
+    /// Tokens billed for streaming for `duration`, counting whole frames only.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn tokens_for(&self, duration: Duration) -> u64 {
+        let frames = (duration.as_secs_f64() * self.frames_per_second).floor() as u64;
+        frames * self.tokens_per_frame
+    }
+}
+
+/// Limit on the tokens a stream of frames may cost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenBudget {
+    /// At most this many tokens per frame
+    PerFrame(u64),
+    /// At most `tokens` per minute of frames sent at `fps`
+    PerMinute { tokens: u64, fps: u32 },
+}
+
+/// Tokens left in a [`TokenBudget`] while frames are being sent.
+///
+/// A per-minute budget is spread over the frames of a one-minute window:
+/// each frame may spend what is left divided by the frames still to come, so
+/// cheap frames leave more for later ones. The window restarts after
+/// `fps * 60` frames. Frames that are never sent, such as skipped unchanged
+/// frames, do not count, which keeps the real rate at or below the budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetTracker {
+    budget: TokenBudget,
+    remaining: u64,
+    frames_left: u64,
+}
+
+impl BudgetTracker {
+    /// Tracker with the whole budget available.
+    pub fn new(budget: TokenBudget) -> Self {
+        let mut tracker = Self {
+            budget,
+            remaining: 0,
+            frames_left: 0,
+        };
+        tracker.restart();
+        tracker
+    }
+
+    /// Budget being tracked.
+    pub fn budget(&self) -> TokenBudget {
+        self.budget
+    }
+
+    /// Tokens the next frame may cost.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn allowance(&self) -> u64 {
+        match self.budget {
+            TokenBudget::PerFrame(tokens) => tokens,
+            TokenBudget::PerMinute { .. } => self.remaining / self.frames_left.max(1),
+        }
+    }
+
+    /// Records a frame that cost `tokens`.
+    ///
+    /// Spending more than the allowance is allowed (the cheapest plan may
+    /// still exceed it) and is taken from the frames after it.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn spend(&mut self, tokens: u64) {
+        if let TokenBudget::PerMinute { .. } = self.budget {
+            self.remaining = self.remaining.saturating_sub(tokens);
+            self.frames_left = self.frames_left.saturating_sub(1);
+            if self.frames_left == 0 {
+                self.restart();
+            }
+        }
+    }
+
+    fn restart(&mut self) {
+        if let TokenBudget::PerMinute { tokens, fps } = self.budget {
+            self.remaining = tokens;
+            self.frames_left = (fps.max(1) as u64) * 60;
+        }
+    }
+}
+
+/// Plan for the largest output among `candidates` whose token cost for
+/// `model` is within `allowance`, with that cost.
+///
+/// "Largest" is by output area; when no candidate fits, the cheapest one is
+/// returned so a frame is always produced. Returns `None` only when
+/// `candidates` is empty.
+///
+/// **Time complexity**: O(candidates).
+pub fn fit_plan(
+    model: &dyn TokenModel,
+    input: Size,
+    candidates: &[ScaleTarget],
+    aspect: AspectMode,
+    allowance: u64,
+) -> Option<(ScalePlan, u64)> {
+    let plans = candidates.iter().map(|&target| {
+        let plan = build_plan(input, target, aspect);
+        (plan, model.plan_tokens(&plan))
+    });
+    let area = |plan: &ScalePlan| plan.out.w as u64 * plan.out.h as u64;
+
+    let mut best: Option<(ScalePlan, u64)> = None;
+    let mut cheapest: Option<(ScalePlan, u64)> = None;
+    for (plan, tokens) in plans {
+        if tokens <= allowance && best.is_none_or(|(b, _)| area(&plan) > area(&b)) {
+            best = Some((plan, tokens));
+        }
+        if cheapest.is_none_or(|(_, c)| tokens < c) {
+            cheapest = Some((plan, tokens));
+        }
+    }
+    best.or(cheapest)
+}
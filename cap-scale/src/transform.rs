@@ -0,0 +1,209 @@
+// SPDX-License-Identifier: MIT
+//! # Frame Geometry Transforms
+//!
+//! Describes how the pixels of a processed frame relate to the pixels it was
+//! made from. Every processing step that changes geometry (cropping a capture
+//! region, scaling with a [`ScalePlan`], packing Gundam tiles into a mosaic)
+//! records one [`Transform`]; the list of transforms a frame went through lets
+//! consumers map model outputs back to screen pixels.
+//!
+//! ## Coordinate Conventions
+//!
+//! - All sizes and rectangles are in whole pixels, origin top-left
+//! - A transform maps its *input* image to its *output* image
+//! - Padding added by [`AspectMode::Pad`](crate::presets::AspectMode::Pad) is
+//!   part of the output but maps to no input pixel
+//! - Points are continuous [`Point`]s, so mapping a point to the other side
+//!   and back returns it up to floating-point rounding
+//!
+//! ## Mapping Back to the Screen
+//!
+//! [`Transform::map_to_input`] undoes one transform and [`map_chain_to_input`]
+//! undoes a whole chain, newest first. Given the transforms recorded for a
+//! frame, a point a model reports on the processed frame maps back to the
+//! pixels of the captured screen or window:
+//!
+//! ```rust
+//! use cap_scale::presets::{build_plan, AspectMode, Point, ScaleTarget, Size};
+//! use cap_scale::transform::{map_chain_to_input, Transform};
+//!
+//! let plan = build_plan(
+//!     Size { w: 1920, h: 1080 },
+//!     ScaleTarget::MaxLongSide(640),
+//!     AspectMode::Preserve,
+//! );
+//! let chain = [Transform::from_plan(&plan)];
+//! let screen = map_chain_to_input(&chain, Point { x: 320.0, y: 180.0 }).unwrap();
+//! assert_eq!(screen, Point { x: 960.0, y: 540.0 });
+//! ```
+//!
+//! ## Future Optimizations
+//!
+//! TODO: Add rotation/flip transforms if capture backends start reporting them.
+
+use crate::gundam::Rect;
+use crate::presets::{Point, ScalePlan, Size};
+
+/// One geometry change applied to a frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transform {
+    /// The output is the `rect` sub-rectangle of an `input`-sized image.
+    Crop { input: Size, rect: Rect },
+    /// The whole `input` image is resized into `content` within an
+    /// `output`-sized image; the rest of the output is padding.
+    Scale {
+        input: Size,
+        output: Size,
+        content: Rect,
+    },
+    /// The output is a mosaic of resized views of the `input` image, such as
+    /// Gundam tiles next to a global view, or several captured screens laid
+    /// out in one frame.
+    Mosaic {
+        input: Size,
+        output: Size,
+        views: Vec<MosaicView>,
+    },
+}
+
+/// One view placed in a [`Transform::Mosaic`] output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MosaicView {
+    /// What the view shows.
+    pub kind: ViewKind,
+    /// Region of the input image shown by the view.
+    pub source: Rect,
+    /// Where that region's pixels land in the output, excluding padding.
+    pub dest: Rect,
+}
+
+/// Role of a [`MosaicView`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewKind {
+    /// Gundam tile with its index in row-major grid order.
+    Tile(usize),
+    /// Downscaled view of the whole input.
+    Global,
+    /// Input with this index of a composite of several captures.
+    Input(usize),
+}
+
+impl Transform {
+    /// Scale transform performed by `plan`.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn from_plan(plan: &ScalePlan) -> Self {
+        let (x, y, w, h) = plan.dst_roi.unwrap_or((0, 0, plan.out.w, plan.out.h));
+        Transform::Scale {
+            input: plan.input,
+            output: plan.out,
+            content: Rect { x, y, w, h },
+        }
+    }
+
+    /// Size of the image this transform reads.
+    pub fn input_size(&self) -> Size {
+        match self {
+            Transform::Crop { input, .. }
+            | Transform::Scale { input, .. }
+            | Transform::Mosaic { input, .. } => *input,
+        }
+    }
+
+    /// Size of the image this transform produces.
+    pub fn output_size(&self) -> Size {
+        match self {
+            Transform::Crop { rect, .. } => Size {
+                w: rect.w,
+                h: rect.h,
+            },
+            Transform::Scale { output, .. } | Transform::Mosaic { output, .. } => *output,
+        }
+    }
+
+    /// Maps `p` from output pixels back to input pixels.
+    ///
+    /// For a mosaic the point is mapped through the view containing it.
+    /// Returns `None` if `p` lies in padding, between mosaic views or outside
+    /// the output.
+    ///
+    /// **Time complexity**: O(1), O(views) for a mosaic.
+    pub fn map_to_input(&self, p: Point) -> Option<Point> {
+        match self {
+            Transform::Crop { rect, .. } => map_rect(p, at_origin(*rect), *rect),
+            Transform::Scale { input, content, .. } => map_rect(p, *content, whole(*input)),
+            Transform::Mosaic { views, .. } => views
+                .iter()
+                .find_map(|view| map_rect(p, view.dest, view.source)),
+        }
+    }
+
+    /// Maps `p` from input pixels to output pixels; the inverse of
+    /// [`Transform::map_to_input`].
+    ///
+    /// For a mosaic the first view showing `p` is used, which is a tile
+    /// before the global view. Returns `None` if no output pixel shows `p`.
+    ///
+    /// **Time complexity**: O(1), O(views) for a mosaic.
+    pub fn map_to_output(&self, p: Point) -> Option<Point> {
+        match self {
+            Transform::Crop { rect, .. } => map_rect(p, *rect, at_origin(*rect)),
+            Transform::Scale { input, content, .. } => map_rect(p, whole(*input), *content),
+            Transform::Mosaic { views, .. } => views
+                .iter()
+                .find_map(|view| map_rect(p, view.source, view.dest)),
+        }
+    }
+}
+
+/// Maps `p` from the output of the last transform in `chain` back to the
+/// input of the first, e.g. from a processed frame to screen pixels.
+///
+/// An empty chain maps every point to itself. Returns `None` as soon as one
+/// transform has no input pixel for the point.
+///
+/// **Time complexity**: O(transforms), plus O(views) per mosaic.
+pub fn map_chain_to_input(chain: &[Transform], p: Point) -> Option<Point> {
+    chain
+        .iter()
+        .rev()
+        .try_fold(p, |p, transform| transform.map_to_input(p))
+}
+
+/// Maps `p` from the input of the first transform in `chain` to the output
+/// of the last; the inverse of [`map_chain_to_input`].
+///
+/// **Time complexity**: O(transforms), plus O(views) per mosaic.
+pub fn map_chain_to_output(chain: &[Transform], p: Point) -> Option<Point> {
+    chain
+        .iter()
+        .try_fold(p, |p, transform| transform.map_to_output(p))
+}
+
+/// Linearly maps `p` from rectangle `from` onto rectangle `to`, or `None` if
+/// `p` lies outside `from` (edges included).
+fn map_rect(p: Point, from: Rect, to: Rect) -> Option<Point> {
+    let inside = |v: f64, start: u32, len: u32| {
+        len > 0 && v >= start as f64 && v <= start as f64 + len as f64
+    };
+    if !inside(p.x, from.x, from.w) || !inside(p.y, from.y, from.h) {
+        return None;
+    }
+    Some(Point {
+        x: to.x as f64 + (p.x - from.x as f64) * to.w as f64 / from.w as f64,
+        y: to.y as f64 + (p.y - from.y as f64) * to.h as f64 / from.h as f64,
+    })
+}
+
+fn whole(size: Size) -> Rect {
+    Rect {
+        x: 0,
+        y: 0,
+        w: size.w,
+        h: size.h,
+    }
+}
+
+fn at_origin(rect: Rect) -> Rect {
+    Rect { x: 0, y: 0, ..rect }
+}
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT
+//! Tests for the Gundam grid selection strategies.
+
+use cap_scale::gundam::{choose_grid, grid_for, tile_rects, GridStrategy, GundamCfg};
+
+fn cfg(grid: GridStrategy) -> GundamCfg {
+    GundamCfg {
+        grid,
+        ..GundamCfg::default()
+    }
+}
+
+#[test]
+fn heuristic_matches_choose_grid() {
+    for (w, h) in [
+        (1920, 1080),
+        (800, 600),
+        (600, 800),
+        (3840, 2160),
+        (5000, 300),
+    ] {
+        assert_eq!(
+            grid_for(w, h, &cfg(GridStrategy::Heuristic)),
+            choose_grid(w, h)
+        );
+    }
+    assert_eq!(GundamCfg::default().grid, GridStrategy::Heuristic);
+}
+
+#[test]
+fn fixed_grid_is_used_as_given() {
+    let fixed = cfg(GridStrategy::Fixed { cols: 4, rows: 1 });
+    assert_eq!(grid_for(3000, 2000, &fixed), (4, 1));
+    let rects = tile_rects(3000, 2000, &fixed);
+    assert_eq!(rects.len(), 4);
+    assert!(rects.iter().all(|r| r.w == 750 && r.h == 2000));
+
+    // Never empty, never more cells than pixels
+    assert_eq!(
+        grid_for(3000, 2000, &cfg(GridStrategy::Fixed { cols: 0, rows: 0 })),
+        (1, 1)
+    );
+    assert_eq!(grid_for(5, 5, &fixed), (3, 1));
+    assert!(tile_rects(5, 5, &fixed).iter().all(|r| r.x + r.w <= 5));
+
+    // Cells past max_tiles are dropped
+    let big = cfg(GridStrategy::Fixed { cols: 4, rows: 4 });
+    assert_eq!(tile_rects(3000, 2000, &big).len(), 9);
+}
+
+#[test]
+fn aspect_ratio_follows_input_shape() {
+    let aspect = cfg(GridStrategy::AspectRatio);
+    assert_eq!(grid_for(3000, 1000, &aspect), (3, 1));
+    assert_eq!(grid_for(1000, 3000, &aspect), (1, 3));
+    assert_eq!(grid_for(1240, 1754, &aspect), (2, 3));
+
+    // Square inputs tie between 2x2 and 3x3; only a large one fills 3x3
+    assert_eq!(grid_for(600, 600, &aspect), (2, 2));
+    assert_eq!(grid_for(2000, 2000, &aspect), (3, 3));
+
+    // Candidates respect the tile limits
+    let few = GundamCfg {
+        max_tiles: 4,
+        ..aspect
+    };
+    assert_eq!(grid_for(3000, 1000, &few), (3, 1));
+    assert_eq!(grid_for(9000, 1000, &few), (4, 1));
+}
+
+#[test]
+fn density_sizes_cells_and_clamps_count() {
+    let native = cfg(GridStrategy::Density { scale: 1.0 });
+    assert_eq!(grid_for(1920, 1080, &native), (3, 2));
+
+    let (cols, rows) = grid_for(1920, 1080, &cfg(GridStrategy::Density { scale: 4.0 }));
+    assert!(cols * rows <= 9 && cols * rows >= 6, "{cols}x{rows}");
+    assert!(cols >= rows);
+
+    // Too few cells grow along the longer cell side up to min_tiles
+    let coarse = cfg(GridStrategy::Density { scale: 0.1 });
+    assert_eq!(grid_for(1920, 1080, &coarse), (2, 1));
+    assert_eq!(grid_for(1080, 1920, &coarse), (1, 2));
+}
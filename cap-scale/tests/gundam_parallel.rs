@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT
+//! Tests that parallel Gundam packing matches the sequential path byte for
+//! byte.
+
+use cap_scale::cpu::Staging;
+use cap_scale::gundam::{
+    gundam_pack_cpu, gundam_pack_cpu_parallel, tile_rects, GundamCfg, GundamOutputs, GundamPool,
+};
+use cap_scale::presets::ResizeQuality;
+use fast_image_resize::Resizer;
+
+/// Noisy BGRA image with `pad` bytes of row padding, so every tile differs.
+fn noise(w: u32, h: u32, pad: usize) -> (Vec<u8>, usize) {
+    let stride = w as usize * 4 + pad;
+    let mut state = 0x2545_f491_u32;
+    let data = (0..stride * h as usize)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+    (data, stride)
+}
+
+/// Tile and global buffers for `cfg`, filled with `fill` so stale bytes show.
+fn buffers(cfg: &GundamCfg, tiles: usize, fill: u8) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let tile = (cfg.tile_side * cfg.tile_side * 4) as usize;
+    let global = (cfg.global_side * cfg.global_side * 4) as usize;
+    (vec![vec![fill; tile]; tiles], vec![fill; global])
+}
+
+fn pack(cfg: GundamCfg, w: u32, h: u32, pool: Option<&mut GundamPool>) -> (Vec<Vec<u8>>, Vec<u8>) {
+    let (src, stride) = noise(w, h, 64);
+    let (mut tiles, mut global) = buffers(&cfg, tile_rects(w, h, &cfg).len(), 7);
+    let out = GundamOutputs {
+        tiles: tiles.iter_mut().map(|t| t.as_mut_slice()).collect(),
+        global: &mut global,
+    };
+    match pool {
+        Some(pool) => gundam_pack_cpu_parallel(pool, &src, w, h, stride, cfg, out),
+        None => gundam_pack_cpu(
+            &mut Resizer::new(),
+            &src,
+            w,
+            h,
+            stride,
+            cfg,
+            &mut Staging::with_capacity(0),
+            out,
+        ),
+    }
+    .unwrap();
+    (tiles, global)
+}
+
+#[test]
+fn parallel_output_is_byte_identical() {
+    let cfg = GundamCfg {
+        tile_side: 128,
+        global_side: 256,
+        ..GundamCfg::default()
+    };
+    let (w, h) = (2100, 300);
+    let expected = pack(cfg, w, h, None);
+    assert_eq!(expected.0.len(), 3);
+
+    for threads in [1, 3, 16] {
+        let mut pool = GundamPool::new(threads);
+        assert_eq!(pool.threads(), threads);
+        let got = pack(cfg, w, h, Some(&mut pool));
+        assert!(got == expected, "{threads} threads differ");
+    }
+    // Staging buffers reused from a larger frame
+    let mut pool = GundamPool::new(2);
+    pack(cfg, 2100, 600, Some(&mut pool));
+    assert!(pack(cfg, w, h, Some(&mut pool)) == expected);
+}
+
+#[test]
+fn parallel_matches_with_ocr_quality() {
+    let cfg = GundamCfg {
+        tile_side: 96,
+        global_side: 160,
+        overlap_frac: 0.1,
+        quality: ResizeQuality::ocr(),
+        ..GundamCfg::default()
+    };
+    let expected = pack(cfg, 700, 1100, None);
+    let got = pack(cfg, 700, 1100, Some(&mut GundamPool::new(3)));
+    assert!(got == expected);
+}
+
+#[test]
+fn pool_has_at_least_one_worker() {
+    assert_eq!(GundamPool::new(0).threads(), 1);
+    assert!(GundamPool::with_available_parallelism().threads() >= 1);
+}
+
+#[test]
+fn parallel_uses_its_own_threads() {
+    let cfg = GundamCfg {
+        tile_side: 128,
+        global_side: 256,
+        ..GundamCfg::default()
+    };
+    let expected = pack(cfg, 900, 700, None);
+    let mut pool = GundamPool::new(4);
+    // A single-threaded caller pool must not limit or block the Gundam pool
+    let outer = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let got = outer.install(|| pack(cfg, 900, 700, Some(&mut pool)));
+    assert!(got == expected);
+}
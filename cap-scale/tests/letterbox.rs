@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: MIT
+//! Tests for scaling with a [`ScaleSpec`]: letterboxing, pad colors, filters
+//! and resize quality.
+
+use cap_scale::cpu::scale_bgra_cpu;
+use cap_scale::presets::{
+    AspectMode, GammaMode, ResizeFilter, ResizeQuality, ScalePlan, ScaleSpec, ScaleTarget, Size,
+    TokenPreset, UnsharpMask,
+};
+use clap::ValueEnum;
+use fast_image_resize::Resizer;
+
+/// Solid BGRA image.
+fn solid(size: Size, bgra: [u8; 4]) -> Vec<u8> {
+    bgra.repeat((size.w * size.h) as usize)
+}
+
+/// Scales a tightly packed `src` with `plan`.
+fn scale(src: &[u8], plan: &ScalePlan) -> Vec<u8> {
+    let mut out = vec![0; (plan.out.w * plan.out.h * 4) as usize];
+    scale_bgra_cpu(
+        &mut Resizer::new(),
+        src,
+        plan.input,
+        None,
+        plan,
+        &mut out,
+        None,
+    )
+    .unwrap();
+    out
+}
+
+fn pixel(buf: &[u8], width: u32, x: u32, y: u32) -> &[u8] {
+    let i = ((y * width + x) * 4) as usize;
+    &buf[i..i + 4]
+}
+
+#[test]
+fn letterbox_pads_with_rgba_color() {
+    let input = Size { w: 400, h: 200 };
+    let src = solid(input, [255, 255, 255, 255]);
+    let spec = ScaleSpec::letterbox(100, [255, 0, 0, 255]);
+    let plan = spec.plan(input);
+    assert_eq!(plan.out, Size { w: 100, h: 100 });
+    assert_eq!(plan.dst_roi, Some((0, 25, 100, 50)));
+
+    let out = scale(&src, &plan);
+
+    // Red bars in BGRA byte order above and below white content
+    assert_eq!(pixel(&out, 100, 50, 10), [0, 0, 255, 255]);
+    assert_eq!(pixel(&out, 100, 50, 90), [0, 0, 255, 255]);
+    assert_eq!(pixel(&out, 100, 50, 50), [255, 255, 255, 255]);
+}
+
+#[test]
+fn pad_color_only_applies_to_padding_modes() {
+    let spec = ScaleSpec::from(TokenPreset::P4_Long640).with_pad_color([1, 2, 3, 4]);
+    let plan = spec.plan(Size { w: 1920, h: 1080 });
+    assert_eq!(plan.out, Size { w: 640, h: 360 });
+    assert_eq!(plan.dst_roi, None);
+
+    let spec = ScaleSpec::letterbox(64, [0; 4]).with_pad_color([9, 9, 9, 9]);
+    assert!(matches!(
+        spec.aspect,
+        AspectMode::Pad {
+            bg_rgba: [9, 9, 9, 9]
+        }
+    ));
+}
+
+#[test]
+fn every_filter_keeps_flat_color() {
+    let input = Size { w: 64, h: 48 };
+    let src = solid(input, [10, 20, 30, 255]);
+    for &filter in ResizeFilter::value_variants() {
+        for quality in [ResizeQuality::default(), ResizeQuality::ocr()] {
+            let plan = ScaleSpec::new(ScaleTarget::MaxLongSide(32))
+                .with_quality(ResizeQuality { filter, ..quality })
+                .plan(input);
+            let out = scale(&src, &plan);
+            assert_eq!(
+                pixel(&out, plan.out.w, 5, 5),
+                [10, 20, 30, 255],
+                "{filter:?} {:?}",
+                quality.gamma
+            );
+        }
+    }
+}
+
+/// Black image with a one pixel wide white column at every even `x`.
+fn stripes(size: Size) -> Vec<u8> {
+    (0..size.w * size.h)
+        .flat_map(|i| {
+            let v = if (i % size.w).is_multiple_of(2) {
+                255
+            } else {
+                0
+            };
+            [v, v, v, 255]
+        })
+        .collect()
+}
+
+#[test]
+fn linear_light_keeps_fine_stripes_bright() {
+    let input = Size { w: 64, h: 8 };
+    let src = stripes(input);
+    let spec = ScaleSpec::new(ScaleTarget::Exact(Size { w: 32, h: 8 }))
+        .with_aspect(AspectMode::Distort)
+        .with_filter(ResizeFilter::Box);
+    let srgb = scale(&src, &spec.plan(input));
+    let linear = scale(
+        &src,
+        &spec
+            .with_quality(ResizeQuality {
+                gamma: GammaMode::Linear,
+                ..spec.quality
+            })
+            .plan(input),
+    );
+
+    // Averaging 0 and 255: half the sRGB value, but half the light is ~188
+    let (s, l) = (pixel(&srgb, 32, 10, 4)[0], pixel(&linear, 32, 10, 4)[0]);
+    assert!((126..=129).contains(&s), "{s}");
+    assert!((186..=189).contains(&l), "{l}");
+}
+
+#[test]
+fn unsharp_mask_only_applies_when_downscaling() {
+    // Black left half, white right half
+    let size = Size { w: 16, h: 4 };
+    let src: Vec<u8> = (0..size.w * size.h)
+        .flat_map(|i| {
+            if i % size.w < 8 {
+                [0, 0, 0, 255]
+            } else {
+                [255; 4]
+            }
+        })
+        .collect();
+    let sharpen = ResizeQuality {
+        sharpen: Some(UnsharpMask {
+            amount: 1.0,
+            threshold: 0,
+        }),
+        ..ResizeQuality::default()
+    };
+
+    // Same size: nothing to restore, the edge is left alone
+    let same = ScaleSpec::new(ScaleTarget::MaxLongSide(16)).with_quality(sharpen);
+    assert!(!same.plan(size).downscales());
+    assert_eq!(scale(&src, &same.plan(size)), src);
+
+    // Downscaled: the soft edge gets steeper than without the mask
+    let spec = ScaleSpec::new(ScaleTarget::Exact(Size { w: 8, h: 4 }))
+        .with_aspect(AspectMode::Distort)
+        .with_filter(ResizeFilter::Bilinear);
+    let soft = scale(&src, &spec.plan(size));
+    let sharp = scale(
+        &src,
+        &spec
+            .with_quality(sharpen)
+            .with_filter(ResizeFilter::Bilinear)
+            .plan(size),
+    );
+    let contrast = |out: &[u8]| pixel(out, 8, 4, 1)[0] as i32 - pixel(out, 8, 3, 1)[0] as i32;
+    assert!(contrast(&sharp) > contrast(&soft));
+    // Alpha is never sharpened
+    assert!(sharp.chunks_exact(4).all(|px| px[3] == 255));
+}
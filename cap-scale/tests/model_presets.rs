@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: MIT
+//! Tests for patch-aligned scaling and the model presets.
+
+use cap_scale::presets::{build_plan, AspectMode, ModelPreset, ScaleTarget, Size};
+use proptest::prelude::*;
+
+fn size() -> impl Strategy<Value = Size> {
+    (1u32..8192, 1u32..8192).prop_map(|(w, h)| Size { w, h })
+}
+
+fn preset() -> impl Strategy<Value = ModelPreset> {
+    prop_oneof![
+        Just(ModelPreset::Qwen),
+        Just(ModelPreset::Claude),
+        Just(ModelPreset::OpenAi),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn presets_produce_whole_patches_within_budget(input in size(), preset in preset()) {
+        let plan = preset.plan(input);
+        let patch = preset.patch();
+        prop_assert_eq!(plan.out.w % patch, 0);
+        prop_assert_eq!(plan.out.h % patch, 0);
+        prop_assert!(plan.out.w >= patch && plan.out.h >= patch);
+
+        let ScaleTarget::PatchAligned { max_pixels, .. } = preset.to_target() else {
+            unreachable!("model presets are patch aligned");
+        };
+        let area = plan.out.w as u64 * plan.out.h as u64;
+        // Budgets below one patch still produce one patch
+        prop_assert!(area <= (max_pixels as u64).max(patch as u64 * patch as u64));
+    }
+
+    #[test]
+    fn padded_content_keeps_aspect_ratio(input in size()) {
+        let plan = ModelPreset::OpenAi.plan(input);
+        let (_, _, w, h) = plan.dst_roi.expect("OpenAI preset pads");
+        prop_assert!(w <= plan.out.w && h <= plan.out.h);
+        let ratio_in = input.w as f64 / input.h as f64;
+        let ratio_out = w as f64 / h as f64;
+        // Rounding to whole pixels changes the ratio by less than a pixel per side
+        prop_assert!((ratio_out - ratio_in).abs() <= ratio_in * (1.0 / w as f64 + 1.0 / h as f64) + 1e-9);
+    }
+
+    #[test]
+    fn openai_pads_less_than_a_tile(input in size()) {
+        let plan = ModelPreset::OpenAi.plan(input);
+        let (_, _, w, h) = plan.dst_roi.expect("OpenAI preset pads");
+        prop_assert!(w.max(h) <= 2048 && w.min(h) <= 768);
+        prop_assert!(plan.out.w - w < 512 && plan.out.h - h < 512);
+    }
+}
+
+#[test]
+fn qwen_matches_smart_resize() {
+    // Values from Qwen2-VL's smart_resize with max_pixels = 1280 * 28 * 28
+    let plan = ModelPreset::Qwen.plan(Size { w: 1920, h: 1080 });
+    assert_eq!(plan.out, Size { w: 1316, h: 728 });
+
+    // Small inputs are scaled up to the minimum of 4 patches
+    let plan = ModelPreset::Qwen.plan(Size { w: 20, h: 20 });
+    assert_eq!(plan.out, Size { w: 56, h: 56 });
+}
+
+#[test]
+fn claude_limits_long_side() {
+    let plan = ModelPreset::Claude.plan(Size { w: 1920, h: 1080 });
+    assert_eq!(plan.out, Size { w: 1428, h: 784 });
+
+    // A wide strip is limited by its long side, not by the pixel budget
+    let plan = ModelPreset::Claude.plan(Size { w: 6000, h: 1000 });
+    assert!(plan.out.w <= 1568, "got {:?}", plan.out);
+}
+
+#[test]
+fn openai_fits_then_pads_to_whole_tiles() {
+    // 2048 fit leaves 1920x1080 as is; the short side shrinks to 768
+    for (w, h) in [(1920, 1080), (2560, 1440), (3840, 2160)] {
+        let plan = ModelPreset::OpenAi.plan(Size { w, h });
+        assert_eq!(plan.out, Size { w: 1536, h: 1024 }, "{w}x{h}");
+        assert_eq!(plan.dst_roi, Some((85, 128, 1365, 768)), "{w}x{h}");
+    }
+
+    // Short side already below 768: only padded up to whole tiles
+    let plan = ModelPreset::OpenAi.plan(Size { w: 1000, h: 500 });
+    assert_eq!(plan.out, Size { w: 1024, h: 512 });
+    assert_eq!(plan.dst_roi, Some((12, 6, 1000, 500)));
+
+    // A tall strip is limited by the 2048 fit
+    let plan = ModelPreset::OpenAi.plan(Size { w: 600, h: 4096 });
+    assert_eq!(plan.out, Size { w: 512, h: 2048 });
+    assert_eq!(plan.dst_roi, Some((106, 0, 300, 2048)));
+}
+
+#[test]
+fn patch_aligned_pad_does_not_upscale() {
+    let plan = build_plan(
+        Size { w: 300, h: 100 },
+        ScaleTarget::PatchAligned {
+            patch: 512,
+            min_pixels: 512 * 512,
+            max_pixels: 2048 * 768,
+        },
+        AspectMode::Pad { bg_rgba: [0; 4] },
+    );
+    assert_eq!(plan.out, Size { w: 512, h: 512 });
+    assert_eq!(plan.dst_roi, Some((106, 206, 300, 100)));
+}
@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: MIT
+//! Tests for content scoring and content-aware Gundam tile selection.
+
+use cap_scale::content::score_region;
+use cap_scale::cpu::Staging;
+use cap_scale::gundam::{
+    gundam_pack_cpu, select_tiles, tile_rects, GundamCfg, GundamOutputs, Rect, TileSelection,
+};
+use fast_image_resize::Resizer;
+
+const W: u32 = 3000;
+const H: u32 = 2000;
+
+/// White BGRA frame with black "text" stripes inside `regions`.
+fn page(regions: &[Rect]) -> Vec<u8> {
+    let mut buf = vec![255u8; (W * H * 4) as usize];
+    for r in regions {
+        for y in (r.y..r.y + r.h).filter(|y| y % 12 < 3) {
+            for x in (r.x..r.x + r.w).filter(|x| x % 8 < 5) {
+                let i = ((y * W + x) * 4) as usize;
+                buf[i..i + 3].fill(0);
+            }
+        }
+    }
+    buf
+}
+
+fn whole() -> Rect {
+    Rect {
+        x: 0,
+        y: 0,
+        w: W,
+        h: H,
+    }
+}
+
+fn cfg(selection: TileSelection, max_tiles: u32) -> GundamCfg {
+    GundamCfg {
+        tile_side: 64,
+        global_side: 64,
+        max_tiles,
+        selection,
+        ..GundamCfg::default()
+    }
+}
+
+#[test]
+fn blank_scores_zero_and_text_scores_high() {
+    let blank = score_region(&page(&[]), W as usize * 4, whole());
+    assert_eq!(blank.edge_density, 0.0);
+    assert_eq!(blank.entropy, 0.0);
+    assert_eq!(blank.information(), 0.0);
+
+    let text = score_region(&page(&[whole()]), W as usize * 4, whole());
+    assert!(text.edge_density > 0.2, "{text:?}");
+    assert!(text.entropy > 0.5, "{text:?}");
+    assert!(text.information() > 0.1);
+}
+
+#[test]
+fn content_selection_keeps_dense_tiles_past_row_major_cut() {
+    // 3×2 grid of 1000×1000 cells; text only in the bottom-right cell
+    let text = Rect {
+        x: 2100,
+        y: 1100,
+        w: 800,
+        h: 800,
+    };
+    let src = page(&[text]);
+    let stride = W as usize * 4;
+
+    let row_major = select_tiles(&src, W, H, stride, &cfg(TileSelection::RowMajor, 4));
+    let rects: Vec<Rect> = row_major.iter().map(|t| t.rect).collect();
+    assert_eq!(rects, tile_rects(W, H, &cfg(TileSelection::RowMajor, 4)));
+    assert!(rects.iter().all(|r| r.x + r.w <= 2000 || r.y + r.h <= 1000));
+
+    let chosen = select_tiles(&src, W, H, stride, &cfg(TileSelection::SKIP_BLANK, 4));
+    assert_eq!(chosen.len(), 1, "blank cells are dropped");
+    assert_eq!(
+        chosen[0].rect,
+        Rect {
+            x: 2000,
+            y: 1000,
+            w: 1000,
+            h: 1000
+        }
+    );
+    assert!(chosen[0].score.information() > 0.05);
+}
+
+#[test]
+fn content_selection_ranks_and_keeps_reading_order() {
+    // Text in every cell but denser towards the end
+    let cells = tile_rects(W, H, &cfg(TileSelection::RowMajor, 9));
+    assert_eq!(cells.len(), 6);
+    let regions: Vec<Rect> = cells
+        .iter()
+        .enumerate()
+        .map(|(i, c)| Rect {
+            h: 100 * (i as u32 + 1),
+            ..*c
+        })
+        .collect();
+    let src = page(&regions);
+
+    let chosen = select_tiles(
+        &src,
+        W,
+        H,
+        W as usize * 4,
+        &cfg(TileSelection::SKIP_BLANK, 3),
+    );
+    let rects: Vec<Rect> = chosen.iter().map(|t| t.rect).collect();
+    assert_eq!(rects, cells[3..].to_vec());
+    assert!(chosen
+        .windows(2)
+        .all(|w| w[0].score.information() < w[1].score.information()));
+}
+
+#[test]
+fn unused_tile_buffers_are_padded() {
+    let src = page(&[Rect {
+        x: 0,
+        y: 0,
+        w: 900,
+        h: 900,
+    }]);
+    let cfg = cfg(TileSelection::SKIP_BLANK, 9);
+    let mut tiles = vec![vec![7u8; 64 * 64 * 4]; 6];
+    let mut global = vec![0u8; 64 * 64 * 4];
+    let chosen = gundam_pack_cpu(
+        &mut Resizer::new(),
+        &src,
+        W,
+        H,
+        W as usize * 4,
+        cfg,
+        &mut Staging::with_capacity(0),
+        GundamOutputs {
+            tiles: tiles.iter_mut().map(|t| t.as_mut_slice()).collect(),
+            global: &mut global,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(chosen.len(), 1);
+    assert!(tiles[1..].iter().flatten().all(|&b| b == 255));
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT
+//! Tests for vision token estimates against the vendors' published examples.
+
+use std::time::Duration;
+
+use cap_scale::gundam::GundamCfg;
+use cap_scale::presets::{build_plan, AspectMode, ScaleTarget, Size, TokenPreset};
+use cap_scale::tokens::{
+    fit_plan, BudgetTracker, StreamEstimate, TokenBudget, TokenModel, CLAUDE, DEEPSEEK_OCR,
+    LONG_SIDE_LADDER, OPENAI_HIGH, OPENAI_LOW, QWEN,
+};
+
+fn size(w: u32, h: u32) -> Size {
+    Size { w, h }
+}
+
+#[test]
+fn openai_tiles_match_documented_examples() {
+    // 1024 square -> 768 square -> 4 tiles
+    assert_eq!(OPENAI_HIGH.image_tokens(size(1024, 1024)), 765);
+    // 2048x4096 -> 1024x2048 -> 768x1536 -> 6 tiles
+    assert_eq!(OPENAI_HIGH.image_tokens(size(2048, 4096)), 1105);
+    assert_eq!(OPENAI_LOW.image_tokens(size(4096, 4096)), 85);
+}
+
+#[test]
+fn claude_counts_area_after_downscaling() {
+    // 1000x1000 is within limits: 1_000_000 / 750
+    assert_eq!(CLAUDE.image_tokens(size(1000, 1000)), 1334);
+    // Larger screens cost the same as the ~1.15 MP they are shrunk to
+    let full_hd = CLAUDE.image_tokens(size(1920, 1080));
+    assert_eq!(full_hd, CLAUDE.image_tokens(size(3840, 2160)));
+    assert!(full_hd <= 1_150_000 / 750 + 1);
+}
+
+#[test]
+fn qwen_counts_aligned_patches() {
+    // 1316x728 after smart_resize: 47 x 26 patches + 2 markers
+    assert_eq!(QWEN.image_tokens(size(1920, 1080)), 47 * 26 + 2);
+    // Scaling first saves tokens: 640x360 -> 644x364 = 23 x 13 patches
+    let plan = build_plan(
+        size(1920, 1080),
+        TokenPreset::P4_Long640.to_target(),
+        AspectMode::Preserve,
+    );
+    assert_eq!(QWEN.plan_tokens(&plan), 23 * 13 + 2);
+}
+
+#[test]
+fn deepseek_gundam_layout() {
+    // 1920x1080 uses a 2x2 grid: 4 tiles x 100 + 256 for the global view
+    let cfg = GundamCfg::default();
+    assert_eq!(DEEPSEEK_OCR.gundam_tokens(size(1920, 1080), &cfg), 656);
+}
+
+#[test]
+fn stream_estimates_scale_with_time() {
+    let stream = StreamEstimate::new(100, 2.0);
+    assert_eq!(stream.tokens_per_minute(), 12_000.0);
+    assert_eq!(stream.tokens_for(Duration::from_millis(2_600)), 500);
+}
+
+fn ladder() -> Vec<ScaleTarget> {
+    LONG_SIDE_LADDER.map(ScaleTarget::MaxLongSide).to_vec()
+}
+
+#[test]
+fn fit_plan_picks_largest_plan_within_allowance() {
+    let input = size(1920, 1080);
+    let (plan, tokens) = fit_plan(&QWEN, input, &ladder(), AspectMode::Preserve, 400).unwrap();
+    assert!(tokens <= 400);
+    // The next rung up would cost more than the allowance
+    let bigger = LONG_SIDE_LADDER
+        .iter()
+        .rev()
+        .find(|&&side| side > plan.out.w)
+        .unwrap();
+    let next = build_plan(
+        input,
+        ScaleTarget::MaxLongSide(*bigger),
+        AspectMode::Preserve,
+    );
+    assert!(QWEN.plan_tokens(&next) > 400);
+
+    // Nothing fits: fall back to the cheapest candidate
+    let (plan, _) = fit_plan(&QWEN, input, &ladder(), AspectMode::Preserve, 1).unwrap();
+    assert_eq!(plan.out.w, 256);
+    assert!(fit_plan(&QWEN, input, &[], AspectMode::Preserve, 1000).is_none());
+}
+
+#[test]
+fn per_minute_budget_carries_savings_forward() {
+    let mut tracker = BudgetTracker::new(TokenBudget::PerMinute {
+        tokens: 1200,
+        fps: 1,
+    });
+    // 1200 tokens over 60 frames
+    assert_eq!(tracker.allowance(), 20);
+    tracker.spend(0);
+    assert_eq!(tracker.allowance(), 1200 / 59);
+    // Overspending is taken from later frames
+    tracker.spend(1200);
+    assert_eq!(tracker.allowance(), 0);
+
+    // The window restarts after a minute of frames
+    for _ in 0..58 {
+        tracker.spend(0);
+    }
+    assert_eq!(tracker.allowance(), 20);
+}
+
+#[test]
+fn per_frame_budget_is_constant() {
+    let mut tracker = BudgetTracker::new(TokenBudget::PerFrame(500));
+    tracker.spend(10_000);
+    assert_eq!(tracker.allowance(), 500);
+}
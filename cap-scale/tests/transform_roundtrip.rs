@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MIT
+//! Property tests for mapping points between processed frames and the input.
+
+use cap_scale::gundam::{map_input_to_view, map_view_to_input, tile_rects, GundamCfg, Rect};
+use cap_scale::presets::{build_plan, AspectMode, Point, ScalePlan, ScaleTarget, Size};
+use cap_scale::transform::{map_chain_to_input, map_chain_to_output, Transform, ViewKind};
+use proptest::prelude::*;
+
+/// Round trips may differ from the original point by floating-point rounding.
+const EPSILON: f64 = 1e-6;
+
+fn close(a: Point, b: Point) -> bool {
+    (a.x - b.x).abs() < EPSILON && (a.y - b.y).abs() < EPSILON
+}
+
+fn size() -> impl Strategy<Value = Size> {
+    (1u32..4096, 1u32..4096).prop_map(|(w, h)| Size { w, h })
+}
+
+fn aspect() -> impl Strategy<Value = AspectMode> {
+    prop_oneof![
+        Just(AspectMode::Preserve),
+        Just(AspectMode::Distort),
+        Just(AspectMode::Pad {
+            bg_rgba: [0, 0, 0, 255]
+        }),
+    ]
+}
+
+fn plan() -> impl Strategy<Value = ScalePlan> {
+    let target = prop_oneof![
+        (16u32..2048).prop_map(ScaleTarget::MaxLongSide),
+        size().prop_map(ScaleTarget::Exact),
+        (1u32..600, 1u32..1 << 22, 1u32..1 << 22).prop_map(|(patch, a, b)| {
+            ScaleTarget::PatchAligned {
+                patch,
+                min_pixels: a.min(b),
+                max_pixels: a.max(b),
+            }
+        }),
+    ];
+    (size(), target, aspect()).prop_map(|(input, target, aspect)| build_plan(input, target, aspect))
+}
+
+/// Point at fractions `(fx, fy)` of the way across `rect`.
+fn point_in(rect: Rect, (fx, fy): (f64, f64)) -> Point {
+    Point {
+        x: rect.x as f64 + fx * rect.w as f64,
+        y: rect.y as f64 + fy * rect.h as f64,
+    }
+}
+
+fn whole(size: Size) -> Rect {
+    Rect {
+        x: 0,
+        y: 0,
+        w: size.w,
+        h: size.h,
+    }
+}
+
+fn content(plan: &ScalePlan) -> Rect {
+    let (x, y, w, h) = plan.dst_roi.unwrap_or((0, 0, plan.out.w, plan.out.h));
+    Rect { x, y, w, h }
+}
+
+fn frac() -> impl Strategy<Value = (f64, f64)> {
+    (0.0..=1.0f64, 0.0..=1.0f64)
+}
+
+proptest! {
+    #[test]
+    fn scale_plan_round_trips_output_points(plan in plan(), f in frac()) {
+        let p = point_in(content(&plan), f);
+        let input = plan.map_to_input(p).expect("content maps to the input");
+        prop_assert!(input.x >= 0.0 && input.x <= plan.input.w as f64 + EPSILON);
+        prop_assert!(input.y >= 0.0 && input.y <= plan.input.h as f64 + EPSILON);
+        prop_assert!(close(plan.map_to_output(input).unwrap(), p));
+    }
+
+    #[test]
+    fn scale_plan_round_trips_input_points(plan in plan(), f in frac()) {
+        let p = point_in(whole(plan.input), f);
+        let output = plan.map_to_output(p).expect("input maps to the output");
+        prop_assert!(close(plan.map_to_input(output).unwrap(), p));
+    }
+
+    #[test]
+    fn padding_maps_to_no_input(input in size(), side in 16u32..2048, f in 0.0..1.0f64) {
+        let plan = build_plan(
+            input,
+            ScaleTarget::Exact(Size { w: side, h: side }),
+            AspectMode::Pad { bg_rgba: [0; 4] },
+        );
+        let roi = content(&plan);
+        // Points in the left and top padding bands, when there are any
+        let middle = point_in(roi, (0.5, 0.5));
+        if roi.x > 0 {
+            let p = Point { x: roi.x as f64 * f, ..middle };
+            prop_assert!(plan.map_to_input(p).is_none());
+        }
+        if roi.y > 0 {
+            let p = Point { y: roi.y as f64 * f, ..middle };
+            prop_assert!(plan.map_to_input(p).is_none());
+        }
+    }
+
+    #[test]
+    fn gundam_views_round_trip(input in size(), f in frac(), pick in any::<prop::sample::Index>()) {
+        let cfg = GundamCfg::default();
+        let rects = tile_rects(input.w, input.h, &cfg);
+        let mut views: Vec<ViewKind> = (0..rects.len()).map(ViewKind::Tile).collect();
+        views.push(ViewKind::Global);
+        let view = views[pick.index(views.len())];
+
+        // A point of the input shown by the chosen view
+        let source = match view {
+            ViewKind::Tile(i) => rects[i],
+            ViewKind::Global => whole(input),
+            ViewKind::Input(_) => unreachable!("not a Gundam view"),
+        };
+        let p = point_in(source, f);
+        let in_view = map_input_to_view(input, &cfg, view, p).expect("view shows the point");
+        let side = match view {
+            ViewKind::Tile(_) => cfg.tile_side,
+            ViewKind::Global => cfg.global_side,
+            ViewKind::Input(_) => unreachable!("not a Gundam view"),
+        } as f64;
+        prop_assert!(in_view.x <= side + EPSILON && in_view.y <= side + EPSILON);
+        prop_assert!(close(map_view_to_input(input, &cfg, view, in_view).unwrap(), p));
+    }
+
+    #[test]
+    fn chains_round_trip(screen in size(), crop in frac(), plan_target in 16u32..2048, f in frac()) {
+        // Crop the right/bottom part of the screen, then scale it into a padded square
+        let rect = Rect {
+            x: (crop.0 * (screen.w - 1) as f64) as u32,
+            y: (crop.1 * (screen.h - 1) as f64) as u32,
+            w: 0,
+            h: 0,
+        };
+        let rect = Rect { w: screen.w - rect.x, h: screen.h - rect.y, ..rect };
+        let plan = build_plan(
+            Size { w: rect.w, h: rect.h },
+            ScaleTarget::MaxLongSide(plan_target),
+            AspectMode::Pad { bg_rgba: [0; 4] },
+        );
+        let chain = [
+            Transform::Crop { input: screen, rect },
+            Transform::from_plan(&plan),
+        ];
+
+        let p = point_in(content(&plan), f);
+        let on_screen = map_chain_to_input(&chain, p).expect("content maps to the screen");
+        prop_assert!(on_screen.x >= rect.x as f64 - EPSILON);
+        prop_assert!(on_screen.y >= rect.y as f64 - EPSILON);
+        prop_assert!(close(map_chain_to_output(&chain, on_screen).unwrap(), p));
+    }
+}
+
+#[test]
+fn pad_offsets_are_undone() {
+    // 1920x1080 into a 1024 square: content 1024x576 at y = 224
+    let plan = build_plan(
+        Size { w: 1920, h: 1080 },
+        ScaleTarget::Exact(Size { w: 1024, h: 1024 }),
+        AspectMode::Pad { bg_rgba: [0; 4] },
+    );
+    assert_eq!(plan.dst_roi, Some((0, 224, 1024, 576)));
+    assert_eq!(
+        plan.map_to_input(Point { x: 512.0, y: 512.0 }),
+        Some(Point { x: 960.0, y: 540.0 })
+    );
+    assert_eq!(plan.map_to_input(Point { x: 512.0, y: 100.0 }), None);
+}
+
+#[test]
+fn empty_chain_is_identity() {
+    let p = Point { x: 3.5, y: 7.25 };
+    assert_eq!(map_chain_to_input(&[], p), Some(p));
+    assert_eq!(map_chain_to_output(&[], p), Some(p));
+}
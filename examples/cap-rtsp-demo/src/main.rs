@@ -4,11 +4,13 @@
 //! Run: cargo run --bin cap-rtsp-demo -- --help
 //! Then: vlc rtsp://127.0.0.1:8554/cap
 //!
-//! This demo generates a moving color gradient and streams it via RTSP.
+//! This demo streams a GStreamer test pattern (SMPTE bars, bouncing ball or
+//! snow, with a running-time overlay) via RTSP.
 //! In a real app, you'd replace the synthetic frames with cap::scrap frames.
 
 use anyhow::Result;
-use cap_rtsp::{frame_from_bgra, start_server, BgraFrame, RtspConfig};
+use cap_rtsp::test_pattern::{TestPattern, TestPatternConfig, TestPatternSource};
+use cap_rtsp::{start_server, RtspConfig};
 use clap::Parser;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -25,6 +27,9 @@ struct Args {
     port: u16,
     #[arg(long, default_value = "/cap")]
     mount: String,
+    /// Test pattern: smpte, ball or snow
+    #[arg(long, default_value = "ball")]
+    pattern: TestPattern,
 }
 
 fn main() -> Result<()> {
@@ -54,35 +59,24 @@ fn main() -> Result<()> {
     );
     println!("Press Ctrl+C to stop");
 
-    // Generate and stream synthetic frames
+    // Generate and stream synthetic frames; the live test source paces them to --fps
+    let source = TestPatternSource::start(TestPatternConfig {
+        width: args.width,
+        height: args.height,
+        framerate: args.fps,
+        pattern: args.pattern,
+        ..Default::default()
+    })?;
     let start = Instant::now();
-    let mut frame_idx = 0u64;
 
-    loop {
-        let elapsed = start.elapsed();
-        let should_exit = elapsed.as_secs() > 300; // Run for 5 minutes max
-
-        if should_exit {
-            break;
-        }
-
-        // Generate a moving gradient frame
-        let frame = generate_gradient_frame(args.width, args.height, frame_idx, args.fps);
+    // Run for 5 minutes max
+    while start.elapsed().as_secs() <= 300 {
+        let frame = source.next_frame()?;
 
         // Send to RTSP (non-blocking)
         if let Err(e) = publisher.send(frame) {
             eprintln!("Failed to send frame: {}", e);
             thread::sleep(Duration::from_millis(10));
-            continue;
-        }
-
-        frame_idx += 1;
-
-        // Sleep to maintain framerate
-        let target_duration = Duration::from_nanos(1_000_000_000 / args.fps as u64);
-        let frame_time = start.elapsed() - elapsed;
-        if frame_time < target_duration {
-            thread::sleep(target_duration - frame_time);
         }
     }
 
@@ -92,30 +86,3 @@ fn main() -> Result<()> {
 
     Ok(())
 }
-
-/// Generate a synthetic BGRA frame with a moving color gradient.
-/// Real apps would use cap::scrap::get_next_frame() instead.
-fn generate_gradient_frame(width: u32, height: u32, frame_idx: u64, fps: u32) -> BgraFrame {
-    let mut data = vec![0u8; (width * height * 4) as usize];
-
-    // Create a moving gradient based on frame index
-    let time = frame_idx as f32 / fps as f32;
-    let wave_speed = 2.0;
-
-    for y in 0..height {
-        for x in 0..width {
-            let idx = ((y * width + x) * 4) as usize;
-
-            // Moving wave pattern
-            let wave = ((x as f32 * 0.01 + y as f32 * 0.01 + time * wave_speed).sin() + 1.0) * 0.5;
-
-            // BGRA format: Blue, Green, Red, Alpha
-            data[idx] = (wave * 255.0) as u8; // Blue
-            data[idx + 1] = ((1.0 - wave) * 255.0) as u8; // Green
-            data[idx + 2] = (wave * 0.5 * 255.0) as u8; // Red
-            data[idx + 3] = 255; // Alpha
-        }
-    }
-
-    frame_from_bgra(data, width, height, fps, frame_idx)
-}
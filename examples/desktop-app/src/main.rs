@@ -1,6 +1,7 @@
 use async_channel::{Receiver, Sender, unbounded};
 use cap_scale::presets::TokenPreset;
 use eframe::egui;
+use hybrid_screen_capture::capture::window_select::WindowSelector;
 use hybrid_screen_capture::config::config::CaptureConfig;
 #[cfg(feature = "session")]
 use hybrid_screen_capture::session::{CaptureSession, CaptureSource};
@@ -17,6 +18,7 @@ struct ScreenCaptureApp {
 
     // UI state
     preset_index: usize, // 0 = None, 1.. = presets
+    window_title: String,
     rtsp_enabled: bool,
     rtsp_port: u16,
 
@@ -37,6 +39,7 @@ impl ScreenCaptureApp {
             status_rx: Some(status_rx),
 
             preset_index: 0,
+            window_title: String::new(),
             rtsp_enabled: false,
             rtsp_port: 8554,
 
@@ -82,6 +85,12 @@ impl eframe::App for ScreenCaptureApp {
             });
 
             ui.checkbox(&mut self.config.window, "Capture specific window");
+            if self.config.window {
+                ui.horizontal(|ui| {
+                    ui.label("Window title (regex):");
+                    ui.text_edit_singleline(&mut self.window_title);
+                });
+            }
 
             // Gundam mode toggle
             ui.checkbox(&mut self.config.gundam_mode, "Gundam mode (tiling)");
@@ -155,6 +164,19 @@ impl eframe::App for ScreenCaptureApp {
                     }
                 } else {
                     // Start a new session (or fallback capture)
+                    // Pick the window up front instead of prompting on stdin
+                    self.config.window_selector = None;
+                    if self.config.window && !self.window_title.is_empty() {
+                        match WindowSelector::new().with_title(&self.window_title) {
+                            Ok(selector) => self.config.window_selector = Some(selector),
+                            Err(e) => {
+                                self.status = "Error";
+                                eprintln!("Window selection error: {}", e);
+                                return;
+                            }
+                        }
+                    }
+
                     // Validate config before starting
                     if let Err(e) = self.config.validate() {
                         self.status = "Error";
@@ -0,0 +1,579 @@
+// # Display and Window Enumeration
+//
+// This module answers "what can be captured?" without starting a capture, so
+// tools can pick a monitor or window programmatically and hand its geometry
+// to `--region` or its title to `--window-title`.
+//
+// ## Backends
+//
+// | Platform | Displays | Windows |
+// |----------|----------|---------|
+// | Linux X11 | RandR 1.5 monitors (root window without RandR) | EWMH `_NET_CLIENT_LIST` (mapped root children without a WM) |
+// | Windows/macOS | `scrap::Display::all()` | `scrap::Window::all()` |
+// | Linux Wayland | Through XWayland when `$DISPLAY` is set | Through XWayland when `$DISPLAY` is set |
+//
+// Wayland compositors do not let clients enumerate outputs or windows of
+// other clients; the portal dialog chooses instead. Under XWayland the lists
+// only cover X11 clients.
+//
+// ## Scale Factor
+//
+// X11 has no per-monitor scale; the factor comes from the `Xft.dpi` resource
+// (`Xft.dpi / 96`) that desktop environments set for HiDPI, and applies to
+// every display and window. scrap does not report scaling, so it is 1.0 there.
+
+use crate::error::CaptureError;
+use crate::processing::Rect;
+use serde_json::{Value, json};
+use std::fmt;
+
+/// A monitor that can be captured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayInfo {
+    /// Index of the display in enumeration order
+    pub id: u32,
+    /// Output name (`DP-1`, `HDMI-A-2`, ...) or a generated `Display N`
+    pub name: String,
+    /// Position and size in desktop pixels, relative to the top-left corner
+    /// of the monitor layout when a monitor sits at negative coordinates
+    pub geometry: Rect,
+    /// Ratio of physical to logical pixels (1.0 without scaling)
+    pub scale_factor: f64,
+    /// Whether this is the primary display
+    pub primary: bool,
+}
+
+impl DisplayInfo {
+    /// JSON object with the display's fields, as printed by `cap list displays --json`.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "geometry": rect_json(self.geometry),
+            "scale_factor": self.scale_factor,
+            "primary": self.primary,
+        })
+    }
+}
+
+impl fmt::Display for DisplayInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:?} {}x{}+{}+{} scale={}",
+            self.id,
+            self.name,
+            self.geometry.w,
+            self.geometry.h,
+            self.geometry.x,
+            self.geometry.y,
+            self.scale_factor
+        )?;
+        if self.primary {
+            f.write_str(" primary")?;
+        }
+        Ok(())
+    }
+}
+
+/// A top-level window that can be captured.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowInfo {
+    /// Backend window id: the X11 window id, or the index in the scrap window list
+    pub id: u64,
+    /// Window title (empty if the window has none)
+    pub title: String,
+    /// `WM_CLASS` instance name, if known
+    pub instance: Option<String>,
+    /// `WM_CLASS` class name, if known
+    pub class: Option<String>,
+    /// Id of the process owning the window, if known
+    pub pid: Option<u32>,
+    /// Visible part of the window in screen coordinates
+    pub geometry: Rect,
+    /// Scale factor of the desktop the window is shown on
+    pub scale_factor: f64,
+    /// Whether the window has input focus (`_NET_ACTIVE_WINDOW`)
+    pub focused: bool,
+}
+
+impl WindowInfo {
+    /// JSON object with the window's fields, as printed by `cap list windows --json`.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "title": self.title,
+            "instance": self.instance,
+            "class": self.class,
+            "pid": self.pid,
+            "geometry": rect_json(self.geometry),
+            "scale_factor": self.scale_factor,
+            "focused": self.focused,
+        })
+    }
+}
+
+impl fmt::Display for WindowInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "0x{:x} {:?} {}x{}",
+            self.id, self.title, self.geometry.w, self.geometry.h
+        )?;
+        if let Some(class) = &self.class {
+            write!(f, " class={}", class)?;
+        }
+        if let Some(pid) = self.pid {
+            write!(f, " pid={}", pid)?;
+        }
+        if self.focused {
+            f.write_str(" focused")?;
+        }
+        Ok(())
+    }
+}
+
+fn rect_json(rect: Rect) -> Value {
+    json!({ "x": rect.x, "y": rect.y, "w": rect.w, "h": rect.h })
+}
+
+/// Lists the displays of the current desktop.
+///
+/// Uses `$DISPLAY` on Linux and scrap on Windows/macOS.
+///
+/// # Errors
+/// Returns a capture-init error if the display server cannot be queried or
+/// the platform has no enumeration backend.
+pub fn enumerate_displays() -> Result<Vec<DisplayInfo>, CaptureError> {
+    #[cfg(all(target_os = "linux", feature = "x11rb"))]
+    return enumerate_x11_displays(&x11_display()?);
+
+    #[cfg(all(
+        feature = "screen-capture",
+        any(target_os = "windows", target_os = "macos")
+    ))]
+    return enumerate_scrap_displays();
+
+    #[allow(unreachable_code)]
+    Err(CaptureError::capture_init("enumerate", UNSUPPORTED))
+}
+
+/// Lists the top-level windows of the current desktop.
+///
+/// Uses `$DISPLAY` on Linux and scrap on Windows/macOS.
+///
+/// # Errors
+/// Returns a capture-init error if the display server cannot be queried or
+/// the platform has no enumeration backend.
+pub fn enumerate_windows() -> Result<Vec<WindowInfo>, CaptureError> {
+    #[cfg(all(target_os = "linux", feature = "x11rb"))]
+    return enumerate_x11_windows(&x11_display()?);
+
+    #[cfg(all(
+        feature = "screen-capture",
+        any(target_os = "windows", target_os = "macos")
+    ))]
+    return Ok(scrap_windows()?.into_iter().map(|(info, _)| info).collect());
+
+    #[allow(unreachable_code)]
+    Err(CaptureError::capture_init("enumerate", UNSUPPORTED))
+}
+
+/// Error message for platforms without an enumeration backend.
+const UNSUPPORTED: &str = "no display enumeration backend on this platform (needs X11 or scrap)";
+
+/// `$DISPLAY`, which must be set: Wayland-only sessions cannot be enumerated.
+#[cfg(all(target_os = "linux", feature = "x11rb"))]
+fn x11_display() -> Result<String, CaptureError> {
+    std::env::var("DISPLAY").map_err(|_| {
+        CaptureError::capture_init(
+            "x11",
+            "$DISPLAY is not set; Wayland compositors do not expose displays or windows to clients",
+        )
+    })
+}
+
+/// Parses the `Xft.dpi` entry of an X resource database string.
+#[cfg(any(test, all(target_os = "linux", feature = "x11rb")))]
+fn xft_dpi(resources: &str) -> Option<f64> {
+    resources.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Xft.dpi")
+            .then(|| value.trim().parse::<f64>().ok())
+            .flatten()
+            .filter(|dpi| *dpi > 0.0)
+    })
+}
+
+/// Top-left corner of a monitor layout, or `(0, 0)` if no monitor lies left
+/// of or above the root window's origin.
+///
+/// Monitor positions are reported relative to this corner, so a layout with
+/// negative RandR coordinates keeps its shape instead of collapsing onto 0.
+#[cfg(any(test, all(target_os = "linux", feature = "x11rb")))]
+fn layout_origin(positions: impl Iterator<Item = (i16, i16)>) -> (i32, i32) {
+    positions.fold((0, 0), |(min_x, min_y), (x, y)| {
+        (min_x.min(x as i32), min_y.min(y as i32))
+    })
+}
+
+/// Desktop scale factor of an X11 screen, from `Xft.dpi` on the root window.
+#[cfg(all(target_os = "linux", feature = "x11rb"))]
+fn x11_scale_factor(conn: &impl x11rb::connection::Connection, root: u32) -> f64 {
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt};
+
+    conn.get_property(
+        false,
+        root,
+        AtomEnum::RESOURCE_MANAGER,
+        AtomEnum::STRING,
+        0,
+        u32::MAX,
+    )
+    .ok()
+    .and_then(|cookie| cookie.reply().ok())
+    .and_then(|reply| xft_dpi(&String::from_utf8_lossy(&reply.value)))
+    .map_or(1.0, |dpi| dpi / 96.0)
+}
+
+/// Opens X11 `display` and returns the connection with its default screen.
+#[cfg(all(target_os = "linux", feature = "x11rb"))]
+fn x11_connect(
+    display: &str,
+) -> Result<(x11rb::rust_connection::RustConnection, usize), CaptureError> {
+    use x11rb::connection::Connection;
+
+    let (conn, screen_num) = x11rb::connect(Some(display)).map_err(|e| {
+        CaptureError::capture_init("x11", format!("cannot open display {}: {}", display, e))
+    })?;
+    if conn.setup().roots.get(screen_num).is_none() {
+        return Err(CaptureError::capture_init(
+            "x11",
+            format!("display {} has no screen {}", display, screen_num),
+        ));
+    }
+    Ok((conn, screen_num))
+}
+
+/// Lists the monitors of X11 `display` through RandR.
+///
+/// Without RandR 1.5 (e.g. bare Xvfb or Xnest), reports the whole screen as
+/// one primary display named after the X11 display.
+///
+/// Time complexity: O(n) round trips for n monitors.
+#[cfg(all(target_os = "linux", feature = "x11rb"))]
+pub fn enumerate_x11_displays(display: &str) -> Result<Vec<DisplayInfo>, CaptureError> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::randr::ConnectionExt as _;
+    use x11rb::protocol::xproto::ConnectionExt as _;
+
+    let (conn, screen_num) = x11_connect(display)?;
+    let screen = &conn.setup().roots[screen_num];
+    let scale_factor = x11_scale_factor(&conn, screen.root);
+
+    // Requests fail up front when the server lacks RandR
+    let has_monitors = conn
+        .randr_query_version(1, 5)
+        .ok()
+        .and_then(|cookie| cookie.reply().ok())
+        .is_some_and(|version| (version.major_version, version.minor_version) >= (1, 5));
+    let monitors = if has_monitors {
+        conn.randr_get_monitors(screen.root, true)
+            .map_err(|e| CaptureError::capture_init("x11", e.to_string()))?
+            .reply()
+            .map_err(|e| CaptureError::capture_init("x11", e.to_string()))?
+            .monitors
+    } else {
+        Vec::new()
+    };
+
+    if monitors.is_empty() {
+        return Ok(vec![DisplayInfo {
+            id: 0,
+            name: display.to_string(),
+            geometry: Rect {
+                x: 0,
+                y: 0,
+                w: screen.width_in_pixels as u32,
+                h: screen.height_in_pixels as u32,
+            },
+            scale_factor,
+            primary: true,
+        }]);
+    }
+
+    let any_primary = monitors.iter().any(|monitor| monitor.primary);
+    let origin = layout_origin(monitors.iter().map(|monitor| (monitor.x, monitor.y)));
+    let mut displays = Vec::with_capacity(monitors.len());
+    for (index, monitor) in monitors.into_iter().enumerate() {
+        let name = conn
+            .get_atom_name(monitor.name)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| String::from_utf8_lossy(&reply.name).into_owned())
+            .unwrap_or_else(|| format!("Display {}", index + 1));
+        displays.push(DisplayInfo {
+            id: index as u32,
+            name,
+            geometry: Rect {
+                x: (monitor.x as i32 - origin.0) as u32,
+                y: (monitor.y as i32 - origin.1) as u32,
+                w: monitor.width as u32,
+                h: monitor.height as u32,
+            },
+            scale_factor,
+            // Without an explicit primary, X11 treats the first monitor as primary
+            primary: monitor.primary || (!any_primary && index == 0),
+        });
+    }
+    Ok(displays)
+}
+
+/// Lists the managed top-level windows of X11 `display`.
+///
+/// Uses the window manager's `_NET_CLIENT_LIST`; without an EWMH window
+/// manager, falls back to the mapped children of the root window. Titles come
+/// from `_NET_WM_NAME` (UTF-8) or `WM_NAME`, and geometry is clipped to the
+/// screen, so the rectangle can be passed to x11grab as a region.
+///
+/// Time complexity: O(n) round trips for n windows.
+#[cfg(all(target_os = "linux", feature = "x11rb"))]
+pub fn enumerate_x11_windows(display: &str) -> Result<Vec<WindowInfo>, CaptureError> {
+    use x11rb::connection::Connection;
+    use x11rb::properties::WmClass;
+    use x11rb::protocol::xproto::{AtomEnum, ConnectionExt, MapState};
+
+    x11rb::atom_manager! {
+        Atoms: AtomsCookie {
+            _NET_ACTIVE_WINDOW,
+            _NET_CLIENT_LIST,
+            _NET_WM_NAME,
+            _NET_WM_PID,
+            UTF8_STRING,
+        }
+    }
+
+    let x11_err = |e: &dyn fmt::Display| CaptureError::capture_init("x11", e.to_string());
+
+    let (conn, screen_num) = x11_connect(display)?;
+    let screen = &conn.setup().roots[screen_num];
+    let root = screen.root;
+    let (screen_w, screen_h) = (
+        screen.width_in_pixels as i32,
+        screen.height_in_pixels as i32,
+    );
+    let scale_factor = x11_scale_factor(&conn, root);
+    let atoms = Atoms::new(&conn)
+        .map_err(|e| x11_err(&e))?
+        .reply()
+        .map_err(|e| x11_err(&e))?;
+
+    // Window lists on the root; missing properties (no window manager) read as empty
+    let root_window_property = |property: u32| -> Vec<u32> {
+        conn.get_property(false, root, property, AtomEnum::WINDOW, 0, u32::MAX)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| Some(reply.value32()?.collect()))
+            .unwrap_or_default()
+    };
+    let active = root_window_property(atoms._NET_ACTIVE_WINDOW)
+        .first()
+        .copied();
+    let mut ids = root_window_property(atoms._NET_CLIENT_LIST);
+    if ids.is_empty() {
+        let tree = conn
+            .query_tree(root)
+            .map_err(|e| x11_err(&e))?
+            .reply()
+            .map_err(|e| x11_err(&e))?;
+        for child in tree.children {
+            let attrs = conn
+                .get_window_attributes(child)
+                .map_err(|e| x11_err(&e))?
+                .reply();
+            if attrs.is_ok_and(|attrs| attrs.map_state == MapState::VIEWABLE) {
+                ids.push(child);
+            }
+        }
+    }
+    let focused = active.or_else(|| {
+        // Without a window manager, fall back to the X input focus
+        conn.get_input_focus()
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.focus)
+    });
+
+    let mut windows = Vec::with_capacity(ids.len());
+    for id in ids {
+        // Windows may disappear while we query them; skip those
+        let Ok(geometry) = conn.get_geometry(id).map_err(|e| x11_err(&e))?.reply() else {
+            continue;
+        };
+        let Ok(origin) = conn
+            .translate_coordinates(id, root, 0, 0)
+            .map_err(|e| x11_err(&e))?
+            .reply()
+        else {
+            continue;
+        };
+
+        let string_property = |property: u32, type_: u32| -> Option<String> {
+            let reply = conn
+                .get_property(false, id, property, type_, 0, u32::MAX)
+                .ok()?
+                .reply()
+                .ok()?;
+            (reply.format == 8 && !reply.value.is_empty())
+                .then(|| String::from_utf8_lossy(&reply.value).into_owned())
+        };
+        let title = string_property(atoms._NET_WM_NAME, atoms.UTF8_STRING)
+            .or_else(|| string_property(AtomEnum::WM_NAME.into(), AtomEnum::STRING.into()))
+            .unwrap_or_default();
+        let wm_class = WmClass::get(&conn, id)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok().flatten());
+        let name =
+            |bytes: &[u8]| (!bytes.is_empty()).then(|| String::from_utf8_lossy(bytes).into_owned());
+        let pid = conn
+            .get_property(false, id, atoms._NET_WM_PID, AtomEnum::CARDINAL, 0, 1)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32()?.next());
+
+        // Clip to the screen: x11grab cannot capture off-screen pixels
+        let x0 = (origin.dst_x as i32).clamp(0, screen_w);
+        let y0 = (origin.dst_y as i32).clamp(0, screen_h);
+        let x1 = (origin.dst_x as i32 + geometry.width as i32).clamp(0, screen_w);
+        let y1 = (origin.dst_y as i32 + geometry.height as i32).clamp(0, screen_h);
+
+        windows.push(WindowInfo {
+            id: id as u64,
+            title,
+            instance: wm_class.as_ref().and_then(|class| name(class.instance())),
+            class: wm_class.as_ref().and_then(|class| name(class.class())),
+            pid,
+            geometry: Rect {
+                x: x0 as u32,
+                y: y0 as u32,
+                w: (x1 - x0) as u32,
+                h: (y1 - y0) as u32,
+            },
+            scale_factor,
+            focused: focused == Some(id),
+        });
+    }
+    Ok(windows)
+}
+
+/// Lists displays through scrap, which reports sizes but no positions or names.
+///
+/// scrap lists the primary display first.
+#[cfg(all(
+    feature = "screen-capture",
+    any(target_os = "windows", target_os = "macos")
+))]
+fn enumerate_scrap_displays() -> Result<Vec<DisplayInfo>, CaptureError> {
+    let displays = scrap::Display::all()
+        .map_err(|e| CaptureError::capture_init("scrap", format!("cannot list displays: {}", e)))?;
+    Ok(displays
+        .iter()
+        .enumerate()
+        .map(|(index, display)| DisplayInfo {
+            id: index as u32,
+            name: format!("Display {}", index + 1),
+            geometry: Rect {
+                x: 0,
+                y: 0,
+                w: display.width() as u32,
+                h: display.height() as u32,
+            },
+            scale_factor: 1.0,
+            primary: index == 0,
+        })
+        .collect())
+}
+
+/// Lists windows through scrap together with the handles needed to capture them.
+///
+/// scrap only reports titles and sizes.
+#[cfg(all(
+    feature = "screen-capture",
+    any(target_os = "windows", target_os = "macos")
+))]
+pub(crate) fn scrap_windows() -> Result<Vec<(WindowInfo, scrap::Window)>, CaptureError> {
+    let windows = scrap::Window::all()
+        .map_err(|e| CaptureError::capture_init("scrap", format!("cannot list windows: {}", e)))?;
+    Ok(windows
+        .into_iter()
+        .enumerate()
+        .map(|(index, window)| {
+            let info = WindowInfo {
+                id: index as u64,
+                title: window.title(),
+                instance: None,
+                class: None,
+                pid: None,
+                geometry: Rect {
+                    x: 0,
+                    y: 0,
+                    w: window.width() as u32,
+                    h: window.height() as u32,
+                },
+                scale_factor: 1.0,
+                focused: false,
+            };
+            (info, window)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_xft_dpi() {
+        assert_eq!(xft_dpi("Xft.antialias:\t1\nXft.dpi:\t144\n"), Some(144.0));
+        assert_eq!(xft_dpi("Xft.dpi: 96.5"), Some(96.5));
+        assert_eq!(xft_dpi("Xcursor.size:\t24\n"), None);
+        assert_eq!(xft_dpi("Xft.dpi:\tlarge\n"), None);
+        assert_eq!(xft_dpi("Xft.dpi:\t0\n"), None);
+    }
+
+    #[test]
+    fn layout_origin_shifts_only_negative_layouts() {
+        assert_eq!(layout_origin([(0, 0), (1920, 0)].into_iter()), (0, 0));
+        assert_eq!(layout_origin([(100, 50)].into_iter()), (0, 0));
+        assert_eq!(
+            layout_origin([(0, 0), (-1280, -200), (1920, 300)].into_iter()),
+            (-1280, -200)
+        );
+    }
+
+    #[test]
+    fn json_uses_stable_field_names() {
+        let display = DisplayInfo {
+            id: 1,
+            name: "DP-1".to_string(),
+            geometry: Rect {
+                x: 1920,
+                y: 0,
+                w: 2560,
+                h: 1440,
+            },
+            scale_factor: 1.5,
+            primary: false,
+        };
+        assert_eq!(
+            display.to_json(),
+            json!({
+                "id": 1,
+                "name": "DP-1",
+                "geometry": { "x": 1920, "y": 0, "w": 2560, "h": 1440 },
+                "scale_factor": 1.5,
+                "primary": false,
+            })
+        );
+    }
+}
@@ -2,7 +2,20 @@
 //
 // This module contains platform-specific screen capture implementations.
 
+pub mod enumerate;
+#[cfg(all(
+    feature = "rtsp-streaming",
+    feature = "wayland-pipe",
+    target_os = "linux"
+))]
+pub(crate) mod pipewire_cursor;
+#[cfg(feature = "wayland-pipe")]
+pub mod portal_tokens;
+#[cfg(feature = "screen-capture")]
 pub mod scrap;
 pub mod session_sources;
 #[cfg(feature = "wayland-pipe")]
 pub mod wayland;
+pub mod window_select;
+
+pub use enumerate::{DisplayInfo, WindowInfo, enumerate_displays, enumerate_windows};
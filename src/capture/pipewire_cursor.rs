@@ -0,0 +1,830 @@
+// # PipeWire Cursor Metadata
+//
+// This module reads the cursor that compositors attach to screencast buffers
+// when the portal session was opened with `CursorMode::Metadata`.
+//
+// ## Overview
+//
+// In metadata mode the compositor leaves the pointer out of the frames and
+// sends it alongside them as `SPA_META_Cursor` buffer metadata: the hotspot
+// position in stream pixels and, whenever the shape changes, a bitmap.
+// `pipewiresrc` never asks for that metadata, so the GStreamer pipeline cannot
+// see it. Instead, a second PipeWire stream is connected to the same node
+// through its own portal remote, negotiates the cursor metadata, and keeps the
+// latest cursor in shared state that the capture source reads with each frame:
+//
+// ```text
+// portal remote ──▶ pipewiresrc ! ... ! appsink ──▶ frame pixels
+// portal remote ──▶ pw_stream (SPA_META_Cursor) ──▶ frame cursor
+// ```
+//
+// ## Linking
+//
+// `libpipewire-0.3` is loaded at runtime, so the crate builds without
+// PipeWire development files and only needs the library when metadata mode is
+// actually used. The library is already present wherever `pipewiresrc` runs.
+
+use anyhow::{Context, Result, anyhow};
+use cap_rtsp::CursorImage;
+use std::ffi::{c_char, c_int, c_void};
+use std::os::fd::{IntoRawFd, OwnedFd};
+use std::ptr;
+use std::sync::{Arc, Mutex, PoisonError};
+
+const LIBPIPEWIRE: &str = "libpipewire-0.3.so.0";
+
+// SPA type ids (spa/utils/type.h, spa/param/*.h)
+const SPA_TYPE_ID: u32 = 3;
+const SPA_TYPE_INT: u32 = 4;
+const SPA_TYPE_OBJECT: u32 = 15;
+const SPA_TYPE_CHOICE: u32 = 19;
+const SPA_TYPE_OBJECT_FORMAT: u32 = 0x40003;
+const SPA_TYPE_OBJECT_PARAM_META: u32 = 0x40005;
+const SPA_CHOICE_RANGE: u32 = 1;
+const SPA_CHOICE_ENUM: u32 = 3;
+const SPA_PARAM_ENUM_FORMAT: u32 = 3;
+const SPA_PARAM_FORMAT: u32 = 4;
+const SPA_PARAM_META: u32 = 6;
+const SPA_PARAM_META_TYPE: u32 = 1;
+const SPA_PARAM_META_SIZE: u32 = 2;
+const SPA_FORMAT_MEDIA_TYPE: u32 = 1;
+const SPA_FORMAT_MEDIA_SUBTYPE: u32 = 2;
+const SPA_FORMAT_VIDEO_FORMAT: u32 = 0x20001;
+const SPA_MEDIA_TYPE_VIDEO: u32 = 2;
+const SPA_MEDIA_SUBTYPE_RAW: u32 = 1;
+const SPA_META_CURSOR: u32 = 5;
+
+// spa_video_format values offered by compositors for screencasts and cursors
+const SPA_VIDEO_FORMAT_RGBX: u32 = 7;
+const SPA_VIDEO_FORMAT_BGRX: u32 = 8;
+const SPA_VIDEO_FORMAT_XRGB: u32 = 9;
+const SPA_VIDEO_FORMAT_XBGR: u32 = 10;
+const SPA_VIDEO_FORMAT_RGBA: u32 = 11;
+const SPA_VIDEO_FORMAT_BGRA: u32 = 12;
+const SPA_VIDEO_FORMAT_ARGB: u32 = 13;
+const SPA_VIDEO_FORMAT_ABGR: u32 = 14;
+
+const SPA_DIRECTION_INPUT: c_int = 0;
+const PW_STREAM_FLAG_AUTOCONNECT: u32 = 1;
+
+/// Largest cursor bitmap requested from the compositor, in pixels per side.
+const MAX_CURSOR_SIZE: i32 = 1024;
+/// Cursor bitmap size the compositor should plan for, in pixels per side.
+const DEFAULT_CURSOR_SIZE: i32 = 64;
+
+/// Size of `spa_meta_cursor`: id, flags, position, hotspot, bitmap_offset.
+const META_CURSOR_LEN: usize = 28;
+/// Size of `spa_meta_bitmap`: format, size, stride, offset.
+const META_BITMAP_LEN: usize = 20;
+
+/// `SPA_META_CURSOR_SIZE` for a `w`×`h` RGBA cursor: both headers plus pixels.
+fn cursor_meta_size(w: i32, h: i32) -> i32 {
+    (META_CURSOR_LEN + META_BITMAP_LEN) as i32 + w * h * 4
+}
+
+/// Latest cursor reported by the compositor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CursorState {
+    /// Hotspot position in stream pixels, `None` while the pointer is off the
+    /// stream or before the first report.
+    pub position: Option<(i32, i32)>,
+    /// Current shape, `None` while the compositor hides the pointer.
+    pub image: Option<Arc<CursorImage>>,
+}
+
+impl CursorState {
+    /// Applies one `spa_meta_cursor` block, given as the raw metadata bytes.
+    ///
+    /// A cursor id of 0 means the pointer is not on the stream. A zero bitmap
+    /// offset keeps the previous shape, and an empty bitmap hides the pointer.
+    /// Truncated or malformed metadata is ignored.
+    ///
+    /// **Time complexity**: O(w·h) when a bitmap is attached, O(1) otherwise.
+    pub fn apply(&mut self, meta: &[u8]) {
+        let (Some(id), Some(x), Some(y), Some(hot_x), Some(hot_y), Some(bitmap_offset)) = (
+            read_u32(meta, 0),
+            read_i32(meta, 8),
+            read_i32(meta, 12),
+            read_i32(meta, 16),
+            read_i32(meta, 20),
+            read_u32(meta, 24),
+        ) else {
+            return;
+        };
+        if id == 0 {
+            self.position = None;
+            return;
+        }
+        self.position = Some((x, y));
+        if bitmap_offset == 0 {
+            return;
+        }
+        let hotspot = (hot_x.max(0) as u32, hot_y.max(0) as u32);
+        if let Some(image) = read_bitmap(meta, bitmap_offset as usize, hotspot) {
+            self.image = image.map(Arc::new);
+        }
+    }
+}
+
+/// Decodes the `spa_meta_bitmap` at `offset` into a premultiplied BGRA image.
+///
+/// Returns `Some(None)` for an empty bitmap, the compositor's way of hiding the
+/// pointer, and `None` if the bitmap is truncated or in an unknown format.
+fn read_bitmap(meta: &[u8], offset: usize, hotspot: (u32, u32)) -> Option<Option<CursorImage>> {
+    let header = meta.get(offset..)?;
+    let format = read_u32(header, 0)?;
+    let width = read_u32(header, 4)?;
+    let height = read_u32(header, 8)?;
+    let stride = read_i32(header, 12)?;
+    let pixels_offset = read_u32(header, 16)? as usize;
+    if width == 0 || height == 0 {
+        return Some(None);
+    }
+
+    // Byte positions of red, green, blue and (if present) alpha in a pixel
+    let (r, g, b, a) = match format {
+        SPA_VIDEO_FORMAT_RGBA => (0, 1, 2, Some(3)),
+        SPA_VIDEO_FORMAT_BGRA => (2, 1, 0, Some(3)),
+        SPA_VIDEO_FORMAT_ARGB => (1, 2, 3, Some(0)),
+        SPA_VIDEO_FORMAT_ABGR => (3, 2, 1, Some(0)),
+        SPA_VIDEO_FORMAT_RGBX => (0, 1, 2, None),
+        SPA_VIDEO_FORMAT_BGRX => (2, 1, 0, None),
+        SPA_VIDEO_FORMAT_XRGB => (1, 2, 3, None),
+        SPA_VIDEO_FORMAT_XBGR => (3, 2, 1, None),
+        _ => return None,
+    };
+    let row_len = width as usize * 4;
+    let stride = if stride > 0 { stride as usize } else { row_len };
+    if stride < row_len {
+        return None;
+    }
+    let pixels = header.get(pixels_offset..)?;
+    let needed = stride * (height as usize - 1) + row_len;
+    if pixels.len() < needed {
+        return None;
+    }
+
+    let mut data = Vec::with_capacity(row_len * height as usize);
+    for row in pixels[..needed].chunks(stride) {
+        // Compositors send premultiplied alpha, which CursorImage keeps
+        for px in row[..row_len].chunks_exact(4) {
+            data.extend_from_slice(&[px[b], px[g], px[r], a.map_or(255, |a| px[a])]);
+        }
+    }
+    Some(Some(CursorImage {
+        width,
+        height,
+        hot_x: hotspot.0,
+        hot_y: hotspot.1,
+        data,
+    }))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    let word = bytes.get(at..at + 4)?;
+    Some(u32::from_ne_bytes(word.try_into().ok()?))
+}
+
+fn read_i32(bytes: &[u8], at: usize) -> Option<i32> {
+    read_u32(bytes, at).map(|word| word as i32)
+}
+
+/// Builder for SPA pods, the serialization PipeWire uses for stream params.
+///
+/// Every pod is a `(size, type)` header followed by its body, padded to 8
+/// bytes. The finished pod is copied into `u64`s so it has the alignment PipeWire
+/// expects.
+#[derive(Debug, Default)]
+struct PodBuilder {
+    bytes: Vec<u8>,
+}
+
+impl PodBuilder {
+    fn push_u32(&mut self, value: u32) {
+        self.bytes.extend(value.to_ne_bytes());
+    }
+
+    fn pad(&mut self) {
+        self.bytes.resize(self.bytes.len().next_multiple_of(8), 0);
+    }
+
+    /// Starts a pod of type `ty`, returning where its size goes.
+    fn begin(&mut self, ty: u32) -> usize {
+        let at = self.bytes.len();
+        self.push_u32(0);
+        self.push_u32(ty);
+        at
+    }
+
+    /// Fills in the size of the pod started at `at` and pads it.
+    fn end(&mut self, at: usize) {
+        let size = (self.bytes.len() - at - 8) as u32;
+        self.bytes[at..at + 4].copy_from_slice(&size.to_ne_bytes());
+        self.pad();
+    }
+
+    fn id(&mut self, value: u32) {
+        self.push_u32(4);
+        self.push_u32(SPA_TYPE_ID);
+        self.push_u32(value);
+        self.pad();
+    }
+
+    /// Choice of `kind` over values of pod type `ty`; the first is the default.
+    fn choice(&mut self, kind: u32, ty: u32, values: &[u32]) {
+        let at = self.begin(SPA_TYPE_CHOICE);
+        self.push_u32(kind);
+        self.push_u32(0);
+        self.push_u32(4);
+        self.push_u32(ty);
+        for &value in values {
+            self.push_u32(value);
+        }
+        self.end(at);
+    }
+
+    fn object(&mut self, ty: u32, id: u32, props: impl FnOnce(&mut Self)) {
+        let at = self.begin(SPA_TYPE_OBJECT);
+        self.push_u32(ty);
+        self.push_u32(id);
+        props(self);
+        self.end(at);
+    }
+
+    fn prop(&mut self, key: u32, value: impl FnOnce(&mut Self)) {
+        self.push_u32(key);
+        self.push_u32(0);
+        value(self);
+    }
+
+    fn finish(self) -> Vec<u64> {
+        self.bytes
+            .chunks_exact(8)
+            .map(|word| u64::from_ne_bytes(word.try_into().expect("8-byte chunk")))
+            .collect()
+    }
+}
+
+/// `EnumFormat` param accepting raw video in any packed RGB format, so the
+/// compositor can pick whatever it already uses for `pipewiresrc`.
+fn enum_format_pod() -> Vec<u64> {
+    let mut pod = PodBuilder::default();
+    pod.object(SPA_TYPE_OBJECT_FORMAT, SPA_PARAM_ENUM_FORMAT, |pod| {
+        pod.prop(SPA_FORMAT_MEDIA_TYPE, |pod| pod.id(SPA_MEDIA_TYPE_VIDEO));
+        pod.prop(SPA_FORMAT_MEDIA_SUBTYPE, |pod| {
+            pod.id(SPA_MEDIA_SUBTYPE_RAW)
+        });
+        pod.prop(SPA_FORMAT_VIDEO_FORMAT, |pod| {
+            pod.choice(
+                SPA_CHOICE_ENUM,
+                SPA_TYPE_ID,
+                &[
+                    SPA_VIDEO_FORMAT_BGRA,
+                    SPA_VIDEO_FORMAT_BGRA,
+                    SPA_VIDEO_FORMAT_BGRX,
+                    SPA_VIDEO_FORMAT_RGBA,
+                    SPA_VIDEO_FORMAT_RGBX,
+                    SPA_VIDEO_FORMAT_ARGB,
+                    SPA_VIDEO_FORMAT_XRGB,
+                    SPA_VIDEO_FORMAT_ABGR,
+                    SPA_VIDEO_FORMAT_XBGR,
+                ],
+            )
+        });
+    });
+    pod.finish()
+}
+
+/// `Meta` param asking for cursor metadata with room for the bitmap.
+fn cursor_meta_pod() -> Vec<u64> {
+    let mut pod = PodBuilder::default();
+    pod.object(SPA_TYPE_OBJECT_PARAM_META, SPA_PARAM_META, |pod| {
+        pod.prop(SPA_PARAM_META_TYPE, |pod| pod.id(SPA_META_CURSOR));
+        pod.prop(SPA_PARAM_META_SIZE, |pod| {
+            pod.choice(
+                SPA_CHOICE_RANGE,
+                SPA_TYPE_INT,
+                &[
+                    cursor_meta_size(DEFAULT_CURSOR_SIZE, DEFAULT_CURSOR_SIZE) as u32,
+                    cursor_meta_size(1, 1) as u32,
+                    cursor_meta_size(MAX_CURSOR_SIZE, MAX_CURSOR_SIZE) as u32,
+                ],
+            )
+        });
+    });
+    pod.finish()
+}
+
+// Opaque libpipewire objects
+#[repr(C)]
+struct PwThreadLoop(c_void);
+#[repr(C)]
+struct PwLoop(c_void);
+#[repr(C)]
+struct PwContext(c_void);
+#[repr(C)]
+struct PwCore(c_void);
+#[repr(C)]
+struct PwStream(c_void);
+
+#[repr(C)]
+struct SpaHook {
+    link: [*mut c_void; 2],
+    funcs: *const c_void,
+    data: *mut c_void,
+    removed: *mut c_void,
+    private: *mut c_void,
+}
+
+#[repr(C)]
+struct SpaMeta {
+    type_: u32,
+    size: u32,
+    data: *mut c_void,
+}
+
+#[repr(C)]
+struct SpaBuffer {
+    n_metas: u32,
+    n_datas: u32,
+    metas: *mut SpaMeta,
+    datas: *mut c_void,
+}
+
+#[repr(C)]
+struct PwBuffer {
+    buffer: *mut SpaBuffer,
+}
+
+/// `pw_stream_events`, version 0.
+#[repr(C)]
+struct PwStreamEvents {
+    version: u32,
+    destroy: Option<unsafe extern "C" fn(*mut c_void)>,
+    state_changed: Option<unsafe extern "C" fn(*mut c_void, c_int, c_int, *const c_char)>,
+    control_info: Option<unsafe extern "C" fn(*mut c_void, u32, *const c_void)>,
+    io_changed: Option<unsafe extern "C" fn(*mut c_void, u32, *mut c_void, u32)>,
+    param_changed: Option<unsafe extern "C" fn(*mut c_void, u32, *const c_void)>,
+    add_buffer: Option<unsafe extern "C" fn(*mut c_void, *mut PwBuffer)>,
+    remove_buffer: Option<unsafe extern "C" fn(*mut c_void, *mut PwBuffer)>,
+    process: Option<unsafe extern "C" fn(*mut c_void)>,
+    drained: Option<unsafe extern "C" fn(*mut c_void)>,
+}
+
+static STREAM_EVENTS: PwStreamEvents = PwStreamEvents {
+    version: 0,
+    destroy: None,
+    state_changed: None,
+    control_info: None,
+    io_changed: None,
+    param_changed: Some(on_param_changed),
+    add_buffer: None,
+    remove_buffer: None,
+    process: Some(on_process),
+    drained: None,
+};
+
+/// libpipewire entry points, resolved once when the library is loaded.
+#[derive(Clone, Copy)]
+struct Api {
+    init: unsafe extern "C" fn(*mut c_int, *mut *mut *mut c_char),
+    thread_loop_new: unsafe extern "C" fn(*const c_char, *const c_void) -> *mut PwThreadLoop,
+    thread_loop_get_loop: unsafe extern "C" fn(*mut PwThreadLoop) -> *mut PwLoop,
+    thread_loop_start: unsafe extern "C" fn(*mut PwThreadLoop) -> c_int,
+    thread_loop_stop: unsafe extern "C" fn(*mut PwThreadLoop),
+    thread_loop_lock: unsafe extern "C" fn(*mut PwThreadLoop),
+    thread_loop_unlock: unsafe extern "C" fn(*mut PwThreadLoop),
+    thread_loop_destroy: unsafe extern "C" fn(*mut PwThreadLoop),
+    context_new: unsafe extern "C" fn(*mut PwLoop, *mut c_void, usize) -> *mut PwContext,
+    context_connect_fd:
+        unsafe extern "C" fn(*mut PwContext, c_int, *mut c_void, usize) -> *mut PwCore,
+    context_destroy: unsafe extern "C" fn(*mut PwContext),
+    core_disconnect: unsafe extern "C" fn(*mut PwCore) -> c_int,
+    stream_new: unsafe extern "C" fn(*mut PwCore, *const c_char, *mut c_void) -> *mut PwStream,
+    stream_add_listener:
+        unsafe extern "C" fn(*mut PwStream, *mut SpaHook, *const PwStreamEvents, *mut c_void),
+    stream_connect:
+        unsafe extern "C" fn(*mut PwStream, c_int, u32, u32, *mut *const c_void, u32) -> c_int,
+    stream_update_params: unsafe extern "C" fn(*mut PwStream, *mut *const c_void, u32) -> c_int,
+    stream_dequeue_buffer: unsafe extern "C" fn(*mut PwStream) -> *mut PwBuffer,
+    stream_queue_buffer: unsafe extern "C" fn(*mut PwStream, *mut PwBuffer) -> c_int,
+    stream_disconnect: unsafe extern "C" fn(*mut PwStream) -> c_int,
+    stream_destroy: unsafe extern "C" fn(*mut PwStream),
+}
+
+impl Api {
+    /// Resolves every symbol from `lib`.
+    ///
+    /// # Safety
+    ///
+    /// `lib` must be libpipewire 0.3, whose exports have the signatures above,
+    /// and must outlive every use of the returned pointers.
+    unsafe fn load(lib: &libloading::Library) -> Result<Self> {
+        Ok(Self {
+            init: unsafe { sym(lib, "pw_init")? },
+            thread_loop_new: unsafe { sym(lib, "pw_thread_loop_new")? },
+            thread_loop_get_loop: unsafe { sym(lib, "pw_thread_loop_get_loop")? },
+            thread_loop_start: unsafe { sym(lib, "pw_thread_loop_start")? },
+            thread_loop_stop: unsafe { sym(lib, "pw_thread_loop_stop")? },
+            thread_loop_lock: unsafe { sym(lib, "pw_thread_loop_lock")? },
+            thread_loop_unlock: unsafe { sym(lib, "pw_thread_loop_unlock")? },
+            thread_loop_destroy: unsafe { sym(lib, "pw_thread_loop_destroy")? },
+            context_new: unsafe { sym(lib, "pw_context_new")? },
+            context_connect_fd: unsafe { sym(lib, "pw_context_connect_fd")? },
+            context_destroy: unsafe { sym(lib, "pw_context_destroy")? },
+            core_disconnect: unsafe { sym(lib, "pw_core_disconnect")? },
+            stream_new: unsafe { sym(lib, "pw_stream_new")? },
+            stream_add_listener: unsafe { sym(lib, "pw_stream_add_listener")? },
+            stream_connect: unsafe { sym(lib, "pw_stream_connect")? },
+            stream_update_params: unsafe { sym(lib, "pw_stream_update_params")? },
+            stream_dequeue_buffer: unsafe { sym(lib, "pw_stream_dequeue_buffer")? },
+            stream_queue_buffer: unsafe { sym(lib, "pw_stream_queue_buffer")? },
+            stream_disconnect: unsafe { sym(lib, "pw_stream_disconnect")? },
+            stream_destroy: unsafe { sym(lib, "pw_stream_destroy")? },
+        })
+    }
+}
+
+/// Looks up `name` in `lib` as a value of type `T`.
+///
+/// # Safety
+///
+/// `T` must match the type of the exported symbol.
+unsafe fn sym<T: Copy>(lib: &libloading::Library, name: &str) -> Result<T> {
+    // SAFETY: the caller guarantees the type
+    let symbol = unsafe { lib.get::<T>(name.as_bytes()) }
+        .with_context(|| format!("{} has no symbol {}", LIBPIPEWIRE, name))?;
+    Ok(*symbol)
+}
+
+/// State the stream callbacks reach through their `data` pointer.
+struct Listener {
+    api: Api,
+    stream: *mut PwStream,
+    state: Arc<Mutex<CursorState>>,
+}
+
+/// Once the format is fixed, asks for cursor metadata on the buffers.
+unsafe extern "C" fn on_param_changed(data: *mut c_void, id: u32, param: *const c_void) {
+    // SAFETY: `data` is the boxed Listener registered with the hook, which
+    // outlives the stream
+    let listener = unsafe { &*(data as *const Listener) };
+    if id != SPA_PARAM_FORMAT || param.is_null() {
+        return;
+    }
+    let pod = cursor_meta_pod();
+    let mut params = [pod.as_ptr() as *const c_void];
+    // SAFETY: called on the loop thread, where stream calls are allowed
+    unsafe { (listener.api.stream_update_params)(listener.stream, params.as_mut_ptr(), 1) };
+}
+
+/// Drains the queued buffers, keeping the cursor from their metadata.
+unsafe extern "C" fn on_process(data: *mut c_void) {
+    // SAFETY: as in `on_param_changed`
+    let listener = unsafe { &*(data as *const Listener) };
+    let mut state = listener
+        .state
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    loop {
+        // SAFETY: dequeued buffers stay valid until queued again
+        let buffer = unsafe { (listener.api.stream_dequeue_buffer)(listener.stream) };
+        if buffer.is_null() {
+            break;
+        }
+        // SAFETY: pw_buffer and spa_buffer are filled in by PipeWire and their
+        // metas point into mapped buffer memory of the advertised size
+        unsafe {
+            let spa = (*buffer).buffer;
+            if !spa.is_null() && !(*spa).metas.is_null() {
+                let metas = std::slice::from_raw_parts((*spa).metas, (*spa).n_metas as usize);
+                for meta in metas {
+                    if meta.type_ == SPA_META_CURSOR && !meta.data.is_null() {
+                        state.apply(std::slice::from_raw_parts(
+                            meta.data as *const u8,
+                            meta.size as usize,
+                        ));
+                    }
+                }
+            }
+            (listener.api.stream_queue_buffer)(listener.stream, buffer);
+        }
+    }
+}
+
+/// PipeWire stream that follows the cursor metadata of a screencast node.
+///
+/// Runs its own PipeWire thread loop; [`cursor`](Self::cursor) returns the
+/// latest cursor at any time. Dropping the stream disconnects it.
+pub(crate) struct CursorMetaStream {
+    api: Api,
+    thread_loop: *mut PwThreadLoop,
+    context: *mut PwContext,
+    core: *mut PwCore,
+    stream: *mut PwStream,
+    hook: Box<SpaHook>,
+    listener: Box<Listener>,
+    state: Arc<Mutex<CursorState>>,
+    _lib: libloading::Library,
+}
+
+// SAFETY: the PipeWire objects are only touched with the thread loop locked,
+// or after it has been stopped in Drop
+unsafe impl Send for CursorMetaStream {}
+// SAFETY: shared references only reach the mutex-guarded cursor state
+unsafe impl Sync for CursorMetaStream {}
+
+impl std::fmt::Debug for CursorMetaStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CursorMetaStream")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CursorMetaStream {
+    /// Connects to `node_id` through the portal remote `fd`, which the stream
+    /// takes over.
+    ///
+    /// Fails if libpipewire cannot be loaded or the connection is refused. The
+    /// cursor metadata itself is negotiated asynchronously, so
+    /// [`cursor`](Self::cursor) stays empty until the first buffer arrives.
+    pub fn connect(fd: OwnedFd, node_id: u32) -> Result<Self> {
+        // SAFETY: loading libpipewire runs no initializers with preconditions
+        let lib = unsafe { libloading::Library::new(LIBPIPEWIRE) }
+            .with_context(|| format!("failed to load {}", LIBPIPEWIRE))?;
+        // SAFETY: `lib` is libpipewire 0.3 and is kept in the returned stream
+        let api = unsafe { Api::load(&lib)? };
+        let state = Arc::new(Mutex::new(CursorState::default()));
+
+        // SAFETY: libpipewire calls in the documented order; every object is
+        // destroyed in Drop, or below if a later step fails
+        unsafe {
+            (api.init)(ptr::null_mut(), ptr::null_mut());
+            let thread_loop = (api.thread_loop_new)(c"cap-cursor".as_ptr(), ptr::null());
+            if thread_loop.is_null() {
+                return Err(anyhow!("failed to create PipeWire thread loop"));
+            }
+            let context =
+                (api.context_new)((api.thread_loop_get_loop)(thread_loop), ptr::null_mut(), 0);
+            if context.is_null() {
+                (api.thread_loop_destroy)(thread_loop);
+                return Err(anyhow!("failed to create PipeWire context"));
+            }
+            let mut this = Self {
+                api,
+                thread_loop,
+                context,
+                core: ptr::null_mut(),
+                stream: ptr::null_mut(),
+                hook: Box::new(std::mem::zeroed()),
+                listener: Box::new(Listener {
+                    api,
+                    stream: ptr::null_mut(),
+                    state: state.clone(),
+                }),
+                state,
+                _lib: lib,
+            };
+            if (api.thread_loop_start)(thread_loop) < 0 {
+                return Err(anyhow!("failed to start PipeWire thread loop"));
+            }
+
+            (api.thread_loop_lock)(thread_loop);
+            let connected = this.connect_stream(fd, node_id);
+            (api.thread_loop_unlock)(thread_loop);
+            connected?;
+            Ok(this)
+        }
+    }
+
+    /// Creates the stream and connects it. Called with the loop locked.
+    unsafe fn connect_stream(&mut self, fd: OwnedFd, node_id: u32) -> Result<()> {
+        let api = self.api;
+        // SAFETY: the context is valid and the loop is locked by the caller
+        unsafe {
+            self.core =
+                (api.context_connect_fd)(self.context, fd.into_raw_fd(), ptr::null_mut(), 0);
+            if self.core.is_null() {
+                return Err(anyhow!("failed to connect to the PipeWire remote"));
+            }
+            self.stream = (api.stream_new)(self.core, c"cap-cursor".as_ptr(), ptr::null_mut());
+            if self.stream.is_null() {
+                return Err(anyhow!("failed to create PipeWire stream"));
+            }
+            self.listener.stream = self.stream;
+            (api.stream_add_listener)(
+                self.stream,
+                &mut *self.hook,
+                &STREAM_EVENTS,
+                &mut *self.listener as *mut Listener as *mut c_void,
+            );
+            let pod = enum_format_pod();
+            let mut params = [pod.as_ptr() as *const c_void];
+            let res = (api.stream_connect)(
+                self.stream,
+                SPA_DIRECTION_INPUT,
+                node_id,
+                PW_STREAM_FLAG_AUTOCONNECT,
+                params.as_mut_ptr(),
+                1,
+            );
+            if res < 0 {
+                return Err(anyhow!(
+                    "failed to connect PipeWire stream to node {}: {}",
+                    node_id,
+                    std::io::Error::from_raw_os_error(-res)
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Latest cursor reported by the compositor.
+    pub fn cursor(&self) -> CursorState {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+impl Drop for CursorMetaStream {
+    fn drop(&mut self) {
+        let api = self.api;
+        // SAFETY: stopping the loop joins its thread, so no callback runs
+        // while the objects are torn down; each pointer is destroyed once
+        unsafe {
+            (api.thread_loop_stop)(self.thread_loop);
+            if !self.stream.is_null() {
+                (api.stream_disconnect)(self.stream);
+                (api.stream_destroy)(self.stream);
+            }
+            if !self.core.is_null() {
+                (api.core_disconnect)(self.core);
+            }
+            (api.context_destroy)(self.context);
+            (api.thread_loop_destroy)(self.thread_loop);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor_meta(id: u32, position: (i32, i32), hotspot: (i32, i32), bitmap: &[u8]) -> Vec<u8> {
+        let mut meta = Vec::new();
+        meta.extend(id.to_ne_bytes());
+        meta.extend(0u32.to_ne_bytes());
+        for value in [position.0, position.1, hotspot.0, hotspot.1] {
+            meta.extend(value.to_ne_bytes());
+        }
+        let offset = if bitmap.is_empty() {
+            0
+        } else {
+            META_CURSOR_LEN as u32
+        };
+        meta.extend(offset.to_ne_bytes());
+        meta.extend(bitmap);
+        meta
+    }
+
+    fn bitmap(format: u32, width: u32, height: u32, stride: i32, pixels: &[u8]) -> Vec<u8> {
+        let mut bitmap = Vec::new();
+        for value in [format, width, height, stride as u32, META_BITMAP_LEN as u32] {
+            bitmap.extend(value.to_ne_bytes());
+        }
+        bitmap.extend(pixels);
+        bitmap
+    }
+
+    #[test]
+    fn reads_position_and_converts_rgba_to_bgra() {
+        // 2x1 RGBA with a padded stride of 12 bytes
+        let pixels = [10, 20, 30, 255, 5, 6, 7, 128, 0, 0, 0, 0];
+        let meta = cursor_meta(
+            1,
+            (100, 50),
+            (1, 0),
+            &bitmap(SPA_VIDEO_FORMAT_RGBA, 2, 1, 12, &pixels),
+        );
+        let mut state = CursorState::default();
+        state.apply(&meta);
+
+        assert_eq!(state.position, Some((100, 50)));
+        let image = state.image.expect("bitmap attached");
+        assert_eq!(
+            (image.width, image.height, image.hot_x, image.hot_y),
+            (2, 1, 1, 0)
+        );
+        assert_eq!(image.data, vec![30, 20, 10, 255, 7, 6, 5, 128]);
+    }
+
+    #[test]
+    fn opaque_formats_get_full_alpha() {
+        let meta = cursor_meta(
+            1,
+            (0, 0),
+            (0, 0),
+            &bitmap(SPA_VIDEO_FORMAT_XRGB, 1, 1, 4, &[0, 1, 2, 3]),
+        );
+        let mut state = CursorState::default();
+        state.apply(&meta);
+        assert_eq!(state.image.unwrap().data, vec![3, 2, 1, 255]);
+    }
+
+    #[test]
+    fn keeps_shape_until_a_new_bitmap_arrives() {
+        let mut state = CursorState::default();
+        state.apply(&cursor_meta(
+            1,
+            (1, 1),
+            (0, 0),
+            &bitmap(SPA_VIDEO_FORMAT_BGRA, 1, 1, 4, &[1, 2, 3, 4]),
+        ));
+        let shape = state.image.clone();
+
+        state.apply(&cursor_meta(1, (7, 8), (0, 0), &[]));
+        assert_eq!(state.position, Some((7, 8)));
+        assert_eq!(state.image, shape);
+
+        // Leaving the stream keeps the shape for when the pointer comes back
+        state.apply(&cursor_meta(0, (0, 0), (0, 0), &[]));
+        assert_eq!(state.position, None);
+        assert_eq!(state.image, shape);
+    }
+
+    #[test]
+    fn empty_bitmap_hides_the_pointer() {
+        let mut state = CursorState::default();
+        state.apply(&cursor_meta(
+            1,
+            (1, 1),
+            (0, 0),
+            &bitmap(SPA_VIDEO_FORMAT_BGRA, 1, 1, 4, &[1, 2, 3, 4]),
+        ));
+        state.apply(&cursor_meta(
+            1,
+            (2, 2),
+            (0, 0),
+            &bitmap(SPA_VIDEO_FORMAT_BGRA, 0, 0, 0, &[]),
+        ));
+        assert_eq!(state.position, Some((2, 2)));
+        assert_eq!(state.image, None);
+    }
+
+    #[test]
+    fn ignores_truncated_metadata() {
+        let mut state = CursorState::default();
+        state.apply(&[1, 0, 0, 0]);
+        assert_eq!(state, CursorState::default());
+
+        // Bitmap claims more pixels than the metadata holds
+        let meta = cursor_meta(
+            1,
+            (3, 4),
+            (0, 0),
+            &bitmap(SPA_VIDEO_FORMAT_BGRA, 2, 2, 8, &[0; 8]),
+        );
+        state.apply(&meta);
+        assert_eq!(state.position, Some((3, 4)));
+        assert_eq!(state.image, None);
+    }
+
+    fn words(pod: &[u64]) -> Vec<u32> {
+        pod.iter()
+            .flat_map(|word| word.to_ne_bytes())
+            .collect::<Vec<u8>>()
+            .chunks(4)
+            .map(|word| u32::from_ne_bytes(word.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn meta_param_requests_cursor_with_bitmap_room() {
+        let pod = words(&cursor_meta_pod());
+        #[rustfmt::skip]
+        let expected = [
+            // Object header and (type, id)
+            80, SPA_TYPE_OBJECT, SPA_TYPE_OBJECT_PARAM_META, SPA_PARAM_META,
+            // type = Id(Cursor), padded
+            SPA_PARAM_META_TYPE, 0, 4, SPA_TYPE_ID, SPA_META_CURSOR, 0,
+            // size = Range(default, min, max) of Int, padded
+            SPA_PARAM_META_SIZE, 0, 28, SPA_TYPE_CHOICE, SPA_CHOICE_RANGE, 0, 4, SPA_TYPE_INT,
+            48 + 64 * 64 * 4, 48 + 4, 48 + 1024 * 1024 * 4, 0,
+        ];
+        assert_eq!(pod, expected);
+    }
+
+    #[test]
+    fn enum_format_pod_sizes_are_consistent() {
+        let pod = words(&enum_format_pod());
+        assert_eq!(pod[0] as usize + 8, pod.len() * 4);
+        assert_eq!(pod[1], SPA_TYPE_OBJECT);
+        assert_eq!(&pod[2..4], &[SPA_TYPE_OBJECT_FORMAT, SPA_PARAM_ENUM_FORMAT]);
+        // Video format choice: 9 Id values after the choice and child headers
+        assert_eq!(
+            &pod[16..20],
+            &[SPA_FORMAT_VIDEO_FORMAT, 0, 16 + 9 * 4, SPA_TYPE_CHOICE]
+        );
+        assert_eq!(&pod[20..24], &[SPA_CHOICE_ENUM, 0, 4, SPA_TYPE_ID]);
+    }
+}
@@ -0,0 +1,233 @@
+// # Portal Restore Token Store
+//
+// This module persists xdg-desktop-portal screencast restore tokens between runs,
+// so the consent dialog is only shown the first time a source type is captured.
+//
+// ## Overview
+//
+// When sources are selected with `PersistMode::ExplicitlyRevoked`, the portal
+// returns a single-use restore token alongside the granted streams. Passing that
+// token to the next `SelectSources` call restores the previous selection without
+// user interaction, and the portal hands back a fresh token to use next time.
+//
+// Tokens are stored as a small JSON object keyed by source type, with
+// multi-source selections (e.g. several monitors) kept apart from single ones:
+//
+// ```text
+// $XDG_STATE_HOME/cap/portal-restore-tokens.json
+// {
+//   "monitor": "8d6a...",
+//   "monitor+multiple": "c41e...",
+//   "window": "1f03..."
+// }
+// ```
+//
+// `$XDG_STATE_HOME` defaults to `~/.local/state`. The file is rewritten with
+// owner-only permissions on every save, since a token grants screen access
+// without a prompt.
+
+use anyhow::{Context, Result};
+use ashpd::desktop::screencast::SourceType;
+use serde_json::{Map, Value};
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+/// File-backed store of portal restore tokens, one per source type and
+/// single/multiple selection.
+#[derive(Debug, Clone)]
+pub struct RestoreTokenStore {
+    path: PathBuf,
+}
+
+impl RestoreTokenStore {
+    /// Store backed by the given JSON file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store in the XDG state directory (`$XDG_STATE_HOME/cap/`, falling back to
+    /// `~/.local/state/cap/`). Returns `None` if neither variable is set.
+    pub fn default_location() -> Option<Self> {
+        let state_dir = std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state"))
+            })?;
+        Some(Self::new(state_dir.join("cap/portal-restore-tokens.json")))
+    }
+
+    /// Path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the saved token for a single or `multiple` selection of
+    /// `source_type`, if any.
+    ///
+    /// A missing or unreadable file is treated as empty: the portal will
+    /// simply ask for consent again.
+    pub fn load(&self, source_type: SourceType, multiple: bool) -> Option<String> {
+        self.read_all()
+            .ok()?
+            .get(&Self::key(source_type, multiple))?
+            .as_str()
+            .map(str::to_owned)
+    }
+
+    /// Saves `token` for a single or `multiple` selection of `source_type`,
+    /// replacing any previous token.
+    pub fn save(&self, source_type: SourceType, multiple: bool, token: &str) -> Result<()> {
+        let mut tokens = self.read_all().unwrap_or_default();
+        tokens.insert(
+            Self::key(source_type, multiple),
+            Value::String(token.to_string()),
+        );
+        self.write_all(&tokens)
+    }
+
+    /// Forgets all saved grants. Succeeds if nothing was saved.
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).with_context(|| format!("failed to remove {}", self.path.display())),
+        }
+    }
+
+    fn key(source_type: SourceType, multiple: bool) -> String {
+        let name = match source_type {
+            SourceType::Monitor => "monitor",
+            SourceType::Window => "window",
+            SourceType::Virtual => "virtual",
+        };
+        if multiple {
+            format!("{}+multiple", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn read_all(&self) -> Result<Map<String, Value>> {
+        let contents = fs::read_to_string(&self.path)?;
+        match serde_json::from_str(&contents)? {
+            Value::Object(tokens) => Ok(tokens),
+            _ => Ok(Map::new()),
+        }
+    }
+
+    fn write_all(&self, tokens: &Map<String, Value>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+
+        // Write a sibling file and rename it over the store: `mode` only
+        // applies when a file is created, so an existing store (or a stale
+        // temp file) would otherwise keep whatever permissions it had.
+        let mut tmp_name = self.path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = self.path.with_file_name(tmp_name);
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp_path)
+            .with_context(|| format!("failed to open {}", tmp_path.display()))?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("failed to restrict {}", tmp_path.display()))?;
+        file.write_all(serde_json::to_string_pretty(tokens)?.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load_per_source_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RestoreTokenStore::new(dir.path().join("state/tokens.json"));
+
+        assert_eq!(store.load(SourceType::Monitor, false), None);
+
+        store
+            .save(SourceType::Monitor, false, "monitor-token")
+            .unwrap();
+        store
+            .save(SourceType::Window, false, "window-token")
+            .unwrap();
+        store
+            .save(SourceType::Monitor, false, "monitor-token-2")
+            .unwrap();
+        store
+            .save(SourceType::Monitor, true, "monitors-token")
+            .unwrap();
+
+        assert_eq!(
+            store.load(SourceType::Monitor, false).as_deref(),
+            Some("monitor-token-2")
+        );
+        assert_eq!(
+            store.load(SourceType::Monitor, true).as_deref(),
+            Some("monitors-token")
+        );
+        assert_eq!(
+            store.load(SourceType::Window, false).as_deref(),
+            Some("window-token")
+        );
+        assert_eq!(store.load(SourceType::Virtual, false), None);
+    }
+
+    #[test]
+    fn clear_forgets_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RestoreTokenStore::new(dir.path().join("tokens.json"));
+
+        store.clear().unwrap();
+        store.save(SourceType::Monitor, false, "token").unwrap();
+        store.clear().unwrap();
+
+        assert_eq!(store.load(SourceType::Monitor, false), None);
+        assert!(!store.path().exists());
+    }
+
+    #[test]
+    fn corrupt_file_is_treated_as_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RestoreTokenStore::new(dir.path().join("tokens.json"));
+        fs::write(store.path(), "not json").unwrap();
+
+        assert_eq!(store.load(SourceType::Monitor, false), None);
+        store.save(SourceType::Monitor, false, "token").unwrap();
+        assert_eq!(
+            store.load(SourceType::Monitor, false).as_deref(),
+            Some("token")
+        );
+    }
+
+    #[test]
+    fn save_restricts_existing_file_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RestoreTokenStore::new(dir.path().join("tokens.json"));
+        fs::write(store.path(), "{}").unwrap();
+        fs::set_permissions(store.path(), fs::Permissions::from_mode(0o644)).unwrap();
+
+        store.save(SourceType::Monitor, false, "token").unwrap();
+
+        let mode = fs::metadata(store.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            store.load(SourceType::Monitor, false).as_deref(),
+            Some("token")
+        );
+    }
+}
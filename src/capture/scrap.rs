@@ -49,8 +49,6 @@
 
 use anyhow::{Context, Result, anyhow};
 #[cfg(any(target_os = "windows", target_os = "macos"))]
-use scrap::Window;
-#[cfg(any(target_os = "windows", target_os = "macos"))]
 use scrap::{Capturer, Display};
 #[cfg(any(target_os = "windows", target_os = "macos"))]
 use std::io::Write;
@@ -76,13 +74,13 @@ pub async fn capture_ffmpeg(options: CaptureOptions) -> Result<()> {
         // For X11 Linux, use ffmpeg's x11grab directly
         #[cfg(target_os = "linux")]
         {
-            if !options.window {
-                return capture_x11_ffmpeg(options);
-            } else {
-                return Err(anyhow!(
-                    "Window capture not supported on Linux. Use full screen capture."
-                ));
+            if options.window {
+                #[cfg(feature = "x11rb")]
+                return capture_x11_window_ffmpeg(options);
+                #[cfg(not(feature = "x11rb"))]
+                return Err(anyhow!("X11 window capture requires the x11rb feature"));
             }
+            return capture_x11_ffmpeg(options);
         }
 
         // For other platforms, use the original scrap + ffmpeg approach
@@ -111,13 +109,22 @@ fn capture_x11_ffmpeg(options: CaptureOptions) -> Result<()> {
     let display = std::env::var("DISPLAY").unwrap_or(":0".to_string());
     println!("Using display: {}", display);
 
+    // A region grabs only that rectangle: -video_size WxH -i :0.0+X,Y
+    let mut input = format!("{}.0", display);
+    let mut grab_args = vec!["-f".to_string(), "x11grab".to_string()];
+    if let Some(region) = options.region {
+        println!("Capturing region {:?}", region);
+        grab_args.push("-video_size".to_string());
+        grab_args.push(format!("{}x{}", region.w, region.h));
+        input = format!("{}+{},{}", input, region.x, region.y);
+    }
+
     let mut child = Command::new("ffmpeg")
+        .arg("-y")
+        .args(&grab_args)
         .args([
-            "-y",
-            "-f",
-            "x11grab",
             "-i",
-            &format!("{}.0", display),
+            &input,
             "-r",
             &options.fps.to_string(),
             "-t",
@@ -151,6 +158,54 @@ fn capture_x11_ffmpeg(options: CaptureOptions) -> Result<()> {
     }
 }
 
+/// Captures one X11 window with x11grab by grabbing its on-screen rectangle.
+///
+/// The window is resolved from `options.window_selector`; a region is taken
+/// relative to the window.
+///
+/// Time complexity: O(n) X11 round trips to list n windows, then the same as
+/// [`capture_x11_ffmpeg`].
+///
+/// Missing functionality: the rectangle is fixed when capture starts, so a
+/// window moved during capture is not followed.
+#[cfg(all(target_os = "linux", feature = "x11rb"))]
+fn capture_x11_window_ffmpeg(mut options: CaptureOptions) -> Result<()> {
+    use crate::error::CaptureError;
+    use crate::processing::Rect;
+
+    let display = std::env::var("DISPLAY").unwrap_or(":0".to_string());
+    let selector = options.window_selector.take().unwrap_or_default();
+    let window = selector.resolve_x11(&display)?;
+    println!("Capturing window {}", window);
+
+    let bounds = window.geometry;
+    if bounds.w == 0 || bounds.h == 0 {
+        return Err(CaptureError::validation(
+            "window",
+            "must be visible on screen",
+            window.to_string(),
+        )
+        .into());
+    }
+    options.region = Some(match options.region {
+        Some(region) if !region.fits_within(bounds.size()) => {
+            return Err(CaptureError::validation(
+                "region",
+                format!("must fit within the {}x{} window", bounds.w, bounds.h),
+                format!("{:?}", region),
+            )
+            .into());
+        }
+        Some(region) => Rect {
+            x: bounds.x + region.x,
+            y: bounds.y + region.y,
+            ..region
+        },
+        None => bounds,
+    });
+    capture_x11_ffmpeg(options)
+}
+
 /// Captures screen content on Windows/macOS using scrap library with FFmpeg encoding.
 ///
 /// Time complexity: O(seconds * fps) where each frame capture is O(1) but scaling
@@ -162,31 +217,13 @@ fn capture_x11_ffmpeg(options: CaptureOptions) -> Result<()> {
 ///
 /// Missing functionality:
 /// - Gundam mode not supported for video capture (returns error)
-/// - Window capture is interactive (requires user input from stdin)
+/// - Window selection matches titles only (scrap reports no class or pid)
 #[cfg(any(target_os = "windows", target_os = "macos"))]
 fn capture_scrap_ffmpeg(options: CaptureOptions) -> Result<()> {
     let (w, h, mut cap) = if options.window {
-        let windows = Window::all().context("failed to list windows")?;
-        if windows.is_empty() {
-            return Err(anyhow!("no windows found"));
-        }
-        println!("Available windows:");
-        for (i, w) in windows.iter().enumerate() {
-            println!("{}: {}", i, w.title());
-        }
-        println!("Enter the number of the window to capture:");
-        let mut input = String::new();
-        std::io::stdin()
-            .read_line(&mut input)
-            .context("failed to read input")?;
-        let index: usize = input.trim().parse().context("invalid number")?;
-        if index >= windows.len() {
-            return Err(anyhow!("invalid window index"));
-        }
-        let window = windows
-            .into_iter()
-            .nth(index)
-            .ok_or_else(|| anyhow!("window index {} out of bounds", index))?;
+        let selector = options.window_selector.clone().unwrap_or_default();
+        let (info, window) = selector.resolve_scrap()?;
+        println!("Capturing window {}", info);
         let w = window.width();
         let h = window.height();
         let cap = Capturer::new(window).context("cannot create capturer for window")?;
@@ -199,6 +236,28 @@ fn capture_scrap_ffmpeg(options: CaptureOptions) -> Result<()> {
         (w, h, cap)
     };
 
+    // Scrap always grabs the whole display/window; a region is cropped per frame
+    let (capture_w, capture_h) = (w, h);
+    let (w, h) = match options.region {
+        Some(region) => {
+            let bounds = crate::processing::Size {
+                w: w as u32,
+                h: h as u32,
+            };
+            if !region.fits_within(bounds) {
+                return Err(crate::error::CaptureError::validation(
+                    "region",
+                    format!("must fit within the {}x{} capture", w, h),
+                    format!("{:?}", region),
+                )
+                .into());
+            }
+            println!("Capturing region {:?}", region);
+            (region.w as usize, region.h as usize)
+        }
+        None => (w, h),
+    };
+
     // Determine output dimensions (scaled or original)
     let (output_w, output_h) = if let Some(preset) = &options.scale_preset {
         use cap_scale::presets::{AspectMode, Size, build_plan};
@@ -255,7 +314,7 @@ fn capture_scrap_ffmpeg(options: CaptureOptions) -> Result<()> {
     // Simple frame pacing
     let frame_time = Duration::from_secs_f64(1.0 / options.fps as f64);
     let end_time = Instant::now() + Duration::from_secs(options.seconds as u64);
-    let frame_size = w as usize * h as usize * 4; // BGRA = 4 bytes per pixel
+    let frame_size = capture_w * capture_h * 4; // BGRA = 4 bytes per pixel
 
     // Initialize scaling resources if needed
     let (mut resizer, mut staging, mut scaled_buffer, output_size) =
@@ -303,6 +362,10 @@ fn capture_scrap_ffmpeg(options: CaptureOptions) -> Result<()> {
                         frame_size
                     ));
                 }
+                let cropped = options
+                    .region
+                    .map(|region| region.crop_bgra(frame_data, capture_w * 4));
+                let frame_data = cropped.as_deref().unwrap_or(frame_data);
 
                 // Apply scaling if enabled
                 let data_to_write = if let (
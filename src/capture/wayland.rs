@@ -44,6 +44,7 @@
 //
 // 3. **GStreamer Pipeline**:
 //    - `pipewiresrc`: Receives video from PipeWire
+//    - `videocrop`: Cuts out the capture region, if one is set
 //    - `videorate`: Controls frame rate
 //    - `videoconvert`: Format conversion if needed
 //    - `x264enc`: Hardware-accelerated H.264 encoding
@@ -72,13 +73,242 @@
 /// Internal API - no public examples available
 
 use anyhow::{Context, Result, anyhow};
-use ashpd::desktop::PersistMode;
 use ashpd::desktop::screencast::{CursorMode, Screencast, SourceType};
+use ashpd::desktop::{PersistMode, Session};
 use gstreamer as gst;
 use gstreamer::prelude::*;
 use std::os::fd::{IntoRawFd, OwnedFd};
 
 use crate::CaptureOptions;
+use crate::capture::portal_tokens::RestoreTokenStore;
+use crate::processing::Rect;
+
+/// What to ask the XDG Desktop Portal for.
+#[derive(Debug, Clone, Copy)]
+pub struct PortalRequest {
+    /// Kind of source offered in the consent dialog.
+    pub source_type: SourceType,
+    /// Let the user pick several sources at once (e.g. every monitor).
+    pub multiple: bool,
+    /// How the compositor delivers the cursor. Falls back to
+    /// [`CursorMode::Embedded`] if the portal does not offer the mode.
+    pub cursor_mode: CursorMode,
+}
+
+impl Default for PortalRequest {
+    fn default() -> Self {
+        Self {
+            source_type: SourceType::Monitor,
+            multiple: false,
+            cursor_mode: CursorMode::Embedded,
+        }
+    }
+}
+
+/// A PipeWire stream authorized through the XDG Desktop Portal.
+///
+/// `position` and `size` describe where the stream sits in the compositor's
+/// logical desktop layout, so frame coordinates can be mapped back to it with
+/// [`PortalStream::map_to_desktop`].
+#[derive(Debug, Clone, Copy)]
+pub struct PortalStream {
+    /// PipeWire node id to pass as `pipewiresrc path=`.
+    pub node_id: u32,
+    /// Top-left corner in logical desktop coordinates, if reported.
+    pub position: Option<(i32, i32)>,
+    /// Logical size reported by the compositor, if any.
+    pub size: Option<(i32, i32)>,
+    /// Kind of source the user picked, if reported.
+    pub source_type: Option<SourceType>,
+}
+
+impl PortalStream {
+    /// Maps a pixel in a `frame_w`×`frame_h` frame of this stream to logical
+    /// desktop coordinates.
+    ///
+    /// Frames can be larger than the logical size on HiDPI outputs, so the
+    /// point is scaled before being offset by the stream position. Returns
+    /// `None` when the portal did not report position and size.
+    pub fn map_to_desktop(&self, x: f64, y: f64, frame_w: u32, frame_h: u32) -> Option<(f64, f64)> {
+        let (px, py) = self.position?;
+        let (lw, lh) = self.size?;
+        if frame_w == 0 || frame_h == 0 {
+            return None;
+        }
+        Some((
+            px as f64 + x * lw as f64 / frame_w as f64,
+            py as f64 + y * lh as f64 / frame_h as f64,
+        ))
+    }
+}
+
+/// An active portal screencast session.
+///
+/// Streams are read through PipeWire remotes opened with
+/// [`PortalSession::open_remote`]. The portal keeps the grant alive until
+/// [`PortalSession::close`] is called or the D-Bus connection goes away.
+#[derive(Debug)]
+pub struct PortalSession {
+    /// Streams selected by the user, one per granted source.
+    pub streams: Vec<PortalStream>,
+    /// Cursor mode the session was opened with, after any fallback to
+    /// [`CursorMode::Embedded`].
+    pub cursor_mode: CursorMode,
+    proxy: Screencast<'static>,
+    session: Session<'static, Screencast<'static>>,
+}
+
+impl PortalSession {
+    /// Opens a new PipeWire remote connection for `pipewiresrc fd=`.
+    ///
+    /// Every pipeline needs its own remote: `pipewiresrc` takes ownership of
+    /// the connection, so pipelines sharing one fd interfere with each other.
+    pub async fn open_remote(&self) -> Result<OwnedFd> {
+        let fd = self.proxy.open_pipe_wire_remote(&self.session).await?;
+        println!("Opened PipeWire remote connection");
+        Ok(fd)
+    }
+
+    /// Ends the screencast session on the portal side.
+    pub async fn close(self) -> Result<()> {
+        self.session.close().await?;
+        Ok(())
+    }
+}
+
+/// Runs the portal handshake: asks the user for the sources described by
+/// `request` and returns the session granting the streams.
+///
+/// With a `token_store`, the saved restore token for the request is passed
+/// along with `PersistMode::ExplicitlyRevoked` so the consent dialog is skipped
+/// when the grant is still valid, and the new token returned by the portal is
+/// saved for the next run. Without a store nothing is persisted.
+///
+/// Time complexity: O(1), but blocks on user interaction with the portal dialog.
+pub async fn open_portal_session(
+    request: PortalRequest,
+    token_store: Option<&RestoreTokenStore>,
+) -> Result<PortalSession> {
+    let PortalRequest {
+        source_type,
+        multiple,
+        cursor_mode,
+    } = request;
+    let proxy = Screencast::new().await?;
+    println!("Created screencast proxy");
+    let session = proxy.create_session().await?;
+    println!("Created session");
+    println!("Using source type: {:?}", source_type);
+
+    let (restore_token, persist_mode) = match token_store {
+        Some(store) => (
+            store.load(source_type, multiple),
+            PersistMode::ExplicitlyRevoked,
+        ),
+        None => (None, PersistMode::DoNot),
+    };
+    if restore_token.is_some() {
+        println!("Restoring previous screencast grant");
+    }
+
+    // Embedded draws the cursor into frames; older portals offer nothing else
+    let cursor_mode = match proxy.available_cursor_modes().await {
+        Ok(modes) if !modes.contains(cursor_mode) => {
+            eprintln!(
+                "Portal does not offer cursor mode {:?}, using Embedded",
+                cursor_mode
+            );
+            CursorMode::Embedded
+        }
+        _ => cursor_mode,
+    };
+    proxy
+        .select_sources(
+            &session,
+            cursor_mode,
+            source_type.into(),
+            multiple,
+            restore_token.as_deref(),
+            persist_mode,
+        )
+        .await?;
+    println!("Selected sources");
+    let start = proxy.start(&session, None).await?;
+    let response = start.response()?;
+    println!("Got {} streams", response.streams().len());
+    if let (Some(store), Some(token)) = (token_store, response.restore_token()) {
+        // A failed save only means the dialog shows again next time
+        if let Err(e) = store.save(source_type, multiple, token) {
+            eprintln!("Failed to save portal restore token: {:#}", e);
+        }
+    }
+    let streams: Vec<PortalStream> = response
+        .streams()
+        .iter()
+        .take(if multiple { usize::MAX } else { 1 })
+        .map(|stream| PortalStream {
+            node_id: stream.pipe_wire_node_id(),
+            position: stream.position(),
+            size: stream.size(),
+            source_type: stream.source_type(),
+        })
+        .collect();
+    if streams.is_empty() {
+        return Err(anyhow!("portal returned no streams"));
+    }
+    for stream in &streams {
+        println!(
+            "Stream node ID: {} (position {:?}, size {:?})",
+            stream.node_id, stream.position, stream.size
+        );
+    }
+    Ok(PortalSession {
+        streams,
+        cursor_mode,
+        proxy,
+        session,
+    })
+}
+
+/// Creates the `pipewiresrc` element reading `node_id` through the portal's remote `fd`.
+pub(crate) fn make_pipewire_src(fd: OwnedFd, node_id: u32) -> Result<gst::Element> {
+    let src = gst::ElementFactory::make("pipewiresrc")
+        .build()
+        .map_err(|_| anyhow!("missing GStreamer element: pipewiresrc"))?;
+    // Provide the portal's remote fd so the source can read the authorized stream.
+    // Convert OwnedFd to RawFd for GStreamer
+    let raw_fd = fd.into_raw_fd();
+    src.set_property("fd", &raw_fd);
+    // Some setups also accept the node id; if unsupported, this property is ignored.
+    // On many desktops providing fd is sufficient (single approved stream).
+    src.set_property("path", &format!("{}", node_id));
+    Ok(src)
+}
+
+/// Creates `videocrop ! capsfilter` elements that cut `region` out of a stream.
+///
+/// Only the left/top offsets are fixed; the right/bottom crops are automatic
+/// (`-1`), so `videocrop` derives them from the downstream `width`/`height`
+/// caps once the stream size is negotiated. A region outside the stream fails
+/// caps negotiation.
+pub(crate) fn make_region_crop(region: Rect) -> Result<[gst::Element; 2]> {
+    let crop = gst::ElementFactory::make("videocrop")
+        .property("left", region.x as i32)
+        .property("top", region.y as i32)
+        .property("right", -1i32)
+        .property("bottom", -1i32)
+        .build()
+        .map_err(|_| anyhow!("missing GStreamer element: videocrop (install gst-plugins-good)"))?;
+    let caps = gst::Caps::builder("video/x-raw")
+        .field("width", region.w as i32)
+        .field("height", region.h as i32)
+        .build();
+    let size = gst::ElementFactory::make("capsfilter")
+        .property("caps", &caps)
+        .build()
+        .map_err(|_| anyhow!("missing GStreamer element: capsfilter"))?;
+    Ok([crop, size])
+}
 
 /// Captures screen content on Wayland using XDG Desktop Portal and GStreamer.
 ///
@@ -87,47 +317,24 @@ use crate::CaptureOptions;
 ///
 /// Missing functionality: None - fully implements Wayland screen capture.
 pub async fn capture_gstreamer(options: &CaptureOptions) -> Result<()> {
-    let (node_id, pw_fd) = {
-        let proxy = Screencast::new().await?;
-        println!("Created screencast proxy");
-        let session = proxy.create_session().await?;
-        println!("Created session");
-        let source_type = if options.window {
-            SourceType::Window
-        } else {
-            SourceType::Monitor
-        };
-        println!("Using source type: {:?}", source_type);
-        // Monitor capture; use CursorMode::Embedded to include cursor in frames.
-        proxy
-            .select_sources(
-                &session,
-                CursorMode::Embedded,
-                source_type.into(),
-                false, // multiple
-                None,  // restore_token
-                PersistMode::DoNot,
-            )
-            .await?;
-        println!("Selected sources");
-        let start = proxy.start(&session, None).await?;
-        let streams = start.response()?;
-        println!("Got {} streams", streams.streams().len());
-        let stream = streams
-            .streams()
-            .first()
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("portal returned no streams"))?;
-        let node_id = stream.pipe_wire_node_id();
-        println!("Stream node ID: {}", node_id);
-        let pw_fd = proxy.open_pipe_wire_remote(&session).await?;
-        println!("Opened PipeWire remote connection");
-        Ok::<(u32, OwnedFd), anyhow::Error>((node_id, pw_fd))
-    }?;
+    let source_type = if options.window {
+        SourceType::Window
+    } else {
+        SourceType::Monitor
+    };
+    let token_store = RestoreTokenStore::default_location();
+    let request = PortalRequest {
+        source_type,
+        ..PortalRequest::default()
+    };
+    let portal = open_portal_session(request, token_store.as_ref()).await?;
+    let node_id = portal.streams[0].node_id;
+    let pw_fd = portal.open_remote().await?;
 
     // 2) Build a simple GStreamer pipeline:
     //
     // pipewiresrc fd=<pw_fd> [path=<node_id>] !
+    //   [videocrop ! video/x-raw,width=<w>,height=<h> !]
     //   videorate !
     //   video/x-raw,format=NV12,framerate=<fps>/1 !
     //   x264enc tune=zerolatency speed-preset=veryfast key-int-max=<fps> !
@@ -139,16 +346,7 @@ pub async fn capture_gstreamer(options: &CaptureOptions) -> Result<()> {
 
     let pipeline = gst::Pipeline::new();
 
-    let src = gst::ElementFactory::make("pipewiresrc")
-        .build()
-        .map_err(|_| anyhow!("missing GStreamer element: pipewiresrc"))?;
-    // Provide the portal's remote fd so the source can read the authorized stream.
-    // Convert OwnedFd to RawFd for GStreamer
-    let raw_fd = pw_fd.into_raw_fd();
-    src.set_property("fd", &raw_fd);
-    // Some setups also accept the node id; if unsupported, this property is ignored.
-    // On many desktops providing fd is sufficient (single approved stream).
-    src.set_property("path", &format!("{}", node_id));
+    let src = make_pipewire_src(pw_fd, node_id)?;
 
     let rate = gst::ElementFactory::make("videorate")
         .build()
@@ -185,8 +383,16 @@ pub async fn capture_gstreamer(options: &CaptureOptions) -> Result<()> {
         .map_err(|_| anyhow!("missing GStreamer element: filesink"))?;
     sink.set_property("location", &options.output);
 
-    pipeline.add_many(&[&src, &capsfilter, &rate, &convert, &enc, &mux, &sink])?;
-    gst::Element::link_many(&[&src, &capsfilter, &rate, &convert, &enc, &mux, &sink])?;
+    // Optional region: pipewiresrc ! videocrop ! video/x-raw,width=W,height=H ! ...
+    let mut chain = vec![src.clone()];
+    if let Some(region) = options.region {
+        println!("Capturing region {:?}", region);
+        chain.extend(make_region_crop(region)?);
+    }
+    chain.extend([capsfilter, rate, convert, enc, mux, sink]);
+
+    pipeline.add_many(&chain)?;
+    gst::Element::link_many(&chain)?;
 
     // Add a pad probe to check if we're getting data
     let src_pad = src.static_pad("src").unwrap();
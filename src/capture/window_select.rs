@@ -0,0 +1,296 @@
+// # Window Selection
+//
+// This module picks the window to capture from criteria given up front, so
+// window capture works from scripts and GUIs without prompting on stdin.
+//
+// ## Overview
+//
+// A `WindowSelector` combines up to three criteria; a window must satisfy all
+// of the ones that are set:
+//
+// | Criterion | CLI flag | Matches |
+// |-----------|----------|---------|
+// | Title | `--window-title <regex>` | Regular expression searched in the window title |
+// | Class | `--window-class <name>` | `WM_CLASS` instance or class name, case-insensitive |
+// | Process | `--window-pid <pid>` | `_NET_WM_PID` of the owning process |
+//
+// Candidates come from the window manager on X11 (`_NET_CLIENT_LIST` read
+// through x11rb) and from `scrap::Window::all()` on Windows/macOS, which only
+// reports titles. Exactly one candidate must match: when none or several do,
+// selection fails with a `CaptureError::Validation` listing the candidates so
+// the criteria can be tightened.
+//
+// An empty selector matches every window, so `--window` on its own succeeds
+// only when a single window is open.
+
+pub use super::enumerate::WindowInfo;
+use crate::error::CaptureError;
+use regex::Regex;
+use std::fmt;
+
+/// Maximum number of candidates spelled out in a selection error.
+const MAX_LISTED_CANDIDATES: usize = 20;
+
+/// Criteria picking exactly one window to capture.
+///
+/// ```rust
+/// use hybrid_screen_capture::capture::window_select::WindowSelector;
+///
+/// let selector = WindowSelector::new()
+///     .with_title("Firefox$")?
+///     .with_class("firefox");
+/// assert!(!selector.is_empty());
+/// # Ok::<(), hybrid_screen_capture::error::CaptureError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WindowSelector {
+    title: Option<Regex>,
+    class: Option<String>,
+    pid: Option<u32>,
+}
+
+impl WindowSelector {
+    /// Selector matching every window.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the title to match the regular expression `pattern`.
+    ///
+    /// # Errors
+    /// Returns a validation error if `pattern` is not a valid regex.
+    pub fn with_title(mut self, pattern: &str) -> Result<Self, CaptureError> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            CaptureError::validation("window-title", format!("invalid regex: {}", e), pattern)
+        })?;
+        self.title = Some(regex);
+        Ok(self)
+    }
+
+    /// Require the `WM_CLASS` instance or class name to equal `class`
+    /// (case-insensitive).
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.class = Some(class.into());
+        self
+    }
+
+    /// Require the window to belong to process `pid`.
+    pub fn with_pid(mut self, pid: u32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Returns `true` if no criterion is set.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.class.is_none() && self.pid.is_none()
+    }
+
+    /// Returns `true` if `window` satisfies every criterion that is set.
+    ///
+    /// Windows without class or pid information never match a class or pid
+    /// criterion.
+    pub fn matches(&self, window: &WindowInfo) -> bool {
+        let title_ok = self
+            .title
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(&window.title));
+        let class_ok = self.class.as_ref().is_none_or(|class| {
+            [&window.instance, &window.class]
+                .into_iter()
+                .flatten()
+                .any(|name| name.eq_ignore_ascii_case(class))
+        });
+        let pid_ok = self.pid.is_none_or(|pid| window.pid == Some(pid));
+        title_ok && class_ok && pid_ok
+    }
+
+    /// Picks the single window in `candidates` matching this selector.
+    ///
+    /// Time complexity: O(n) regex searches for n candidates.
+    ///
+    /// # Errors
+    /// Returns a [`CaptureError::Validation`] listing the candidates when no
+    /// window or more than one window matches.
+    pub fn select(&self, candidates: Vec<WindowInfo>) -> Result<WindowInfo, CaptureError> {
+        let (mut matching, others): (Vec<_>, Vec<_>) = candidates
+            .into_iter()
+            .partition(|window| self.matches(window));
+        match matching.len() {
+            1 => Ok(matching.remove(0)),
+            0 => Err(CaptureError::validation(
+                "window",
+                format!("no window matches {}", self),
+                list_candidates(&others),
+            )),
+            n => Err(CaptureError::validation(
+                "window",
+                format!("{} windows match {}; narrow the selection", n, self),
+                list_candidates(&matching),
+            )),
+        }
+    }
+
+    /// Resolves the selector against the top-level windows of X11 `display`.
+    ///
+    /// # Errors
+    /// Returns a capture-init error if the display cannot be queried, or a
+    /// validation error if not exactly one window matches.
+    #[cfg(all(target_os = "linux", feature = "x11rb"))]
+    pub fn resolve_x11(&self, display: &str) -> Result<WindowInfo, CaptureError> {
+        self.select(super::enumerate::enumerate_x11_windows(display)?)
+    }
+
+    /// Resolves the selector against the scrap window list.
+    ///
+    /// scrap only reports titles, so class and pid criteria are rejected.
+    ///
+    /// # Errors
+    /// Returns a validation error if a class or pid criterion is set or if
+    /// not exactly one window matches.
+    #[cfg(all(
+        feature = "screen-capture",
+        any(target_os = "windows", target_os = "macos")
+    ))]
+    pub fn resolve_scrap(&self) -> Result<(WindowInfo, scrap::Window), CaptureError> {
+        if self.class.is_some() || self.pid.is_some() {
+            return Err(CaptureError::validation(
+                "window",
+                "only --window-title is supported by the scrap window list",
+                self.to_string(),
+            ));
+        }
+        let (candidates, mut windows): (Vec<_>, Vec<_>) =
+            super::enumerate::scrap_windows()?.into_iter().unzip();
+        let info = self.select(candidates)?;
+        let window = windows.swap_remove(info.id as usize);
+        Ok((info, window))
+    }
+}
+
+impl fmt::Display for WindowSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut criteria = Vec::new();
+        if let Some(title) = &self.title {
+            criteria.push(format!("title /{}/", title));
+        }
+        if let Some(class) = &self.class {
+            criteria.push(format!("class {:?}", class));
+        }
+        if let Some(pid) = self.pid {
+            criteria.push(format!("pid {}", pid));
+        }
+        if criteria.is_empty() {
+            f.write_str("any window")
+        } else {
+            f.write_str(&criteria.join(", "))
+        }
+    }
+}
+
+/// Formats candidates for an error message, one per line.
+fn list_candidates(windows: &[WindowInfo]) -> String {
+    if windows.is_empty() {
+        return "no windows found".to_string();
+    }
+    let mut list = format!("{} candidates:", windows.len());
+    for window in windows.iter().take(MAX_LISTED_CANDIDATES) {
+        list.push_str(&format!("\n  {}", window));
+    }
+    if windows.len() > MAX_LISTED_CANDIDATES {
+        list.push_str(&format!(
+            "\n  ... and {} more",
+            windows.len() - MAX_LISTED_CANDIDATES
+        ));
+    }
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::processing::Rect;
+
+    fn window(id: u64, title: &str, class: &str, pid: u32) -> WindowInfo {
+        WindowInfo {
+            id,
+            title: title.to_string(),
+            instance: Some(class.to_lowercase()),
+            class: Some(class.to_string()),
+            pid: Some(pid),
+            geometry: Rect {
+                x: 0,
+                y: 0,
+                w: 800,
+                h: 600,
+            },
+            scale_factor: 1.0,
+            focused: false,
+        }
+    }
+
+    fn candidates() -> Vec<WindowInfo> {
+        vec![
+            window(1, "Inbox - Mozilla Firefox", "Firefox", 100),
+            window(2, "notes.md - Code", "Code", 200),
+            window(3, "Docs - Mozilla Firefox", "Firefox", 100),
+        ]
+    }
+
+    #[test]
+    fn selects_single_match() {
+        let selector = WindowSelector::new().with_title("^notes").unwrap();
+        assert_eq!(selector.select(candidates()).unwrap().id, 2);
+
+        let selector = WindowSelector::new()
+            .with_class("firefox")
+            .with_title("Docs")
+            .unwrap();
+        assert_eq!(selector.select(candidates()).unwrap().id, 3);
+    }
+
+    #[test]
+    fn class_and_pid_must_all_match() {
+        let selector = WindowSelector::new().with_class("code").with_pid(100);
+        assert!(selector.select(candidates()).is_err());
+
+        let selector = WindowSelector::new().with_pid(200);
+        assert_eq!(selector.select(candidates()).unwrap().id, 2);
+    }
+
+    #[test]
+    fn ambiguous_or_missing_matches_list_candidates() {
+        let err = WindowSelector::new()
+            .with_class("Firefox")
+            .select(candidates())
+            .unwrap_err();
+        let CaptureError::Validation { value, .. } = &err else {
+            panic!("expected a validation error, got {:?}", err);
+        };
+        assert!(value.starts_with("2 candidates:"));
+        assert!(value.contains("Inbox") && value.contains("Docs"));
+        assert!(!value.contains("notes.md"));
+
+        let err = WindowSelector::new()
+            .with_title("Terminal")
+            .unwrap()
+            .select(candidates())
+            .unwrap_err();
+        let CaptureError::Validation { value, .. } = &err else {
+            panic!("expected a validation error, got {:?}", err);
+        };
+        assert!(value.starts_with("3 candidates:"));
+    }
+
+    #[test]
+    fn empty_selector_needs_exactly_one_window() {
+        let only = vec![window(7, "Terminal", "XTerm", 1)];
+        assert_eq!(WindowSelector::new().select(only).unwrap().id, 7);
+        assert!(WindowSelector::new().select(candidates()).is_err());
+        assert!(WindowSelector::new().select(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_title_regex() {
+        assert!(WindowSelector::new().with_title("(unclosed").is_err());
+    }
+}
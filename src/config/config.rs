@@ -21,6 +21,7 @@
 //! | `seconds` | `u32` | 1-3600 | Capture duration in seconds |
 //! | `crf` | `u8` | 18-28 | x264 quality factor (lower = higher quality) |
 //! | `window` | `bool` | true/false | Window vs full screen capture |
+//! | `region` | `Option<Rect>` | Non-empty | Capture only this rectangle |
 //!
 //! ## Quality Presets
 //!
@@ -76,6 +77,7 @@
 /// - `seconds`: Duration of the capture in seconds
 /// - `crf`: Constant Rate Factor (quality setting for x264 encoding)
 /// - `window`: Whether to capture a specific window or the full screen
+/// - `region`: Optional rectangle to capture instead of the whole screen
 ///
 /// # Examples
 ///
@@ -91,6 +93,7 @@
 ///     window: false,
 ///     scale_preset: None,
 ///     gundam_mode: false,
+///     region: None,
 /// };
 /// ```
 ///
@@ -105,6 +108,7 @@
 ///     window: true,  // Window capture
 ///     scale_preset: None,
 ///     gundam_mode: false,
+///     region: None,
 /// };
 /// ```
 pub struct CaptureConfig {
@@ -140,9 +144,9 @@ pub struct CaptureConfig {
 
     /// Whether to capture a specific window instead of the full screen.
     ///
-    /// When `true`, the user will be prompted to select a window to capture.
-    /// When `false`, captures the primary display. Note that window capture
-    /// is not supported on Linux platforms.
+    /// When `true`, the window is picked by `window_selector`.
+    /// When `false`, captures the primary display. Note that on Linux window
+    /// capture needs an X11 session.
     pub window: bool,
 
     /// Optional scaling preset for token-efficient VLM input.
@@ -156,6 +160,19 @@ pub struct CaptureConfig {
     /// When enabled, produces n×640×640 tiles + 1×1024×1024 global view
     /// exactly matching DeepSeek-OCR's input requirements.
     pub gundam_mode: bool,
+
+    /// Optional rectangle to capture, in pixels relative to the captured
+    /// display or window.
+    ///
+    /// When `None`, the whole display (or window) is captured. Set it with
+    /// [`CaptureConfig::with_region`]; the CLI exposes it as `--region x,y,w,h`.
+    pub region: Option<crate::processing::Rect>,
+
+    /// Criteria picking the window to capture when `window` is set.
+    ///
+    /// Set it with [`CaptureConfig::with_window_selector`]; the CLI exposes it
+    /// as `--window-title`, `--window-class` and `--window-pid`.
+    pub window_selector: Option<crate::capture::window_select::WindowSelector>,
 }
 
 impl Default for CaptureConfig {
@@ -169,6 +186,8 @@ impl Default for CaptureConfig {
     /// - `window`: false (full screen capture)
     /// - `scale_preset`: None (no scaling)
     /// - `gundam_mode`: false (standard capture)
+    /// - `region`: None (whole display)
+    /// - `window_selector`: None (the only window, when `window` is set)
     ///
     /// # Examples
     ///
@@ -188,6 +207,8 @@ impl Default for CaptureConfig {
             window: false,
             scale_preset: None,
             gundam_mode: false,
+            region: None,
+            window_selector: None,
         }
     }
 }
@@ -216,9 +237,31 @@ impl CaptureConfig {
             window,
             scale_preset,
             gundam_mode,
+            region: None,
+            window_selector: None,
         }
     }
 
+    /// Restricts capture to `region` (or the whole display for `None`).
+    ///
+    /// Time complexity: O(1) - Simple field assignment.
+    pub fn with_region(mut self, region: Option<crate::processing::Rect>) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Captures the window matched by `selector`; also enables window capture.
+    ///
+    /// Time complexity: O(1) - Simple field assignment.
+    pub fn with_window_selector(
+        mut self,
+        selector: crate::capture::window_select::WindowSelector,
+    ) -> Self {
+        self.window = true;
+        self.window_selector = Some(selector);
+        self
+    }
+
     /// Validates the configuration parameters.
     ///
     /// Time complexity: O(1) - Performs constant-time range checks on numeric fields.
@@ -235,6 +278,12 @@ impl CaptureConfig {
         if !(18..=28).contains(&self.crf) {
             return Err("CRF must be between 18 and 28".to_string());
         }
+        if self
+            .region
+            .is_some_and(|region| region.w == 0 || region.h == 0)
+        {
+            return Err("Capture region must have non-zero width and height".to_string());
+        }
         Ok(())
     }
 
@@ -254,6 +303,8 @@ impl CaptureConfig {
             window: self.window,
             scale_preset: self.scale_preset,
             gundam_mode: self.gundam_mode,
+            region: self.region,
+            window_selector: self.window_selector.clone(),
         }
     }
 }
@@ -299,4 +350,51 @@ mod tests {
         // Valid again
         assert!(config.validate().is_ok());
     }
+
+    #[test]
+    fn test_region() {
+        use crate::processing::Rect;
+
+        let region: Rect = "100, 50,1280,720".parse().unwrap();
+        assert_eq!(
+            region,
+            Rect {
+                x: 100,
+                y: 50,
+                w: 1280,
+                h: 720
+            }
+        );
+        assert!("100,50,0,720".parse::<Rect>().is_err());
+        assert!("100,50,1280".parse::<Rect>().is_err());
+        assert!("a,b,c,d".parse::<Rect>().is_err());
+
+        let config = CaptureConfig::default().with_region(Some(region));
+        assert!(config.validate().is_ok());
+        assert_eq!(config.to_capture_options().region, Some(region));
+
+        let empty = Rect { w: 0, ..region };
+        assert!(
+            CaptureConfig::default()
+                .with_region(Some(empty))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_window_selector() {
+        use crate::capture::window_select::WindowSelector;
+
+        let selector = WindowSelector::new().with_title("Firefox").unwrap();
+        let config = CaptureConfig::default().with_window_selector(selector);
+        assert!(config.window);
+
+        let options = config.to_capture_options();
+        assert!(options.window);
+        assert_eq!(
+            options.window_selector.map(|selector| selector.to_string()),
+            Some("title /Firefox/".to_string())
+        );
+    }
 }
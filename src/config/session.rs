@@ -117,12 +117,8 @@ impl CaptureSessionBuilder {
     pub fn with_gundam(mut self) -> Self {
         use crate::processing::processing::GundamProcessor;
         use cap_scale::gundam::GundamCfg;
-        self.processors.push(Box::new(GundamProcessor {
-            cfg: GundamCfg::default(),
-            tile_buffers: Vec::new(),
-            global_buffer: Vec::new(),
-            output_size: Size { w: 0, h: 0 },
-        }));
+        self.processors
+            .push(Box::new(GundamProcessor::new(GundamCfg::default())));
         self
     }
 
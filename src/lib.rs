@@ -35,6 +35,8 @@
 //!     window: false,
 //!     scale_preset: None,
 //!     gundam_mode: false,
+//!     region: None,
+//!     window_selector: None,
 //! };
 //!
 //! capture_screen(options).await?;
@@ -62,7 +64,7 @@ pub use error::{
 
 /// Re-export commonly used types from dependencies
 #[cfg(feature = "rtsp-streaming")]
-pub use cap_rtsp::BgraFrame;
+pub use cap_rtsp::{BgraFrame, FrameMeta, GundamFrameSet, GundamTile};
 
 /// Configuration options for screen capture operations.
 ///
@@ -82,6 +84,8 @@ pub use cap_rtsp::BgraFrame;
 ///     window: false,  // Full screen capture
 ///     scale_preset: None,
 ///     gundam_mode: false,
+///     region: None,
+///     window_selector: None,
 /// };
 /// ```
 #[derive(Debug, Clone)]
@@ -115,10 +119,11 @@ pub struct CaptureOptions {
 
     /// Whether to capture a specific window instead of the full screen.
     ///
-    /// When `true`, the user will be prompted to select a window to capture.
+    /// When `true`, the window is picked by [`CaptureOptions::window_selector`].
     /// When `false`, captures the primary display.
     ///
-    /// Note: Window capture is not supported on Linux (will fall back to full screen).
+    /// Note: On Linux, window capture needs X11; on Wayland the portal picker
+    /// chooses the window.
     pub window: bool,
 
     /// Optional scaling preset for token-efficient VLM input.
@@ -132,6 +137,20 @@ pub struct CaptureOptions {
     /// When enabled, produces n×640×640 tiles + 1×1024×1024 global view
     /// exactly matching DeepSeek-OCR's input requirements.
     pub gundam_mode: bool,
+
+    /// Optional rectangle to capture instead of the whole display or window.
+    ///
+    /// Coordinates are in pixels relative to the captured display (or window).
+    /// Honored by every backend: x11grab captures only the rectangle, the
+    /// scrap path crops each frame, and Wayland crops with `videocrop`.
+    pub region: Option<processing::Rect>,
+
+    /// Criteria picking the window to capture when `window` is set.
+    ///
+    /// `None` behaves like an empty selector: capture succeeds only if exactly
+    /// one window exists. Ambiguous or unmatched criteria fail with a
+    /// validation error listing the candidates instead of prompting.
+    pub window_selector: Option<capture::window_select::WindowSelector>,
 }
 
 /// Dispatch capture to the appropriate platform-specific implementation.
@@ -240,6 +259,15 @@ async fn dispatch_linux(options: CaptureOptions) -> Result<()> {
 /// but current implementation provides clear error messages and fallbacks.
 #[cfg(target_os = "linux")]
 async fn dispatch_wayland(options: CaptureOptions) -> Result<()> {
+    // Wayland clients cannot enumerate windows; the portal dialog picks one
+    if let Some(selector) = options.window_selector.as_ref().filter(|s| !s.is_empty()) {
+        return Err(error::CaptureError::validation(
+            "window",
+            "cannot be preselected on Wayland; the portal dialog picks the window",
+            selector.to_string(),
+        )
+        .into());
+    }
     #[cfg(feature = "wayland-pipe")]
     {
         println!("Detected Wayland session → using Portal + PipeWire (ashpd) + GStreamer …");
@@ -425,6 +453,8 @@ pub fn is_wayland_session() -> bool {
 ///         window: false,
 ///         scale_preset: None,
 ///         gundam_mode: false,
+///         region: None,
+///         window_selector: None,
 ///     };
 ///
 ///     capture_screen(options).await?;
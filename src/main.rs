@@ -3,7 +3,7 @@ use clap::Parser;
 use hybrid_screen_capture::config::config::CaptureConfig;
 
 #[cfg(feature = "rtsp-streaming")]
-use cap_rtsp::{BgraFrame, RtspConfig, RtspPublisher, start_server};
+use cap_rtsp::{BgraFrame, FrameMeta, RtspConfig, RtspPublisher, start_server};
 #[cfg(feature = "rtsp-streaming")]
 use std::sync::Arc;
 #[cfg(feature = "rtsp-streaming")]
@@ -59,6 +59,38 @@ struct Args {
     #[arg(long, help = "Capture a specific window instead of the entire screen")]
     window: bool,
 
+    /// Pick the window by title
+    #[arg(
+        long,
+        value_name = "REGEX",
+        help = "Capture the window whose title matches this regular expression (implies --window)"
+    )]
+    window_title: Option<String>,
+
+    /// Pick the window by WM_CLASS
+    #[arg(
+        long,
+        value_name = "CLASS",
+        help = "Capture the window with this WM_CLASS instance or class name, case-insensitive (X11, implies --window)"
+    )]
+    window_class: Option<String>,
+
+    /// Pick the window by owning process
+    #[arg(
+        long,
+        value_name = "PID",
+        help = "Capture the window owned by this process id (X11, implies --window)"
+    )]
+    window_pid: Option<u32>,
+
+    /// Capture only a rectangle of the screen
+    #[arg(
+        long,
+        value_name = "X,Y,W,H",
+        help = "Capture only this rectangle (pixels, relative to the screen, window or synthetic frame), e.g. 0,0,1280,720"
+    )]
+    region: Option<hybrid_screen_capture::processing::Rect>,
+
     /// Token-efficient scaling preset for VLM input
     #[arg(
         long,
@@ -67,6 +99,56 @@ struct Args {
     )]
     scale_preset: Option<cap_scale::presets::TokenPreset>,
 
+    /// Exact scaling target size
+    #[arg(
+        long,
+        value_parser = parse_size,
+        conflicts_with = "scale_preset",
+        help = "With --session: scale frames to WIDTHxHEIGHT, keeping the aspect ratio within it unless --aspect says otherwise"
+    )]
+    scale_size: Option<(u32, u32)>,
+
+    /// Aspect ratio handling when scaling
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = AspectChoice::Preserve,
+        help = "With --session and --scale-preset/--scale-size: preserve the aspect ratio, distort to fill the target, or pad (letterbox) to the exact target size"
+    )]
+    aspect: AspectChoice,
+
+    /// Padding color for --aspect pad
+    #[arg(
+        long,
+        default_value = "000000",
+        value_parser = parse_color,
+        help = "Color of the bars added by --aspect pad, as RRGGBB or RRGGBBAA hex"
+    )]
+    pad_color: [u8; 4],
+
+    /// Resampling filter when scaling
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = cap_scale::presets::ResizeFilter::Lanczos3,
+        help = "With --session: resampling filter for scaling"
+    )]
+    resize_filter: cap_scale::presets::ResizeFilter,
+
+    /// Resample in linear light
+    #[arg(
+        long,
+        help = "With --session: resample in linear light (gamma-correct) when scaling or tiling; keeps thin bright text from darkening"
+    )]
+    gamma_correct: bool,
+
+    /// Sharpen after downscaling
+    #[arg(
+        long,
+        help = "With --session: apply a mild unsharp mask after downscaling, for OCR of small text"
+    )]
+    ocr_sharpen: bool,
+
     /// Enable DeepSeek-OCR Gundam tiling mode
     #[arg(
         long,
@@ -74,6 +156,49 @@ struct Args {
     )]
     gundam: bool,
 
+    /// Threads for Gundam tile packing
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "With --session and --gundam: scale tiles and the global view on N threads; output is identical"
+    )]
+    gundam_threads: u32,
+
+    /// Choose Gundam tiles by content
+    #[arg(
+        long,
+        help = "With --session and --gundam: drop blank tiles and keep the most detailed ones instead of the first in reading order"
+    )]
+    gundam_skip_blank: bool,
+
+    /// Gundam tile grid
+    #[arg(
+        long,
+        default_value = "auto",
+        value_parser = parse_grid,
+        help = "With --session and --gundam: tile grid as auto (up to 3x3 by size), aspect (grid closest to the frame's aspect ratio, as DeepSeek-OCR does), COLSxROWS, or density:SCALE (tile pixels per frame pixel)"
+    )]
+    gundam_grid: cap_scale::gundam::GridStrategy,
+
+    /// Gundam mosaic layout
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = GundamLayoutChoice::Last,
+        help = "With --session and --gundam: put the global view after the tiles, before them, or beside the tile grid at its full 1024px"
+    )]
+    gundam_layout: GundamLayoutChoice,
+
+    /// Gundam mosaic background
+    #[arg(
+        long,
+        default_value = "ffffff",
+        value_parser = parse_color,
+        help = "With --session and --gundam: color of empty mosaic cells, as RRGGBB or RRGGBBAA hex"
+    )]
+    gundam_background: [u8; 4],
+
     /// Enable RTSP streaming mode
     #[arg(
         long,
@@ -95,6 +220,222 @@ struct Args {
         help = "Use session-based capture architecture with CaptureSessionBuilder"
     )]
     session: bool,
+
+    /// Capture several portal streams (multi-monitor Wayland)
+    #[arg(
+        long,
+        help = "Let the Wayland portal grant several monitors at once (with --session)"
+    )]
+    multi_monitor: bool,
+
+    /// How to record several streams
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = MonitorLayout::Composite,
+        help = "With --multi-monitor: composite streams side by side into one output, or record each to its own output"
+    )]
+    monitor_layout: MonitorLayout,
+
+    /// Where frames come from
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = SourceKind::Screen,
+        help = "Frame source: screen, or synthetic GStreamer test patterns for headless machines"
+    )]
+    source: SourceKind,
+
+    /// Test pattern for synthetic frames
+    #[cfg(feature = "rtsp-streaming")]
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = cap_rtsp::test_pattern::TestPattern::Smpte,
+        help = "Test pattern with --source synthetic"
+    )]
+    pattern: cap_rtsp::test_pattern::TestPattern,
+
+    /// Size of synthetic frames
+    #[arg(
+        long,
+        default_value = "1920x1080",
+        value_parser = parse_size,
+        help = "Frame size with --source synthetic, as WIDTHxHEIGHT"
+    )]
+    synthetic_size: (u32, u32),
+
+    /// What to do with frames identical to the previous one
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = UnchangedFrameMode::Process,
+        help = "With --session: process every frame, skip frames without changes, or repeat the last output for them (XDamage on X11, tile hashing elsewhere)"
+    )]
+    unchanged_frames: UnchangedFrameMode,
+
+    /// Composite the cursor into frames
+    #[arg(
+        long,
+        help = "With --session: draw the cursor into frames from sources that report it separately (X11; on Wayland the compositor is asked for cursor metadata instead of embedding it)"
+    )]
+    draw_cursor: bool,
+
+    /// Ring clicks in the output
+    #[arg(
+        long,
+        help = "With --session: draw a ring around the cursor on mouse clicks (implies --draw-cursor)"
+    )]
+    highlight_clicks: bool,
+
+    /// Model family for the token estimate
+    #[arg(
+        long,
+        value_enum,
+        help = "With --session: model family for the vision token estimate printed before capture (default: all)"
+    )]
+    token_model: Option<TokenModelKind>,
+
+    /// Per-frame vision token budget
+    #[arg(
+        long,
+        conflicts_with_all = ["scale_preset", "scale_size", "max_tokens_per_minute"],
+        help = "With --session: scale each frame to the largest size costing at most this many vision tokens for --token-model (default qwen)"
+    )]
+    max_frame_tokens: Option<u64>,
+
+    /// Per-minute vision token budget
+    #[arg(
+        long,
+        conflicts_with_all = ["scale_preset", "scale_size"],
+        help = "With --session: adapt the frame size to stay under this many vision tokens per minute at --fps for --token-model (default qwen)"
+    )]
+    max_tokens_per_minute: Option<u64>,
+
+    /// Forget saved Wayland screencast grants
+    #[arg(
+        long,
+        help = "Forget saved Wayland screencast permissions (portal restore tokens) and exit"
+    )]
+    forget_portal_grants: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Commands that inspect the desktop instead of capturing.
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// List capturable displays or windows and exit
+    List {
+        /// What to list
+        #[arg(value_enum)]
+        target: ListTarget,
+
+        /// Print a JSON array instead of one line per entry
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// What `cap list` enumerates.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ListTarget {
+    /// Monitors, with geometry usable as --region
+    Displays,
+    /// Top-level windows, selectable with --window-title/--window-class/--window-pid
+    Windows,
+}
+
+/// How multiple capture streams are recorded.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum MonitorLayout {
+    /// One output with all streams side by side
+    Composite,
+    /// One output per stream (numbered files or consecutive RTSP ports)
+    Separate,
+}
+
+/// Where captured frames come from.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum SourceKind {
+    /// The platform screen capture backend
+    Screen,
+    /// Generated test-pattern frames (no display needed)
+    Synthetic,
+}
+
+/// Handling of frames without changes in session mode.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum UnchangedFrameMode {
+    /// Process and output every frame
+    Process,
+    /// Drop frames without changes
+    Skip,
+    /// Re-send the last output without processing
+    Duplicate,
+}
+
+#[cfg(feature = "rtsp-streaming")]
+impl From<UnchangedFrameMode> for hybrid_screen_capture::session::UnchangedFrames {
+    fn from(mode: UnchangedFrameMode) -> Self {
+        match mode {
+            UnchangedFrameMode::Process => Self::Process,
+            UnchangedFrameMode::Skip => Self::Skip,
+            UnchangedFrameMode::Duplicate => Self::Duplicate,
+        }
+    }
+}
+
+/// Aspect ratio handling selected by `--aspect`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum AspectChoice {
+    /// Keep the aspect ratio, fitting within the target
+    Preserve,
+    /// Stretch to the target size
+    Distort,
+    /// Keep the aspect ratio and pad to the target size with --pad-color
+    Pad,
+}
+
+/// Placement of the Gundam global view selected by `--gundam-layout`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum GundamLayoutChoice {
+    /// In the grid cell after the last tile
+    Last,
+    /// In the first grid cell, before the tiles
+    First,
+    /// Full size, right of the tile grid
+    Beside,
+}
+
+/// Model family priced by `--token-model`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenModelKind {
+    /// Qwen2.5-VL / Qwen3-VL (28px patches)
+    Qwen,
+    /// Claude (area / 750)
+    Claude,
+    /// OpenAI high detail (512px tiles)
+    Openai,
+    /// OpenAI low detail (fixed cost)
+    OpenaiLow,
+    /// DeepSeek-OCR (64px per token)
+    DeepseekOcr,
+}
+
+#[cfg(feature = "rtsp-streaming")]
+impl TokenModelKind {
+    fn model(self) -> &'static (dyn cap_scale::tokens::TokenModel + Send + Sync) {
+        use cap_scale::tokens::{CLAUDE, DEEPSEEK_OCR, OPENAI_HIGH, OPENAI_LOW, QWEN};
+        match self {
+            TokenModelKind::Qwen => &QWEN,
+            TokenModelKind::Claude => &CLAUDE,
+            TokenModelKind::Openai => &OPENAI_HIGH,
+            TokenModelKind::OpenaiLow => &OPENAI_LOW,
+            TokenModelKind::DeepseekOcr => &DEEPSEEK_OCR,
+        }
+    }
 }
 
 /// Main entry point for the screen capture application.
@@ -106,11 +447,39 @@ struct Args {
 /// Missing functionality: None - fully implemented with RTSP and file output modes.
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Args::parse();
+    let mut args = Args::parse();
+    // Any window criterion implies --window
+    args.window |=
+        args.window_title.is_some() || args.window_class.is_some() || args.window_pid.is_some();
+
+    if args.forget_portal_grants {
+        return forget_portal_grants();
+    }
+
+    if let Some(Command::List { target, json }) = args.command {
+        return list_targets(target, json);
+    }
 
-    // Handle RTSP streaming mode
+    // Handle RTSP streaming mode (synthetic frames go through the session pipeline)
     #[cfg(feature = "rtsp-streaming")]
-    if args.rtsp {
+    if args.rtsp && !args.session && args.source == SourceKind::Screen {
+        if args.region.is_some() {
+            return Err(hybrid_screen_capture::error::CaptureError::validation(
+                "region",
+                "requires --session when streaming via RTSP",
+                "--region",
+            )
+            .into());
+        }
+        #[cfg(target_os = "linux")]
+        if args.window {
+            return Err(hybrid_screen_capture::error::CaptureError::validation(
+                "window",
+                "requires --session when streaming via RTSP on Linux",
+                "--window",
+            )
+            .into());
+        }
         return run_rtsp_mode(args).await;
     }
 
@@ -126,7 +495,7 @@ async fn main() -> Result<()> {
         .clone()
         .unwrap_or_else(|| args.output.clone());
 
-    let config = CaptureConfig::new(
+    let mut config = CaptureConfig::new(
         output,
         args.fps,
         seconds,
@@ -134,7 +503,11 @@ async fn main() -> Result<()> {
         args.window,
         args.scale_preset,
         args.gundam,
-    );
+    )
+    .with_region(args.region);
+    if args.window {
+        config = config.with_window_selector(window_selector(&args)?);
+    }
 
     config.validate().map_err(|e| {
         hybrid_screen_capture::error::CaptureError::validation("config", "invalid", &e)
@@ -143,20 +516,73 @@ async fn main() -> Result<()> {
 
     // Use session-based capture if requested
     #[cfg(feature = "rtsp-streaming")]
-    if args.session {
+    if args.session || args.source == SourceKind::Synthetic {
         return run_session_capture(args, config).await;
     }
 
     #[cfg(not(feature = "rtsp-streaming"))]
-    if args.session {
+    if args.session || args.source == SourceKind::Synthetic {
         return Err(anyhow::anyhow!(
-            "Session-based capture requires rtsp-streaming feature"
+            "Session-based capture and synthetic sources require rtsp-streaming feature"
         ));
     }
 
     hybrid_screen_capture::capture_screen(options).await
 }
 
+/// Deletes saved portal restore tokens so the next Wayland capture asks for consent again.
+///
+/// Time complexity: O(1) - removes a single state file.
+fn forget_portal_grants() -> Result<()> {
+    #[cfg(all(feature = "wayland-pipe", target_os = "linux"))]
+    {
+        use hybrid_screen_capture::capture::portal_tokens::RestoreTokenStore;
+
+        match RestoreTokenStore::default_location() {
+            Some(store) => {
+                store.clear()?;
+                println!(
+                    "Forgot saved screencast grants ({})",
+                    store.path().display()
+                );
+            }
+            None => println!("No state directory found; nothing to forget"),
+        }
+    }
+
+    #[cfg(not(all(feature = "wayland-pipe", target_os = "linux")))]
+    println!("Portal grants are only saved on Wayland; nothing to forget");
+
+    Ok(())
+}
+
+/// Prints the capturable displays or windows, one per line or as a JSON array.
+///
+/// Time complexity: O(n) for n displays or windows, plus the backend queries.
+fn list_targets(target: ListTarget, json: bool) -> Result<()> {
+    use hybrid_screen_capture::capture::{enumerate_displays, enumerate_windows};
+
+    let (lines, values): (Vec<String>, Vec<serde_json::Value>) = match target {
+        ListTarget::Displays => enumerate_displays()?
+            .iter()
+            .map(|display| (display.to_string(), display.to_json()))
+            .unzip(),
+        ListTarget::Windows => enumerate_windows()?
+            .iter()
+            .map(|window| (window.to_string(), window.to_json()))
+            .unzip(),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&values)?);
+    } else {
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
 /// Runs session-based capture using CaptureSessionBuilder.
 ///
 /// This function demonstrates the new session-based capture architecture
@@ -169,7 +595,7 @@ async fn main() -> Result<()> {
 /// support for scaling presets and Gundam tiling.
 #[cfg(feature = "rtsp-streaming")]
 async fn run_session_capture(args: Args, _config: CaptureConfig) -> Result<()> {
-    use hybrid_screen_capture::session::CaptureSessionBuilder;
+    use hybrid_screen_capture::session::CaptureSource;
     use std::sync::Arc;
 
     println!("Starting session-based capture mode...");
@@ -184,49 +610,407 @@ async fn run_session_capture(args: Args, _config: CaptureConfig) -> Result<()> {
         4,                   // max 4 buffers
     ));
 
-    // Build session with capture source
-    let mut session_builder = CaptureSessionBuilder::new();
+    // Add platform-specific capture source(s), or the test-pattern generator
+    let sources: Vec<Box<dyn CaptureSource>> = if args.source == SourceKind::Synthetic {
+        use hybrid_screen_capture::capture::session_sources::SyntheticCaptureSource;
+        let (width, height) = args.synthetic_size;
+        let mut source = SyntheticCaptureSource::new(width, height)
+            .with_pattern(args.pattern)
+            .with_framerate(args.fps);
+        if let Some(region) = args.region {
+            source = source.with_region(region)?;
+        }
+        vec![Box::new(source)]
+    } else {
+        #[cfg(any(target_os = "windows", target_os = "macos"))]
+        {
+            use crate::capture::session_sources::ScrapCaptureSource;
+            if args.window {
+                return Err(hybrid_screen_capture::error::CaptureError::validation(
+                    "window",
+                    "is not supported by the scrap session source; drop --session",
+                    "--window",
+                )
+                .into());
+            }
+            let mut source = ScrapCaptureSource::new()?;
+            if let Some(region) = args.region {
+                source = source.with_region(region)?;
+            }
+            vec![Box::new(source)]
+        }
 
-    // Add platform-specific capture source
-    #[cfg(any(target_os = "windows", target_os = "macos"))]
-    {
-        use crate::capture::session_sources::ScrapCaptureSource;
-        let capture_source = ScrapCaptureSource::new()?;
-        session_builder = session_builder.with_capture_source(capture_source);
-    }
+        #[cfg(target_os = "linux")]
+        linux_session_sources(&args).await?
+    };
 
-    #[cfg(target_os = "linux")]
-    {
-        use hybrid_screen_capture::capture::session_sources::FFmpegCaptureSource;
-        let capture_source = FFmpegCaptureSource::new(":0.0")?;
-        session_builder = session_builder.with_capture_source(capture_source);
+    // Several portal streams: one composite session or one session per stream
+    let sources = if sources.len() > 1 && args.monitor_layout == MonitorLayout::Composite {
+        use hybrid_screen_capture::capture::session_sources::CompositeCaptureSource;
+        println!("Compositing {} streams side by side", sources.len());
+        let composite: Box<dyn CaptureSource> =
+            Box::new(CompositeCaptureSource::side_by_side(sources));
+        vec![composite]
+    } else {
+        sources
+    };
+
+    let numbered = sources.len() > 1;
+    let mut sessions = Vec::with_capacity(sources.len());
+    for (index, source) in sources.into_iter().enumerate() {
+        sessions.push(build_session(&args, source, numbered.then_some(index)).await?);
     }
 
+    // Run all sessions concurrently
+    futures_util::future::try_join_all(sessions.into_iter().map(|session| session.run())).await?;
+    Ok(())
+}
+
+/// Builds one capture session around `capture_source` from the CLI options.
+///
+/// With an `index`, the output is numbered so several sessions can run side by
+/// side: `capture.mp4` becomes `capture-1.mp4`, `capture-2.mp4`, ... and RTSP
+/// ports count up from `--rtsp-port`.
+///
+/// Time complexity: O(1) - initializes the source and configures the pipeline.
+#[cfg(feature = "rtsp-streaming")]
+async fn build_session(
+    args: &Args,
+    mut capture_source: Box<dyn hybrid_screen_capture::session::CaptureSource>,
+    index: Option<usize>,
+) -> Result<hybrid_screen_capture::session::CaptureSession> {
+    use hybrid_screen_capture::session::CaptureSessionBuilder;
+
+    let mut session_builder = CaptureSessionBuilder::new();
+
+    // Initialize up front: some sources only know their size once running
+    capture_source.initialize().await?;
+
+    // Stream dimensions follow the capture source unless a processor changes them
+    let mut stream_size = capture_source.input_size();
+    session_builder = session_builder.with_capture_source(capture_source);
+
     // Add processing if requested
-    if let Some(preset) = args.scale_preset {
-        session_builder = session_builder.with_scaling(preset);
+    if let Some(spec) = scale_spec(args) {
+        let plan = spec.plan(cap_scale::presets::Size {
+            w: stream_size.w,
+            h: stream_size.h,
+        });
+        stream_size.w = plan.out.w;
+        stream_size.h = plan.out.h;
+        session_builder = session_builder.with_scaling(spec);
     }
 
+    if let Some(budget) = token_budget(args) {
+        use cap_scale::presets::{AspectMode, ScaleTarget, Size};
+        use cap_scale::tokens::{BudgetTracker, LONG_SIDE_LADDER, fit_plan};
+        // Streams start at the size of the first frame; later sizes are negotiated
+        let model = args.token_model.unwrap_or(TokenModelKind::Qwen).model();
+        let first = fit_plan(
+            model,
+            Size {
+                w: stream_size.w,
+                h: stream_size.h,
+            },
+            &LONG_SIDE_LADDER.map(ScaleTarget::MaxLongSide),
+            AspectMode::Preserve,
+            BudgetTracker::new(budget).allowance(),
+        );
+        if let Some((plan, _)) = first {
+            stream_size.w = plan.out.w;
+            stream_size.h = plan.out.h;
+        }
+        session_builder = session_builder.with_adaptive_scaling(Box::new(model), budget);
+    }
+
+    print_token_estimate(
+        args,
+        cap_scale::presets::Size {
+            w: stream_size.w,
+            h: stream_size.h,
+        },
+    );
+
     if args.gundam {
-        session_builder = session_builder.with_gundam();
+        use hybrid_screen_capture::processing::GundamProcessor;
+        session_builder = session_builder.with_gundam_processor(
+            GundamProcessor::new(gundam_cfg(args))
+                .with_threads(args.gundam_threads as usize)
+                .with_layout(composite_layout(args)),
+        );
+    }
+
+    session_builder = session_builder.with_unchanged_frames(args.unchanged_frames.into());
+
+    if args.draw_cursor || args.highlight_clicks {
+        use hybrid_screen_capture::processing::{ClickHighlight, CursorOverlayProcessor};
+        let mut overlay = CursorOverlayProcessor::new();
+        if args.highlight_clicks {
+            overlay = overlay.with_click_highlight(ClickHighlight::default());
+        }
+        session_builder = session_builder.with_cursor_overlay(overlay);
     }
 
     // Add output streams
     if args.rtsp {
         // Create RTSP stream using the builder method
-        session_builder = session_builder.with_rtsp_stream(args.rtsp_port, 1920, 1080, args.fps);
+        let offset = index.unwrap_or(0);
+        let port = u16::try_from(offset)
+            .ok()
+            .and_then(|offset| args.rtsp_port.checked_add(offset))
+            .ok_or_else(|| {
+                hybrid_screen_capture::error::CaptureError::validation(
+                    "rtsp_port",
+                    format!("must leave room for {} consecutive ports", offset + 1),
+                    args.rtsp_port.to_string(),
+                )
+            })?;
+        session_builder =
+            session_builder.with_rtsp_stream(port, stream_size.w, stream_size.h, args.fps);
     } else {
         // For file output, use the builder method
-        let output = args
+        let mut output = args
             .output_flag
             .clone()
             .unwrap_or_else(|| args.output.clone());
-        session_builder = session_builder.with_file_output(output, 1920, 1080, args.fps);
+        if let Some(index) = index {
+            output = numbered_output(&output, index + 1);
+        }
+        session_builder =
+            session_builder.with_file_output(output, stream_size.w, stream_size.h, args.fps);
+    }
+
+    session_builder.build()
+}
+
+/// Scaling from `--scale-preset` or `--scale-size` with `--aspect`,
+/// `--pad-color` and `--resize-filter`, or `None` without a target.
+#[cfg(feature = "rtsp-streaming")]
+fn scale_spec(args: &Args) -> Option<cap_scale::presets::ScaleSpec> {
+    use cap_scale::presets::{AspectMode, ScaleSpec, ScaleTarget, Size};
+    let target = match (args.scale_preset, args.scale_size) {
+        (Some(preset), _) => preset.to_target(),
+        (None, Some((w, h))) => ScaleTarget::Exact(Size { w, h }),
+        (None, None) => return None,
+    };
+    let aspect = match args.aspect {
+        AspectChoice::Preserve => AspectMode::Preserve,
+        AspectChoice::Distort => AspectMode::Distort,
+        AspectChoice::Pad => AspectMode::Pad {
+            bg_rgba: args.pad_color,
+        },
+    };
+    Some(
+        ScaleSpec::new(target)
+            .with_aspect(aspect)
+            .with_quality(resize_quality(args)),
+    )
+}
+
+/// Resize quality from `--resize-filter`, `--gamma-correct` and
+/// `--ocr-sharpen`, used for scaling and Gundam tiles.
+#[cfg(feature = "rtsp-streaming")]
+fn resize_quality(args: &Args) -> cap_scale::presets::ResizeQuality {
+    use cap_scale::presets::{GammaMode, ResizeQuality, UnsharpMask};
+    ResizeQuality {
+        filter: args.resize_filter,
+        gamma: if args.gamma_correct {
+            GammaMode::Linear
+        } else {
+            GammaMode::Srgb
+        },
+        sharpen: args.ocr_sharpen.then_some(UnsharpMask::MILD),
+    }
+}
+
+/// Gundam tiling configuration from `--gundam-grid`, `--gundam-skip-blank`
+/// and the resize quality flags.
+#[cfg(feature = "rtsp-streaming")]
+fn gundam_cfg(args: &Args) -> cap_scale::gundam::GundamCfg {
+    use cap_scale::gundam::{GundamCfg, TileSelection};
+    GundamCfg {
+        quality: resize_quality(args),
+        selection: if args.gundam_skip_blank {
+            TileSelection::SKIP_BLANK
+        } else {
+            TileSelection::RowMajor
+        },
+        grid: args.gundam_grid,
+        ..Default::default()
+    }
+}
+
+/// Gundam mosaic layout from `--gundam-layout` and `--gundam-background`.
+#[cfg(feature = "rtsp-streaming")]
+fn composite_layout(args: &Args) -> cap_rtsp::CompositeLayout {
+    use cap_rtsp::{CompositeLayout, GlobalPlacement};
+    CompositeLayout {
+        global: match args.gundam_layout {
+            GundamLayoutChoice::Last => GlobalPlacement::Last,
+            GundamLayoutChoice::First => GlobalPlacement::First,
+            GundamLayoutChoice::Beside => GlobalPlacement::Beside,
+        },
+        bg_rgba: args.gundam_background,
+    }
+}
+
+/// Token budget from `--max-frame-tokens` or `--max-tokens-per-minute`.
+#[cfg(feature = "rtsp-streaming")]
+fn token_budget(args: &Args) -> Option<cap_scale::tokens::TokenBudget> {
+    use cap_scale::tokens::TokenBudget;
+    match (args.max_frame_tokens, args.max_tokens_per_minute) {
+        (Some(tokens), _) => Some(TokenBudget::PerFrame(tokens)),
+        (None, Some(tokens)) => Some(TokenBudget::PerMinute {
+            tokens,
+            fps: args.fps,
+        }),
+        (None, None) => None,
+    }
+}
+
+/// Prints the vision tokens each frame of a session will cost, per model
+/// family, for frames of `size` entering Gundam tiling or sent as they are.
+///
+/// Time complexity: O(models) - one estimate per model family.
+#[cfg(feature = "rtsp-streaming")]
+fn print_token_estimate(args: &Args, size: cap_scale::presets::Size) {
+    use cap_scale::tokens::StreamEstimate;
+    use clap::ValueEnum;
+
+    let kinds = match args.token_model {
+        Some(kind) => vec![kind],
+        None => TokenModelKind::value_variants().to_vec(),
+    };
+    let layout = if args.gundam {
+        "Gundam tiles + global view".to_string()
+    } else {
+        format!("{}x{} frames", size.w, size.h)
+    };
+    let bound = if args.unchanged_frames == UnchangedFrameMode::Skip {
+        ", upper bound as unchanged frames are skipped"
+    } else {
+        ""
+    };
+    println!(
+        "Estimated vision tokens ({} at {} fps{}):",
+        layout, args.fps, bound
+    );
+    for kind in kinds {
+        let model = kind.model();
+        let per_frame = if args.gundam {
+            model.gundam_tokens(size, &gundam_cfg(args))
+        } else {
+            model.image_tokens(size)
+        };
+        let stream = StreamEstimate::new(per_frame, args.fps as f64);
+        println!(
+            "  {:<13} {:>6} per frame, {:>11.0} per minute",
+            model.name(),
+            per_frame,
+            stream.tokens_per_minute()
+        );
+    }
+}
+
+/// Inserts `-<n>` before the file extension: `capture.mp4` → `capture-2.mp4`.
+#[cfg(feature = "rtsp-streaming")]
+fn numbered_output(path: &str, n: usize) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, n, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, n),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Picks the session capture sources for the current Linux desktop.
+///
+/// Wayland sessions go through the XDG Desktop Portal (PipeWire), with one
+/// source per granted stream when `--multi-monitor` is set; everything else
+/// uses x11grab on `$DISPLAY`, which already spans all X11 monitors.
+#[cfg(all(feature = "rtsp-streaming", target_os = "linux"))]
+async fn linux_session_sources(
+    args: &Args,
+) -> Result<Vec<Box<dyn hybrid_screen_capture::session::CaptureSource>>> {
+    use hybrid_screen_capture::capture::session_sources::FFmpegCaptureSource;
+
+    #[cfg(feature = "wayland-pipe")]
+    if hybrid_screen_capture::is_wayland_session() {
+        use ashpd::desktop::screencast::{CursorMode, SourceType};
+        use hybrid_screen_capture::capture::session_sources::PipeWireCaptureSource;
+
+        if args.window_title.is_some() || args.window_class.is_some() || args.window_pid.is_some() {
+            return Err(hybrid_screen_capture::error::CaptureError::validation(
+                "window",
+                "cannot be preselected on Wayland; the portal dialog picks the window",
+                window_selector(args)?.to_string(),
+            )
+            .into());
+        }
+        let source_type = if args.window {
+            SourceType::Window
+        } else {
+            SourceType::Monitor
+        };
+        let mut source = PipeWireCaptureSource::new()
+            .with_source_type(source_type)
+            .with_framerate(args.fps);
+        // The overlay draws the cursor, so keep the compositor from embedding
+        // a second one
+        if args.draw_cursor || args.highlight_clicks {
+            source = source.with_cursor_mode(CursorMode::Metadata);
+        }
+        if let Some(region) = args.region {
+            source = source.with_region(region);
+        }
+
+        if !args.multi_monitor {
+            return Ok(vec![Box::new(source)]);
+        }
+
+        let sources = source.open_multiple().await?;
+        for (index, source) in sources.iter().enumerate() {
+            if let Some(stream) = source.stream() {
+                println!(
+                    "Stream {}: node {} at {:?}, logical size {:?}",
+                    index + 1,
+                    stream.node_id,
+                    stream.position,
+                    stream.size
+                );
+            }
+        }
+        return Ok(sources
+            .into_iter()
+            .map(|source| {
+                Box::new(source) as Box<dyn hybrid_screen_capture::session::CaptureSource>
+            })
+            .collect());
     }
 
-    // Build and run the session
-    let session = session_builder.build()?;
-    session.run().await
+    let display = std::env::var("DISPLAY").unwrap_or(":0".to_string());
+    if args.window {
+        use hybrid_screen_capture::capture::session_sources::{
+            X11CaptureTarget, X11ShmCaptureSource,
+        };
+
+        let window = window_selector(args)?.resolve_x11(&display)?;
+        println!("Capturing window {}", window);
+        let mut source =
+            X11ShmCaptureSource::with_target(&display, X11CaptureTarget::Window(window.id as u32))?
+                .with_framerate(args.fps);
+        if let Some(region) = args.region {
+            source = source.with_region(region)?;
+        }
+        return Ok(vec![Box::new(source)]);
+    }
+
+    let mut source = FFmpegCaptureSource::new(&display)?.with_framerate(args.fps);
+    if let Some(region) = args.region {
+        source = source.with_region(region)?;
+    }
+    Ok(vec![Box::new(source)])
 }
 
 /// Runs the application in RTSP streaming mode.
@@ -340,7 +1124,7 @@ fn capture_to_rtsp(rtsp_publisher: RtspPublisher, args: Args) -> Result<()> {
 /// - Composite arrangement: O(total_pixels) where total_pixels depends on grid layout
 ///
 /// Missing functionality:
-/// - Window capture mode is interactive (requires user input) - could be improved with GUI selection
+/// - Window selection matches titles only (scrap reports no class or pid)
 /// - Gundam mode composite arrangement could be optimized for better cache locality
 #[cfg(all(
     feature = "rtsp-streaming",
@@ -351,30 +1135,8 @@ fn capture_scrap_rtsp(rtsp_publisher: RtspPublisher, args: Args) -> Result<()> {
     use std::thread;
 
     let (w, h, mut cap) = if args.window {
-        let windows = scrap::Window::all().context("failed to list windows")?;
-        if windows.is_empty() {
-            return Err(hybrid_screen_capture::error::CaptureError::resource(
-                "windows",
-                "no windows found for capture",
-            ));
-        }
-        println!("Available windows:");
-        for (i, w) in windows.iter().enumerate() {
-            println!("{}: {}", i, w.title());
-        }
-        println!("Enter the number of the window to capture:");
-        let mut input = String::new();
-        std::io::stdin()
-            .read_line(&mut input)
-            .context("failed to read input")?;
-        let index: usize = input.trim().parse().context("invalid number")?;
-        if index >= windows.len() {
-            return Err(anyhow::anyhow!("invalid window index"));
-        }
-        let window = windows
-            .into_iter()
-            .nth(index)
-            .ok_or_else(|| anyhow::anyhow!("window index {} out of bounds", index))?;
+        let (info, window) = window_selector(&args)?.resolve_scrap()?;
+        println!("Capturing window {}", info);
         let w = window.width();
         let h = window.height();
         let cap = Capturer::new(window).context("cannot create capturer for window")?;
@@ -531,6 +1293,8 @@ fn capture_scrap_rtsp(rtsp_publisher: RtspPublisher, args: Args) -> Result<()> {
                         .map(|s| s.w as usize * 4)
                         .unwrap_or(w as usize * 4),
                     pts_ns: None, // Let RTSP handle timing
+                    cursor: None,
+                    meta: FrameMeta::default(),
                 };
 
                 // Send to RTSP (non-blocking)
@@ -717,6 +1481,8 @@ fn capture_x11_rtsp(rtsp_publisher: RtspPublisher, args: Args) -> Result<()> {
                             .map(|s| s.w as usize * 4)
                             .unwrap_or(w as usize * 4),
                         pts_ns: None, // Let RTSP handle timing
+                        cursor: None,
+                        meta: FrameMeta::default(),
                     };
 
                     // Send to RTSP (non-blocking)
@@ -748,63 +1514,35 @@ fn capture_x11_rtsp(rtsp_publisher: RtspPublisher, args: Args) -> Result<()> {
     }
 }
 
-/// Generates synthetic frames for RTSP streaming when screen capture is unavailable.
+/// Streams test-pattern frames via RTSP when screen capture is unavailable.
 ///
 /// Time complexity: O(n) where n is the number of frames generated until interruption.
-/// Each frame generation is O(width * height) due to the nested loops creating
-/// the gradient pattern.
+/// Frames come from a live `videotestsrc` pipeline, which also paces them to `--fps`.
 ///
 /// Missing functionality:
 /// - This is a fallback implementation - real screen capture should be preferred
-/// - Could add more sophisticated synthetic patterns or test signals
+/// - Frames are always 1920x1080 and are not scaled to match the server size
 #[cfg(all(target_os = "linux", feature = "rtsp-streaming"))]
 fn capture_x11_synthetic_rtsp(rtsp_publisher: RtspPublisher, args: Args) -> Result<()> {
+    use cap_rtsp::test_pattern::{TestPatternConfig, TestPatternSource};
+
     // Fallback to synthetic frames when scrap is not available
     println!("X11 RTSP streaming not available, using synthetic frames for demonstration...");
 
-    let frame_time = Duration::from_secs_f64(1.0 / args.fps as f64);
-    let mut frame_idx = 0u64;
+    let source = TestPatternSource::start(TestPatternConfig {
+        width: 1920,
+        height: 1080,
+        framerate: args.fps,
+        pattern: args.pattern,
+        ..Default::default()
+    })?;
 
-    // Generate synthetic BGRA frames directly
     loop {
-        let width = 1920u32;
-        let height = 1080u32;
-        let mut data = vec![0u8; (width * height * 4) as usize];
-
-        // Create a moving gradient pattern
-        let time = frame_idx as f32 / args.fps as f32;
-        let wave_speed = 1.0;
-
-        for y in 0..height {
-            for x in 0..width {
-                let idx = ((y * width + x) * 4) as usize;
-
-                // Moving wave pattern
-                let wave =
-                    ((x as f32 * 0.005 + y as f32 * 0.005 + time * wave_speed).sin() + 1.0) * 0.5;
-
-                // BGRA format
-                data[idx] = (wave * 255.0) as u8; // Blue
-                data[idx + 1] = ((1.0 - wave) * 255.0) as u8; // Green
-                data[idx + 2] = (wave * 0.5 * 255.0) as u8; // Red
-                data[idx + 3] = 255; // Alpha
-            }
-        }
-
-        let frame = BgraFrame {
-            data: Arc::new(data),
-            width,
-            height,
-            stride: width as usize * 4,
-            pts_ns: Some(frame_idx * (1_000_000_000u64 / args.fps as u64)),
-        };
-
+        let frame = source.next_frame()?;
         if let Err(e) = rtsp_publisher.send(frame) {
             eprintln!("Failed to send frame to RTSP: {}", e);
             break;
         }
-        frame_idx += 1;
-        std::thread::sleep(frame_time);
     }
 
     Ok(())
@@ -945,3 +1683,121 @@ fn parse_quality(quality: &str) -> Result<u8> {
         )),
     }
 }
+
+/// Builds the window selector from `--window-title`, `--window-class` and `--window-pid`.
+///
+/// # Errors
+///
+/// Returns a validation error if the title is not a valid regular expression.
+///
+/// # Performance Characteristics
+///
+/// **Time complexity**: O(pattern length) - Compiles the title regex.
+fn window_selector(
+    args: &Args,
+) -> Result<hybrid_screen_capture::capture::window_select::WindowSelector> {
+    let mut selector = hybrid_screen_capture::capture::window_select::WindowSelector::new();
+    if let Some(title) = &args.window_title {
+        selector = selector.with_title(title)?;
+    }
+    if let Some(class) = &args.window_class {
+        selector = selector.with_class(class);
+    }
+    if let Some(pid) = args.window_pid {
+        selector = selector.with_pid(pid);
+    }
+    Ok(selector)
+}
+
+/// Parse a frame size like "1280x720" into `(width, height)`.
+///
+/// # Errors
+///
+/// Returns an error if the string is not two positive integers separated by `x`.
+///
+/// # Performance Characteristics
+///
+/// **Time complexity**: O(1) - Splits and parses two short numbers.
+fn parse_size(size: &str) -> Result<(u32, u32)> {
+    let invalid = || {
+        anyhow::Error::from(hybrid_screen_capture::error::CaptureError::validation(
+            "size",
+            "WIDTHxHEIGHT with non-zero dimensions",
+            size,
+        ))
+    };
+
+    let (w, h) = size.split_once(['x', 'X']).ok_or_else(invalid)?;
+    match (w.trim().parse::<u32>(), h.trim().parse::<u32>()) {
+        (Ok(w), Ok(h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parses an `RRGGBB` or `RRGGBBAA` hex color (optionally prefixed with `#`)
+/// into RGBA bytes; without alpha the color is opaque.
+///
+/// # Errors
+///
+/// Returns an error if the string is not 6 or 8 hex digits.
+///
+/// # Performance Characteristics
+///
+/// **Time complexity**: O(1) - Parses at most four bytes.
+fn parse_color(color: &str) -> Result<[u8; 4]> {
+    let invalid = || {
+        anyhow::Error::from(hybrid_screen_capture::error::CaptureError::validation(
+            "color",
+            "RRGGBB or RRGGBBAA hex digits",
+            color,
+        ))
+    };
+
+    let hex = color.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut rgba = [0, 0, 0, 255];
+    for (i, byte) in rgba.iter_mut().enumerate().take(hex.len() / 2) {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(rgba)
+}
+
+/// Parses a Gundam grid strategy: `auto`, `aspect`, `COLSxROWS` or
+/// `density:SCALE` with a positive scale.
+///
+/// # Errors
+///
+/// Returns an error if the string is none of these forms.
+///
+/// # Performance Characteristics
+///
+/// **Time complexity**: O(1) - Matches a keyword or parses two short numbers.
+fn parse_grid(grid: &str) -> Result<cap_scale::gundam::GridStrategy> {
+    use cap_scale::gundam::GridStrategy;
+    let invalid = || {
+        anyhow::Error::from(hybrid_screen_capture::error::CaptureError::validation(
+            "grid",
+            "auto, aspect, COLSxROWS or density:SCALE",
+            grid,
+        ))
+    };
+
+    match grid.trim() {
+        "auto" => Ok(GridStrategy::Heuristic),
+        "aspect" => Ok(GridStrategy::AspectRatio),
+        spec => match spec.strip_prefix("density:") {
+            Some(scale) => match scale.trim().parse::<f32>() {
+                Ok(scale) if scale.is_finite() && scale > 0.0 => {
+                    Ok(GridStrategy::Density { scale })
+                }
+                _ => Err(invalid()),
+            },
+            None => {
+                let (cols, rows) = parse_size(spec).map_err(|_| invalid())?;
+                Ok(GridStrategy::Fixed { cols, rows })
+            }
+        },
+    }
+}
@@ -0,0 +1,377 @@
+//! # Cursor Overlay
+//!
+//! Draws the pointer reported in [`BgraFrame::cursor`] into the frame pixels.
+//! Capture backends disagree on whether the cursor is part of the image: the
+//! Wayland portal embeds it, X11 and scrap grabs never contain it. Sources that
+//! report cursor metadata instead can have it composited here, so recordings
+//! look the same everywhere.
+//!
+//! ## Click Highlight
+//!
+//! With a [`ClickHighlight`], a ring is drawn around the hotspot while a mouse
+//! button is held and for a few frames after each press, so replays of agent
+//! sessions show where a click landed even when it was shorter than a frame.
+//!
+//! ## Cost
+//!
+//! Frames without a visible cursor pass through untouched. Otherwise only the
+//! cursor and ring bounding boxes are touched, but the frame buffer is copied
+//! first when it is shared with another consumer.
+
+use crate::processing::{FrameProcessor, Size};
+use anyhow::Result;
+use async_trait::async_trait;
+use cap_rtsp::{BgraFrame, CursorImage, CursorInfo};
+use std::sync::Arc;
+
+/// Ring drawn around the cursor hotspot when a mouse button is pressed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClickHighlight {
+    /// Ring radius in pixels, measured to the middle of the stroke.
+    pub radius: f32,
+    /// Stroke width in pixels.
+    pub thickness: f32,
+    /// Straight-alpha BGRA color.
+    pub color: [u8; 4],
+    /// Frames the ring stays visible after a press starts, even if the button
+    /// is released sooner.
+    pub hold_frames: u32,
+}
+
+impl Default for ClickHighlight {
+    fn default() -> Self {
+        Self {
+            radius: 18.0,
+            thickness: 3.0,
+            color: [0, 200, 255, 220],
+            hold_frames: 6,
+        }
+    }
+}
+
+/// Composites the cursor image, and optionally a click ring, into each frame.
+///
+/// Place it before any scaling so the cursor is drawn at capture resolution;
+/// [`CaptureSessionBuilder::with_cursor_overlay`](crate::session::CaptureSessionBuilder::with_cursor_overlay)
+/// does that automatically. When a source reports a position without a shape,
+/// a plain arrow is drawn.
+///
+/// ```rust
+/// use hybrid_screen_capture::processing::{ClickHighlight, CursorOverlayProcessor};
+///
+/// let overlay = CursorOverlayProcessor::new().with_click_highlight(ClickHighlight::default());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CursorOverlayProcessor {
+    highlight: Option<ClickHighlight>,
+    fallback: Arc<CursorImage>,
+    was_pressed: bool,
+    /// Where the current ring is drawn and for how many more frames.
+    ring: Option<(i32, i32, u32)>,
+}
+
+impl Default for CursorOverlayProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CursorOverlayProcessor {
+    /// Overlay drawing the cursor only.
+    pub fn new() -> Self {
+        Self {
+            highlight: None,
+            fallback: Arc::new(arrow_cursor()),
+            was_pressed: false,
+            ring: None,
+        }
+    }
+
+    /// Also draw `highlight` around the hotspot on clicks.
+    pub fn with_click_highlight(mut self, highlight: ClickHighlight) -> Self {
+        self.highlight = Some(highlight);
+        self
+    }
+
+    /// Advances the click ring state for `cursor` and returns where to draw it.
+    fn update_ring(&mut self, cursor: &CursorInfo) -> Option<(i32, i32)> {
+        let highlight = self.highlight?;
+        if cursor.pressed && !self.was_pressed {
+            self.ring = Some((cursor.x, cursor.y, highlight.hold_frames));
+        }
+        self.was_pressed = cursor.pressed;
+
+        if cursor.pressed {
+            // Follow drags while the button is held
+            return Some((cursor.x, cursor.y));
+        }
+        let (x, y, frames) = self.ring.take()?;
+        if frames > 0 {
+            self.ring = Some((x, y, frames - 1));
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl FrameProcessor for CursorOverlayProcessor {
+    async fn initialize(&mut self, input_size: Size) -> Result<Size> {
+        self.was_pressed = false;
+        self.ring = None;
+        Ok(input_size)
+    }
+
+    /// Draws the cursor into `frame` if it is visible.
+    ///
+    /// Time complexity: O(cursor area + ring area), plus O(width * height) when
+    /// the pixel buffer is shared and has to be copied.
+    async fn process_frame(&mut self, mut frame: BgraFrame) -> Result<Option<BgraFrame>> {
+        let Some(cursor) = frame.cursor.clone() else {
+            return Ok(Some(frame));
+        };
+        let ring = self.update_ring(&cursor);
+        if !cursor.visible && ring.is_none() {
+            return Ok(Some(frame));
+        }
+
+        let (width, height, stride) = (frame.width, frame.height, frame.stride);
+        let data = Arc::make_mut(&mut frame.data);
+        if let (Some((x, y)), Some(highlight)) = (ring, self.highlight) {
+            draw_ring(data, width, height, stride, x, y, &highlight);
+        }
+        if cursor.visible {
+            let image = cursor.image.as_deref().unwrap_or(&self.fallback);
+            draw_cursor(data, width, height, stride, cursor.x, cursor.y, image);
+        }
+        Ok(Some(frame))
+    }
+}
+
+/// Blends the premultiplied cursor `image` with its hotspot at (`x`, `y`).
+fn draw_cursor(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: usize,
+    x: i32,
+    y: i32,
+    image: &CursorImage,
+) {
+    let left = x - image.hot_x as i32;
+    let top = y - image.hot_y as i32;
+    for iy in 0..image.height as i32 {
+        let fy = top + iy;
+        if fy < 0 || fy >= height as i32 {
+            continue;
+        }
+        for ix in 0..image.width as i32 {
+            let fx = left + ix;
+            if fx < 0 || fx >= width as i32 {
+                continue;
+            }
+            let src = &image.data[(iy * image.width as i32 + ix) as usize * 4..][..4];
+            let alpha = src[3] as u32;
+            if alpha == 0 {
+                continue;
+            }
+            let dst = &mut data[fy as usize * stride + fx as usize * 4..][..4];
+            for (d, &s) in dst[..3].iter_mut().zip(&src[..3]) {
+                *d = (s as u32 + *d as u32 * (255 - alpha) / 255).min(255) as u8;
+            }
+        }
+    }
+}
+
+/// Blends an anti-aliased ring centered on (`cx`, `cy`).
+fn draw_ring(
+    data: &mut [u8],
+    width: u32,
+    height: u32,
+    stride: usize,
+    cx: i32,
+    cy: i32,
+    highlight: &ClickHighlight,
+) {
+    let half = highlight.thickness / 2.0;
+    let reach = (highlight.radius + half + 1.0).ceil() as i32;
+    let alpha = highlight.color[3] as f32 / 255.0;
+
+    for y in (cy - reach).max(0)..(cy + reach + 1).min(height as i32) {
+        for x in (cx - reach).max(0)..(cx + reach + 1).min(width as i32) {
+            let dx = (x - cx) as f32;
+            let dy = (y - cy) as f32;
+            let distance = (dx * dx + dy * dy).sqrt();
+            // One-pixel soft edge on both sides of the stroke
+            let coverage = (half + 0.5 - (distance - highlight.radius).abs()).clamp(0.0, 1.0);
+            if coverage == 0.0 {
+                continue;
+            }
+            let weight = alpha * coverage;
+            let dst = &mut data[y as usize * stride + x as usize * 4..][..4];
+            for (d, &c) in dst[..3].iter_mut().zip(&highlight.color[..3]) {
+                *d = (*d as f32 + (c as f32 - *d as f32) * weight).round() as u8;
+            }
+        }
+    }
+}
+
+/// Black-outlined white arrow with its hotspot at the tip, used when the source
+/// reports no cursor shape.
+fn arrow_cursor() -> CursorImage {
+    const W: i32 = 12;
+    const H: i32 = 19;
+    // Tip at the origin, left edge straight down, right edge towards (11, 11),
+    // tail back to (0, 16)
+    let inside = |x: i32, y: i32| {
+        (0..W).contains(&x)
+            && (0..H).contains(&y)
+            && x <= y
+            && (y <= 11 || x * 5 <= (16 - y) * 11)
+            && y <= 16
+    };
+
+    let mut data = Vec::with_capacity((W * H * 4) as usize);
+    for y in 0..H {
+        for x in 0..W {
+            let px = if !inside(x, y) {
+                [0, 0, 0, 0]
+            } else if [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .iter()
+                .all(|&(nx, ny)| inside(nx, ny))
+            {
+                [255, 255, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            };
+            data.extend_from_slice(&px);
+        }
+    }
+    CursorImage {
+        width: W as u32,
+        height: H as u32,
+        hot_x: 0,
+        hot_y: 0,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cap_rtsp::FrameMeta;
+
+    fn frame(w: u32, h: u32, cursor: Option<CursorInfo>) -> BgraFrame {
+        BgraFrame {
+            data: Arc::new(vec![0; (w * h * 4) as usize]),
+            width: w,
+            height: h,
+            stride: w as usize * 4,
+            pts_ns: None,
+            cursor,
+            meta: FrameMeta::default(),
+        }
+    }
+
+    fn cursor(x: i32, y: i32, pressed: bool) -> CursorInfo {
+        CursorInfo {
+            x,
+            y,
+            visible: true,
+            pressed,
+            image: Some(Arc::new(CursorImage {
+                width: 2,
+                height: 2,
+                hot_x: 1,
+                hot_y: 1,
+                data: [255, 255, 255, 255].repeat(4),
+            })),
+        }
+    }
+
+    fn pixel(frame: &BgraFrame, x: u32, y: u32) -> [u8; 4] {
+        let i = y as usize * frame.stride + x as usize * 4;
+        frame.data[i..i + 4].try_into().unwrap()
+    }
+
+    #[tokio::test]
+    async fn draws_cursor_at_hotspot_and_clips() -> Result<()> {
+        let mut overlay = CursorOverlayProcessor::new();
+        let out = overlay
+            .process_frame(frame(8, 8, Some(cursor(4, 4, false))))
+            .await?
+            .unwrap();
+        // 2x2 image with hotspot (1, 1) covers (3..5, 3..5)
+        assert_eq!(pixel(&out, 3, 3), [255, 255, 255, 0]);
+        assert_eq!(pixel(&out, 4, 4), [255, 255, 255, 0]);
+        assert_eq!(pixel(&out, 5, 5), [0, 0, 0, 0]);
+
+        // Partly outside the frame: only the visible corner is drawn
+        let out = overlay
+            .process_frame(frame(8, 8, Some(cursor(0, 0, false))))
+            .await?
+            .unwrap();
+        assert_eq!(pixel(&out, 0, 0), [255, 255, 255, 0]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn hidden_cursor_leaves_frame_untouched() -> Result<()> {
+        let mut overlay = CursorOverlayProcessor::new();
+        let input = frame(
+            8,
+            8,
+            Some(CursorInfo {
+                visible: false,
+                ..cursor(4, 4, false)
+            }),
+        );
+        let data = input.data.clone();
+        let out = overlay.process_frame(input).await?.unwrap();
+        assert!(Arc::ptr_eq(&out.data, &data));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn click_ring_outlasts_short_press() -> Result<()> {
+        let highlight = ClickHighlight {
+            radius: 10.0,
+            thickness: 2.0,
+            color: [0, 0, 255, 255],
+            hold_frames: 2,
+        };
+        let mut overlay = CursorOverlayProcessor::new().with_click_highlight(highlight);
+        let ring_px = |frame: &BgraFrame| pixel(frame, 30, 20);
+
+        let pressed = overlay
+            .process_frame(frame(64, 64, Some(cursor(20, 20, true))))
+            .await?
+            .unwrap();
+        assert_eq!(ring_px(&pressed), [0, 0, 255, 0]);
+
+        // Released: the ring stays for hold_frames frames, then disappears
+        for _ in 0..2 {
+            let held = overlay
+                .process_frame(frame(64, 64, Some(cursor(40, 40, false))))
+                .await?
+                .unwrap();
+            assert_eq!(ring_px(&held), [0, 0, 255, 0]);
+        }
+        let gone = overlay
+            .process_frame(frame(64, 64, Some(cursor(40, 40, false))))
+            .await?
+            .unwrap();
+        assert_eq!(ring_px(&gone), [0, 0, 0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn arrow_has_outline_and_fill() {
+        let arrow = arrow_cursor();
+        let px = |x: u32, y: u32| &arrow.data[((y * arrow.width + x) * 4) as usize..][..4];
+        assert_eq!(px(0, 0), [0, 0, 0, 255]);
+        assert_eq!(px(2, 6), [255, 255, 255, 255]);
+        assert_eq!(px(11, 0), [0, 0, 0, 0]);
+    }
+}
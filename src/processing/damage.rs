@@ -0,0 +1,219 @@
+//! # Tile-Hash Damage Tracking
+//!
+//! Detects which parts of a frame changed since the previous frame by hashing
+//! fixed-size tiles. Sources without native damage reporting (x11grab,
+//! PipeWire, scrap, replays) rely on it so the session can skip or duplicate
+//! unchanged frames.
+//!
+//! ## Cost
+//!
+//! One pass over the pixels per frame with a multiply-xor hash over 8-byte
+//! words, far cheaper than scaling or encoding the frame. Only one `u64` per
+//! tile is kept between frames, not the previous frame itself.
+//!
+//! ## Output
+//!
+//! Changed tiles are merged into horizontal runs per tile row and clipped to
+//! the frame, so a full-screen change is reported as one rectangle per tile row
+//! and a blinking cursor as a single tile.
+
+use crate::processing::{Rect, Size};
+
+/// Tracks per-tile hashes of consecutive frames to find changed regions.
+#[derive(Debug, Clone)]
+pub struct DamageTracker {
+    tile_size: u32,
+    size: Option<Size>,
+    hashes: Vec<u64>,
+}
+
+impl Default for DamageTracker {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TILE_SIZE)
+    }
+}
+
+impl DamageTracker {
+    /// Tile edge in pixels used by [`DamageTracker::default`].
+    pub const DEFAULT_TILE_SIZE: u32 = 64;
+
+    /// Tracker comparing `tile_size` × `tile_size` tiles (at least 1 pixel).
+    pub fn new(tile_size: u32) -> Self {
+        Self {
+            tile_size: tile_size.max(1),
+            size: None,
+            hashes: Vec::new(),
+        }
+    }
+
+    /// Tile edge in pixels.
+    pub fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// Forgets the previous frame, so the next update reports the whole frame.
+    pub fn reset(&mut self) {
+        self.size = None;
+        self.hashes.clear();
+    }
+
+    /// Compares a BGRA frame against the previous one and returns the changed
+    /// rectangles; empty if nothing changed.
+    ///
+    /// The first frame, and any frame whose size differs from the previous
+    /// one, is reported as changed in full.
+    ///
+    /// Time complexity: O(width * height) - every pixel is hashed once.
+    pub fn update(&mut self, data: &[u8], width: u32, height: u32, stride: usize) -> Vec<Rect> {
+        let tile = self.tile_size;
+        let cols = width.div_ceil(tile) as usize;
+        let rows = height.div_ceil(tile) as usize;
+        let row_len = width as usize * 4;
+
+        let mut hashes = vec![HASH_SEED; cols * rows];
+        for y in 0..height as usize {
+            let row = &data[y * stride..y * stride + row_len];
+            let tiles = &mut hashes[(y / tile as usize) * cols..][..cols];
+            for (hash, segment) in tiles.iter_mut().zip(row.chunks(tile as usize * 4)) {
+                *hash = hash_bytes(*hash, segment);
+            }
+        }
+
+        let same_size = self
+            .size
+            .is_some_and(|size| size.w == width && size.h == height);
+        let previous = std::mem::replace(&mut self.hashes, hashes);
+        self.size = Some(Size {
+            w: width,
+            h: height,
+        });
+        if !same_size {
+            return if width == 0 || height == 0 {
+                Vec::new()
+            } else {
+                vec![Rect {
+                    x: 0,
+                    y: 0,
+                    w: width,
+                    h: height,
+                }]
+            };
+        }
+
+        // Merge runs of changed tiles in each tile row
+        let mut changed = Vec::new();
+        for ty in 0..rows {
+            let mut tx = 0;
+            while tx < cols {
+                let i = ty * cols + tx;
+                if previous[i] == self.hashes[i] {
+                    tx += 1;
+                    continue;
+                }
+                let start = tx;
+                while tx < cols && previous[ty * cols + tx] != self.hashes[ty * cols + tx] {
+                    tx += 1;
+                }
+                let x = start as u32 * tile;
+                let y = ty as u32 * tile;
+                changed.push(Rect {
+                    x,
+                    y,
+                    w: (tx as u32 * tile).min(width) - x,
+                    h: tile.min(height - y),
+                });
+            }
+        }
+        changed
+    }
+}
+
+const HASH_SEED: u64 = 0xcbf2_9ce4_8422_2325;
+const HASH_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Folds `bytes` into `hash`, eight bytes at a time.
+fn hash_bytes(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut words = bytes.chunks_exact(8);
+    for word in &mut words {
+        let word = u64::from_le_bytes(word.try_into().unwrap());
+        hash = (hash ^ word).wrapping_mul(HASH_PRIME).rotate_left(29);
+    }
+    for &byte in words.remainder() {
+        hash = (hash ^ byte as u64).wrapping_mul(HASH_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(w: u32, h: u32) -> Vec<u8> {
+        vec![0u8; (w * h * 4) as usize]
+    }
+
+    fn paint(data: &mut [u8], stride: usize, x: u32, y: u32) {
+        let i = y as usize * stride + x as usize * 4;
+        data[i..i + 4].copy_from_slice(&[255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn first_frame_and_resize_are_fully_changed() {
+        let mut tracker = DamageTracker::new(16);
+        let full = Rect {
+            x: 0,
+            y: 0,
+            w: 40,
+            h: 20,
+        };
+        assert_eq!(tracker.update(&frame(40, 20), 40, 20, 160), vec![full]);
+        assert!(tracker.update(&frame(40, 20), 40, 20, 160).is_empty());
+
+        let resized = tracker.update(&frame(20, 20), 20, 20, 80);
+        assert_eq!(resized, vec![Rect { w: 20, ..full }]);
+    }
+
+    #[test]
+    fn reports_changed_tiles_clipped_to_frame() {
+        let (w, h, stride) = (40, 20, 40 * 4);
+        let mut tracker = DamageTracker::new(16);
+        let mut data = frame(w, h);
+        tracker.update(&data, w, h, stride);
+
+        // Bottom-right partial tile
+        paint(&mut data, stride, 39, 19);
+        assert_eq!(
+            tracker.update(&data, w, h, stride),
+            vec![Rect {
+                x: 32,
+                y: 16,
+                w: 8,
+                h: 4
+            }]
+        );
+
+        // Two adjacent tiles in the top row merge into one run
+        paint(&mut data, stride, 0, 0);
+        paint(&mut data, stride, 20, 5);
+        assert_eq!(
+            tracker.update(&data, w, h, stride),
+            vec![Rect {
+                x: 0,
+                y: 0,
+                w: 32,
+                h: 16
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_row_padding() {
+        let (w, h, stride) = (8, 8, 8 * 4 + 16);
+        let mut tracker = DamageTracker::new(4);
+        let mut data = vec![0u8; stride * h as usize];
+        tracker.update(&data, w, h, stride);
+
+        data[8 * 4] = 7; // first padding byte of row 0
+        assert!(tracker.update(&data, w, h, stride).is_empty());
+    }
+}
@@ -2,12 +2,18 @@
 //!
 //! This module contains the frame processing pipeline for screen capture operations.
 
+#[cfg(feature = "rtsp-streaming")]
+pub mod cursor_overlay;
+pub mod damage;
 pub mod processing;
 
 // Re-export commonly used types for convenience
 #[cfg(feature = "rtsp-streaming")]
+pub use cursor_overlay::{ClickHighlight, CursorOverlayProcessor};
+pub use damage::DamageTracker;
+#[cfg(feature = "rtsp-streaming")]
 pub use processing::{
-    FileStream, FrameProcessor, GundamProcessor, ProcessingPipeline, RtspStream, ScalingProcessor,
-    Stream, StreamMultiplexer,
+    AdaptiveScalingProcessor, CropProcessor, FileStream, FrameProcessor, GundamProcessor,
+    GundamRender, ProcessingPipeline, RtspStream, ScalingProcessor, Stream, StreamMultiplexer,
 };
-pub use processing::{Size, StreamConfig, StreamFormat};
+pub use processing::{Rect, Size, StreamConfig, StreamFormat};
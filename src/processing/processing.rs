@@ -33,7 +33,7 @@ use anyhow::Result;
 #[cfg(feature = "rtsp-streaming")]
 use async_trait::async_trait;
 #[cfg(feature = "rtsp-streaming")]
-use cap_rtsp::BgraFrame;
+use cap_rtsp::{BgraFrame, CursorInfo, Transform};
 #[cfg(feature = "rtsp-streaming")]
 use futures_util::future::join_all;
 #[cfg(feature = "rtsp-streaming")]
@@ -46,12 +46,87 @@ use gstreamer_app as gst_app;
 use std::sync::Arc;
 
 /// Size representation for frame dimensions.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Size {
     pub w: u32,
     pub h: u32,
 }
 
+/// Rectangle in pixel coordinates, used for capture regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Rect {
+    /// Dimensions of the rectangle.
+    pub fn size(&self) -> Size {
+        Size {
+            w: self.w,
+            h: self.h,
+        }
+    }
+
+    /// Returns `true` if the rectangle is non-empty and lies within `bounds`.
+    pub fn fits_within(&self, bounds: Size) -> bool {
+        self.w > 0
+            && self.h > 0
+            && self.x as u64 + self.w as u64 <= bounds.w as u64
+            && self.y as u64 + self.h as u64 <= bounds.h as u64
+    }
+
+    /// Overlap of two rectangles, or `None` if they do not overlap.
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x as u64 + self.w as u64).min(other.x as u64 + other.w as u64);
+        let y1 = (self.y as u64 + self.h as u64).min(other.y as u64 + other.h as u64);
+        (x1 > x0 as u64 && y1 > y0 as u64).then(|| Rect {
+            x: x0,
+            y: y0,
+            w: (x1 - x0 as u64) as u32,
+            h: (y1 - y0 as u64) as u32,
+        })
+    }
+
+    /// Copies this rectangle out of a BGRA image with `stride` bytes per row
+    /// into a tightly packed buffer (`w * 4` bytes per row).
+    ///
+    /// The rectangle must lie within the image; see [`Rect::fits_within`].
+    ///
+    /// Time complexity: O(w * h) - one row copy per output row.
+    pub fn crop_bgra(&self, src: &[u8], stride: usize) -> Vec<u8> {
+        let row_len = self.w as usize * 4;
+        let mut out = Vec::with_capacity(row_len * self.h as usize);
+        for row in 0..self.h as usize {
+            let start = (self.y as usize + row) * stride + self.x as usize * 4;
+            out.extend_from_slice(&src[start..start + row_len]);
+        }
+        out
+    }
+}
+
+impl std::str::FromStr for Rect {
+    type Err = String;
+
+    /// Parses `x,y,w,h`, e.g. `100,50,1280,720`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split(',')
+            .map(|part| part.trim().parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("invalid region '{}': {}", s, e))?;
+        match parts[..] {
+            [x, y, w, h] if w > 0 && h > 0 => Ok(Rect { x, y, w, h }),
+            [_, _, _, _] => Err(format!("region '{}' must have non-zero size", s)),
+            _ => Err(format!("region '{}' must be x,y,w,h", s)),
+        }
+    }
+}
+
 /// Stream configuration specifying output format and parameters.
 #[derive(Debug, Clone)]
 pub struct StreamConfig {
@@ -101,6 +176,20 @@ pub trait FrameProcessor: Send + Sync {
 pub trait Stream: Send + Sync {
     /// Send a frame to this stream.
     async fn send_frame(&mut self, frame: BgraFrame) -> Result<()>;
+    /// Send a frame that carries the Gundam views `set`, which is also in
+    /// `frame.meta.gundam`.
+    ///
+    /// Streams that take Gundam input override this to use the separate
+    /// tiles and full-resolution global view. The default sends the frame's
+    /// own rendering of them, e.g. the mosaic.
+    async fn send_gundam(
+        &mut self,
+        frame: BgraFrame,
+        set: Arc<cap_rtsp::GundamFrameSet>,
+    ) -> Result<()> {
+        let _ = set;
+        self.send_frame(frame).await
+    }
     /// Shut down this stream.
     async fn shutdown(&mut self) -> Result<()>;
     /// Get this stream's configuration.
@@ -111,6 +200,22 @@ pub trait Stream: Send + Sync {
     fn config(&self) -> &StreamConfig;
     /// Initialize this stream.
     async fn initialize(&mut self) -> Result<()>;
+    /// Accept frames of `size` from now on, e.g. after adaptive scaling
+    /// picked a new output size.
+    ///
+    /// Called before the first frame of the new size, but not for the size
+    /// the pipeline was initialized with. The default refuses, so streams
+    /// that cannot change their input caps are never sent frames that do not
+    /// match them.
+    async fn resize(&mut self, size: Size) -> Result<()> {
+        Err(anyhow::anyhow!(
+            "stream {}x{} cannot accept {}x{} frames",
+            self.config().width,
+            self.config().height,
+            size.w,
+            size.h
+        ))
+    }
 }
 
 /// Composable processing pipeline.
@@ -118,6 +223,8 @@ pub trait Stream: Send + Sync {
 #[cfg(feature = "rtsp-streaming")]
 pub struct ProcessingPipeline {
     pub processors: Vec<Box<dyn FrameProcessor>>,
+    /// Output size of each processor, as last initialized
+    sizes: Vec<Size>,
 }
 
 #[cfg(feature = "rtsp-streaming")]
@@ -158,6 +265,7 @@ impl ProcessingPipeline {
     pub fn new() -> Self {
         Self {
             processors: Vec::new(),
+            sizes: Vec::new(),
         }
     }
 
@@ -205,9 +313,29 @@ impl ProcessingPipeline {
     /// **Missing functionality**: None - sequentially initializes all processors and
     /// propagates size changes through the pipeline.
     pub async fn initialize(&mut self, input_size: Size) -> Result<Size> {
+        self.sizes.clear();
+        self.reinitialize_from(0, input_size).await
+    }
+
+    /// Size of the frames the pipeline currently outputs, or `None` before
+    /// [`ProcessingPipeline::initialize`].
+    ///
+    /// Changes when a processor such as [`AdaptiveScalingProcessor`] picks a
+    /// new output size.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn output_size(&self) -> Option<Size> {
+        self.sizes.last().copied()
+    }
+
+    /// Initializes the processors from index `start` on with `input_size`,
+    /// keeping the recorded sizes of the ones before it.
+    async fn reinitialize_from(&mut self, start: usize, input_size: Size) -> Result<Size> {
+        self.sizes.truncate(start);
         let mut current_size = input_size;
-        for processor in &mut self.processors {
+        for processor in &mut self.processors[start..] {
             current_size = processor.initialize(current_size).await?;
+            self.sizes.push(current_size);
         }
         Ok(current_size)
     }
@@ -218,6 +346,18 @@ impl ProcessingPipeline {
     /// Each processor receives the output of the previous processor as input.
     /// The final processed frame is returned.
     ///
+    /// [`BgraFrame::meta`] travels with the frame: processors that change its
+    /// geometry append a transform, so the returned frame lists every step
+    /// from captured to output pixels and [`FrameMeta::map_to_screen`] maps
+    /// a point on it back to the screen.
+    ///
+    /// [`FrameMeta::map_to_screen`]: cap_rtsp::FrameMeta::map_to_screen
+    ///
+    /// When a processor outputs a frame of another size than it was
+    /// initialized for, the processors after it are initialized again with
+    /// the new size before they see the frame, and
+    /// [`ProcessingPipeline::output_size`] reports the result.
+    ///
     /// # Parameters
     ///
     /// - `frame`: The input frame to process
@@ -239,7 +379,7 @@ impl ProcessingPipeline {
     ///
     /// ```rust
     /// use hybrid_screen_capture::processing::ProcessingPipeline;
-    /// use cap_rtsp::BgraFrame;
+    /// use cap_rtsp::{BgraFrame, FrameMeta};
     /// use std::sync::Arc;
     ///
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
@@ -252,6 +392,8 @@ impl ProcessingPipeline {
     ///     height: 1080,
     ///     stride: 1920 * 4,
     ///     pts_ns: None,
+    ///     cursor: None,
+    ///     meta: FrameMeta::default(),
     /// };
     ///
     /// let output_frame = pipeline.process_frame(input_frame).await?;
@@ -273,14 +415,28 @@ impl ProcessingPipeline {
     pub async fn process_frame(&mut self, frame: BgraFrame) -> Result<BgraFrame> {
         let mut current_frame = frame;
 
-        for processor in &mut self.processors {
-            if let Some(processed) = processor.process_frame(current_frame).await? {
+        for index in 0..self.processors.len() {
+            if let Some(processed) = self.processors[index].process_frame(current_frame).await? {
                 current_frame = processed;
             } else {
                 // Processor skipped frame but consumed it - this indicates an API issue
                 // For now, panic as this shouldn't happen with current processors
                 panic!("Frame processor returned None but consumed the input frame");
             }
+
+            // Downstream processors were set up for the old size
+            let size = Size {
+                w: current_frame.width,
+                h: current_frame.height,
+            };
+            if self
+                .sizes
+                .get(index)
+                .is_some_and(|&expected| expected != size)
+            {
+                self.sizes[index] = size;
+                self.reinitialize_from(index + 1, size).await?;
+            }
         }
 
         Ok(current_frame)
@@ -388,10 +544,14 @@ impl StreamMultiplexer {
     /// This approach ensures that slow streams don't block faster ones, and
     /// provides natural load balancing across different output destinations.
     ///
+    /// Frames carrying a [`GundamFrameSet`](cap_rtsp::GundamFrameSet) go to
+    /// [`Stream::send_gundam`] instead of [`Stream::send_frame`].
+    ///
     /// # Parameters
     ///
     /// * `frame` - The BGRA frame to send to all streams. Will be cloned for
-    ///   each stream to maintain reference counting.
+    ///   each stream to maintain reference counting; its [`BgraFrame::meta`]
+    ///   is shared the same way.
     ///
     /// # Returns
     ///
@@ -409,7 +569,7 @@ impl StreamMultiplexer {
     ///
     /// ```rust,no_run
     /// use hybrid_screen_capture::processing::{StreamMultiplexer, StreamConfig, StreamFormat};
-    /// use hybrid_screen_capture::BgraFrame;
+    /// use hybrid_screen_capture::{BgraFrame, FrameMeta};
     /// use std::sync::Arc;
     ///
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
@@ -422,6 +582,8 @@ impl StreamMultiplexer {
     ///     height: 1080,
     ///     stride: 1920 * 4,
     ///     pts_ns: Some(0),
+    ///     cursor: None,
+    ///     meta: FrameMeta::default(),
     /// };
     ///
     /// multiplexer.send_frame(frame).await?;
@@ -445,7 +607,10 @@ impl StreamMultiplexer {
         // Wait for all sends to complete
         let mut futures = Vec::new();
         for (stream, frame_ref) in self.streams.iter_mut().zip(frame_refs) {
-            futures.push(stream.send_frame(frame_ref));
+            futures.push(match frame_ref.meta.gundam.clone() {
+                Some(set) => stream.send_gundam(frame_ref, set),
+                None => stream.send_frame(frame_ref),
+            });
         }
 
         // Wait for all sends to complete
@@ -497,6 +662,29 @@ impl StreamMultiplexer {
         Ok(())
     }
 
+    /// Tell every stream whose configured size differs from `size` that
+    /// frames of `size` follow.
+    ///
+    /// Call this before sending the first frame of a new size.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`Stream::resize`], e.g. for a stream
+    /// that cannot change its caps.
+    ///
+    /// # Performance Characteristics
+    ///
+    /// **Time complexity**: O(n) where n is the number of streams.
+    pub async fn resize(&mut self, size: Size) -> Result<()> {
+        for stream in &mut self.streams {
+            let config = stream.config();
+            if (config.width, config.height) != (size.w, size.h) {
+                stream.resize(size).await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Get the number of streams in the multiplexer.
     ///
     /// Returns the current count of configured streams. This is useful for
@@ -525,13 +713,93 @@ impl StreamMultiplexer {
     }
 }
 
+/// What the frames out of a [`GundamProcessor`] show.
+///
+/// Either way the separate views travel along as a
+/// [`GundamFrameSet`](cap_rtsp::GundamFrameSet) in the frame's metadata.
+#[cfg(feature = "rtsp-streaming")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GundamRender {
+    /// Tiles and a tile-sized global view side by side in one mosaic
+    #[default]
+    Mosaic,
+    /// The full-resolution global view alone, without copying it
+    Global,
+}
+
 /// Gundam tiling processor for DeepSeek-OCR optimization.
+///
+/// With a `pool` the tiles and the global view are scaled in parallel by
+/// [`gundam_pack_cpu_parallel`](cap_scale::gundam::gundam_pack_cpu_parallel);
+/// the output is the same as without one.
+///
+/// Buffers are shared with the [`GundamFrameSet`](cap_rtsp::GundamFrameSet)
+/// of each output frame and reused once consumers drop it.
 #[cfg(feature = "rtsp-streaming")]
 pub struct GundamProcessor {
     pub cfg: cap_scale::gundam::GundamCfg,
-    pub tile_buffers: Vec<Vec<u8>>,
-    pub global_buffer: Vec<u8>,
+    pub tile_buffers: Vec<Arc<Vec<u8>>>,
+    pub global_buffer: Arc<Vec<u8>>,
     pub output_size: Size,
+    /// Per-thread workers for parallel packing; `None` packs on the calling thread
+    pub pool: Option<cap_scale::gundam::GundamPool>,
+    /// What output frames show
+    pub render: GundamRender,
+    /// Writes the mosaic for [`GundamRender::Mosaic`]
+    pub compositor: cap_rtsp::GundamCompositor,
+}
+
+#[cfg(feature = "rtsp-streaming")]
+impl GundamProcessor {
+    /// Processor tiling frames as `cfg` describes, on the calling thread.
+    ///
+    /// **Time complexity**: O(1); buffers are sized on initialization.
+    pub fn new(cfg: cap_scale::gundam::GundamCfg) -> Self {
+        Self {
+            cfg,
+            tile_buffers: Vec::new(),
+            global_buffer: Arc::default(),
+            output_size: Size { w: 0, h: 0 },
+            pool: None,
+            render: GundamRender::Mosaic,
+            compositor: cap_rtsp::GundamCompositor::default(),
+        }
+    }
+
+    /// Packs tiles on `threads` threads; 1 or less keeps packing on the
+    /// calling thread.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.pool = (threads > 1).then(|| cap_scale::gundam::GundamPool::new(threads));
+        self
+    }
+
+    /// Divides frames into tiles with `grid` instead of the configured strategy.
+    pub fn with_grid(mut self, grid: cap_scale::gundam::GridStrategy) -> Self {
+        self.cfg.grid = grid;
+        self
+    }
+
+    /// Renders output frames as `render` describes.
+    pub fn with_render(mut self, render: GundamRender) -> Self {
+        self.render = render;
+        self
+    }
+
+    /// Arranges mosaics as `layout` describes.
+    pub fn with_layout(mut self, layout: cap_rtsp::CompositeLayout) -> Self {
+        self.compositor = cap_rtsp::GundamCompositor::new(layout);
+        self
+    }
+}
+
+/// `buffer` as a writable slice of `len` bytes, replacing it with a fresh
+/// buffer if an earlier frame set still holds it.
+#[cfg(feature = "rtsp-streaming")]
+fn writable(buffer: &mut Arc<Vec<u8>>, len: usize) -> &mut [u8] {
+    if Arc::get_mut(buffer).is_none_or(|data| data.len() != len) {
+        *buffer = Arc::new(vec![0; len]);
+    }
+    Arc::get_mut(buffer).expect("buffer was just made unique")
 }
 
 #[cfg(feature = "rtsp-streaming")]
@@ -541,6 +809,9 @@ impl std::fmt::Debug for GundamProcessor {
             .field("tile_buffers_count", &self.tile_buffers.len())
             .field("global_buffer_size", &self.global_buffer.len())
             .field("output_size", &self.output_size)
+            .field("pool", &self.pool)
+            .field("render", &self.render)
+            .field("compositor", &self.compositor)
             .finish()
     }
 }
@@ -552,11 +823,14 @@ impl FrameProcessor for GundamProcessor {
     ///
     /// Calculates the optimal tile grid layout based on input dimensions and
     /// Gundam configuration parameters. The output frame will be a composite
-    /// grid containing individual tiles plus a global downscaled view.
+    /// grid containing individual tiles plus a global downscaled view, or
+    /// with [`GundamRender::Global`] the `global_side` square global view.
     ///
-    /// The algorithm estimates the number of tiles needed based on input size
-    /// (typically aiming for tiles around 1024x1024 pixels), then arranges them
-    /// in a roughly square grid layout for the composite output.
+    /// The number of tiles comes from the configured
+    /// [`GridStrategy`](cap_scale::gundam::GridStrategy), capped at
+    /// `max_tiles`; the compositor's
+    /// [`CompositeLayout`](cap_rtsp::CompositeLayout) arranges them and the
+    /// global view in the composite output.
     ///
     /// # Parameters
     ///
@@ -575,15 +849,18 @@ impl FrameProcessor for GundamProcessor {
     /// # Examples
     ///
     /// ```rust,no_run
-    /// use hybrid_screen_capture::processing::{GundamProcessor, FrameProcessor, Size};
+    /// use hybrid_screen_capture::processing::{FrameProcessor, GundamProcessor, GundamRender, Size};
     /// use cap_scale::gundam::GundamCfg;
     ///
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let mut processor = GundamProcessor {
     ///     cfg: GundamCfg::default(),
     ///     tile_buffers: Vec::new(),
-    ///     global_buffer: Vec::new(),
+    ///     global_buffer: Default::default(),
     ///     output_size: Size { w: 0, h: 0 },
+    ///     pool: None,
+    ///     render: GundamRender::Mosaic,
+    ///     compositor: Default::default(),
     /// };
     ///
     /// let input_size = Size { w: 1920, h: 1080 };
@@ -595,35 +872,40 @@ impl FrameProcessor for GundamProcessor {
     ///
     /// # Performance Characteristics
     ///
-    /// **Time complexity**: O(1) - Simple arithmetic calculations for grid layout
-    /// and output size determination.
+    /// **Time complexity**: O(max_tiles²) - Grid selection plus simple
+    /// arithmetic for the output size.
     ///
     /// **Missing functionality**: None - calculates tile grid and composite frame
     /// dimensions based on input size and Gundam configuration.
     async fn initialize(&mut self, input_size: Size) -> Result<Size> {
-        // Estimate number of tiles based on input dimensions
-        // Use the same logic as choose_grid but simplified
-        let cols = ((input_size.w as f32 / 1024.0).ceil() as u32).clamp(1, 3);
-        let rows = ((input_size.h as f32 / 1024.0).ceil() as u32).clamp(1, 3);
-        let num_tiles = (cols * rows).clamp(self.cfg.min_tiles, self.cfg.max_tiles);
+        // Number of tiles the configured grid produces for this input
+        let num_tiles =
+            cap_scale::gundam::tile_rects(input_size.w, input_size.h, &self.cfg).len() as u32;
 
         // Allocate tile buffers
         let tile_buffer_size = (self.cfg.tile_side * self.cfg.tile_side * 4) as usize;
-        self.tile_buffers = vec![vec![0u8; tile_buffer_size]; num_tiles as usize];
+        self.tile_buffers = (0..num_tiles)
+            .map(|_| Arc::new(vec![0u8; tile_buffer_size]))
+            .collect();
 
         // Allocate global buffer
         let global_buffer_size = (self.cfg.global_side * self.cfg.global_side * 4) as usize;
-        self.global_buffer = vec![0u8; global_buffer_size];
+        self.global_buffer = Arc::new(vec![0u8; global_buffer_size]);
 
-        // Calculate output size based on Gundam configuration
-        // The output is a composite grid of tiles + global view
-        let total_elements = num_tiles + 1; // tiles + global view
-        let cols_out = ((total_elements as f32).sqrt().ceil() as u32).max(1);
-        let rows_out = (((total_elements as u32 + cols_out - 1) / cols_out) as usize).max(1) as u32;
+        if self.render == GundamRender::Global {
+            self.output_size = Size {
+                w: self.cfg.global_side,
+                h: self.cfg.global_side,
+            };
+            return Ok(self.output_size);
+        }
 
-        // Each element is tile_side x tile_side, except global which gets scaled
-        let output_width = cols_out * self.cfg.tile_side;
-        let output_height = rows_out * self.cfg.tile_side;
+        // The output is a mosaic of the tiles and the global view
+        let (output_width, output_height) = self.compositor.layout().size(
+            num_tiles as usize,
+            self.cfg.tile_side,
+            self.cfg.global_side,
+        );
 
         self.output_size = Size {
             w: output_width,
@@ -644,6 +926,15 @@ impl FrameProcessor for GundamProcessor {
     /// 3. Arranges tiles and global view in a composite grid
     /// 4. Returns the composite as a new BGRA frame
     ///
+    /// The tiles and the full-resolution global view are attached to the
+    /// output as a [`GundamFrameSet`](cap_rtsp::GundamFrameSet) in
+    /// `meta.gundam`; the frame itself shows the mosaic, or just the global
+    /// view with [`GundamRender::Global`].
+    ///
+    /// The chosen tile rectangles are recorded in the frame's mosaic
+    /// transform and their [`TileScore::information`](cap_scale::content::TileScore::information)
+    /// values, in tile order, in the `gundam_tile_scores` attribute.
+    ///
     /// This preprocessing significantly improves OCR accuracy for large documents
     /// or complex layouts by providing multiple views at different scales.
     ///
@@ -666,8 +957,8 @@ impl FrameProcessor for GundamProcessor {
     /// # Examples
     ///
     /// ```rust,no_run
-    /// use hybrid_screen_capture::processing::{GundamProcessor, FrameProcessor, Size};
-    /// use cap_rtsp::BgraFrame;
+    /// use hybrid_screen_capture::processing::{FrameProcessor, GundamProcessor, GundamRender, Size};
+    /// use cap_rtsp::{BgraFrame, FrameMeta};
     /// use cap_scale::gundam::GundamCfg;
     /// use std::sync::Arc;
     ///
@@ -675,8 +966,11 @@ impl FrameProcessor for GundamProcessor {
     /// let mut processor = GundamProcessor {
     ///     cfg: GundamCfg::default(),
     ///     tile_buffers: Vec::new(),
-    ///     global_buffer: Vec::new(),
+    ///     global_buffer: Default::default(),
     ///     output_size: Size { w: 0, h: 0 },
+    ///     pool: None,
+    ///     render: GundamRender::Mosaic,
+    ///     compositor: Default::default(),
     /// };
     ///
     /// let frame = BgraFrame {
@@ -685,6 +979,8 @@ impl FrameProcessor for GundamProcessor {
     ///     height: 1080,
     ///     stride: 1920 * 4,
     ///     pts_ns: Some(0),
+    ///     cursor: None,
+    ///     meta: FrameMeta::default(),
     /// };
     ///
     /// let result = processor.process_frame(frame).await?;
@@ -714,49 +1010,100 @@ impl FrameProcessor for GundamProcessor {
     /// extraction, global view scaling, and composite arrangement.
     async fn process_frame(&mut self, frame: BgraFrame) -> Result<Option<BgraFrame>> {
         // Process frame with Gundam tiling
-        use cap_scale::gundam::gundam_pack_cpu;
+        use cap_scale::gundam::{gundam_pack_cpu, gundam_pack_cpu_parallel};
 
         // Update tile buffer references
+        let tile_len = (self.cfg.tile_side * self.cfg.tile_side * 4) as usize;
+        let global_len = (self.cfg.global_side * self.cfg.global_side * 4) as usize;
         let tile_refs: Vec<&mut [u8]> = self
             .tile_buffers
             .iter_mut()
-            .map(|v| v.as_mut_slice())
+            .map(|v| writable(v, tile_len))
             .collect();
 
         let gundam_outputs = cap_scale::gundam::GundamOutputs {
             tiles: tile_refs,
-            global: self.global_buffer.as_mut_slice(),
+            global: writable(&mut self.global_buffer, global_len),
         };
 
         // Process the frame
-        gundam_pack_cpu(
-            &mut fast_image_resize::Resizer::new(),
-            &frame.data,
-            frame.width,
-            frame.height,
-            frame.stride,
-            self.cfg,
-            &mut cap_scale::cpu::Staging::with_capacity(frame.stride * frame.height as usize),
-            gundam_outputs,
-        )?;
+        let tiles = match &mut self.pool {
+            Some(pool) => gundam_pack_cpu_parallel(
+                pool,
+                &frame.data,
+                frame.width,
+                frame.height,
+                frame.stride,
+                self.cfg,
+                gundam_outputs,
+            )?,
+            None => gundam_pack_cpu(
+                &mut fast_image_resize::Resizer::new(),
+                &frame.data,
+                frame.width,
+                frame.height,
+                frame.stride,
+                self.cfg,
+                &mut cap_scale::cpu::Staging::with_capacity(frame.stride * frame.height as usize),
+                gundam_outputs,
+            )?,
+        };
 
-        // Arrange tiles and global into composite frame
-        let (composite, width, height) = cap_rtsp::arrange_gundam_composite(
-            &self.tile_buffers,
-            &self.global_buffer,
-            self.cfg.tile_side,
-            self.cfg.global_side,
-        );
+        let set = cap_rtsp::GundamFrameSet {
+            input: cap_scale::presets::Size {
+                w: frame.width,
+                h: frame.height,
+            },
+            grid: cap_scale::gundam::grid_for(frame.width, frame.height, &self.cfg),
+            cfg: self.cfg,
+            tiles: tiles
+                .iter()
+                .zip(&self.tile_buffers)
+                .map(|(tile, data)| cap_rtsp::GundamTile {
+                    data: Arc::clone(data),
+                    source: tile.rect,
+                    score: tile.score,
+                })
+                .collect(),
+            global: Arc::clone(&self.global_buffer),
+        };
 
-        let composite_frame = BgraFrame {
-            data: Arc::new(composite),
+        // Render the views into the output frame
+        let mut meta = frame.meta;
+        let (data, width, height) = match self.render {
+            GundamRender::Mosaic => {
+                let slots = self.tile_buffers.len();
+                meta.push_transform(set.mosaic_transform(&self.compositor.layout(), slots));
+                set.render_mosaic(&mut self.compositor, slots)?
+            }
+            GundamRender::Global => {
+                meta.push_transform(set.global_transform());
+                (
+                    Arc::clone(&set.global),
+                    self.cfg.global_side,
+                    self.cfg.global_side,
+                )
+            }
+        };
+        // Scores in the order of the mosaic's tile views
+        let scores: Vec<String> = tiles
+            .iter()
+            .map(|tile| format!("{:.3}", tile.score.information()))
+            .collect();
+        meta.set_attribute("gundam_tile_scores", scores.join(","));
+        meta.gundam = Some(Arc::new(set));
+
+        let output = BgraFrame {
+            data,
             width,
             height,
             stride: width as usize * 4,
             pts_ns: frame.pts_ns,
+            cursor: None,
+            meta,
         };
 
-        Ok(Some(composite_frame))
+        Ok(Some(output))
     }
 }
 
@@ -855,6 +1202,19 @@ impl Stream for RtspStream {
         // RTSP publisher is already initialized
         Ok(())
     }
+
+    /// Accept frames of `size`.
+    ///
+    /// The publisher updates its appsrc caps itself when the first frame of
+    /// the new size reaches it, and keeps encoding at the size the server was
+    /// started with; this only records the new frame size in the config.
+    ///
+    /// **Time complexity**: O(1).
+    async fn resize(&mut self, size: Size) -> Result<()> {
+        self.config.width = size.w;
+        self.config.height = size.h;
+        Ok(())
+    }
 }
 
 /// File stream implementation for saving frames to disk.
@@ -1055,7 +1415,8 @@ impl Stream for FileStream {
     ///
     /// 1. `appsrc` - Receives raw BGRA frames from the application
     /// 2. `videoconvert` - Converts BGRA to I420 color format
-    /// 3. `videoscale` - Handles any necessary scaling (though input should match config)
+    /// 3. `videoscale` - Scales frames to the configured size, so the file keeps
+    ///    one resolution if [`Stream::resize`] changes the frame size later
     /// 4. `x264enc` - H.264 video encoding with low-latency settings
     /// 5. `h264parse` - Parses encoded H.264 stream
     /// 6. `mp4mux` - Multiplexes video into MP4 container
@@ -1097,10 +1458,15 @@ impl Stream for FileStream {
         // Create pipeline for file encoding
         let launch = format!(
             "appsrc name=src is-live=true format=time do-timestamp=true caps=video/x-raw,format=BGRA,width={},height={},framerate={}/1 \
-             ! videoconvert ! videoscale ! video/x-raw,format=I420 \
+             ! videoconvert ! videoscale ! video/x-raw,format=I420,width={},height={} \
              ! x264enc tune=zerolatency speed-preset=veryfast bitrate=4000 \
              ! h264parse ! mp4mux ! filesink location={}",
-            self.config.width, self.config.height, self.config.fps, self.path
+            self.config.width,
+            self.config.height,
+            self.config.fps,
+            self.config.width,
+            self.config.height,
+            self.path
         );
 
         let pipeline = match gst::parse::launch(&launch) {
@@ -1132,13 +1498,41 @@ impl Stream for FileStream {
         println!("Initialized file stream to '{}'", self.path);
         Ok(())
     }
+
+    /// Accept frames of `size` by updating the appsrc caps.
+    ///
+    /// Frames are pushed synchronously, so the new caps apply from the next
+    /// frame on. The file keeps the resolution it was initialized with;
+    /// `videoscale` scales the new frames to it.
+    ///
+    /// **Time complexity**: O(1).
+    async fn resize(&mut self, size: Size) -> Result<()> {
+        if let Some(appsrc) = &self.appsrc {
+            let caps = gst::Caps::builder("video/x-raw")
+                .field("format", "BGRA")
+                .field("width", size.w as i32)
+                .field("height", size.h as i32)
+                .field("framerate", gst::Fraction::new(self.config.fps as i32, 1))
+                .build();
+            appsrc.set_caps(Some(&caps));
+        }
+        self.config.width = size.w;
+        self.config.height = size.h;
+        Ok(())
+    }
 }
 
 /// Scaling processor for token-efficient image resizing.
 /// Implements VLM-optimized scaling with configurable presets.
+///
+/// `spec` sets the target size, aspect handling (including letterboxing into
+/// a fixed size with a pad color) and
+/// [`ResizeQuality`](cap_scale::presets::ResizeQuality): filter,
+/// gamma-correct resampling and OCR sharpening. A
+/// [`TokenPreset`](cap_scale::presets::TokenPreset) converts into one.
 #[cfg(feature = "rtsp-streaming")]
 pub struct ScalingProcessor {
-    pub preset: cap_scale::presets::TokenPreset,
+    pub spec: cap_scale::presets::ScaleSpec,
     pub resizer: fast_image_resize::Resizer,
     pub staging: cap_scale::cpu::Staging,
     pub output_buffer: Vec<u8>,
@@ -1149,19 +1543,35 @@ pub struct ScalingProcessor {
 impl std::fmt::Debug for ScalingProcessor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ScalingProcessor")
-            .field("preset", &self.preset)
+            .field("spec", &self.spec)
             .field("output_buffer_size", &self.output_buffer.len())
             .field("output_size", &self.output_size)
             .finish()
     }
 }
 
+#[cfg(feature = "rtsp-streaming")]
+impl ScalingProcessor {
+    /// Processor scaling frames as `spec` describes.
+    ///
+    /// **Time complexity**: O(1); buffers are sized on initialization.
+    pub fn new(spec: impl Into<cap_scale::presets::ScaleSpec>) -> Self {
+        Self {
+            spec: spec.into(),
+            resizer: fast_image_resize::Resizer::new(),
+            staging: cap_scale::cpu::Staging::with_capacity(1920 * 1080 * 4), // Pre-allocate for HD
+            output_buffer: Vec::new(),
+            output_size: Size { w: 0, h: 0 },
+        }
+    }
+}
+
 #[cfg(feature = "rtsp-streaming")]
 #[async_trait]
 impl FrameProcessor for ScalingProcessor {
     /// Initialize the scaling processor with input size and return output size.
     ///
-    /// Computes the scaling plan based on the configured preset and input dimensions.
+    /// Computes the scaling plan based on the configured spec and input dimensions.
     /// Allocates output buffer and staging area for efficient processing.
     ///
     /// # Parameters
@@ -1183,15 +1593,11 @@ impl FrameProcessor for ScalingProcessor {
     ///
     /// **Missing functionality**: None - fully initializes scaling processor with preset-based plan.
     async fn initialize(&mut self, input_size: Size) -> Result<Size> {
-        // Build scaling plan from preset
-        let plan = cap_scale::presets::build_plan(
-            cap_scale::presets::Size {
-                w: input_size.w,
-                h: input_size.h,
-            },
-            self.preset.to_target(),
-            cap_scale::presets::AspectMode::Preserve,
-        );
+        // Build scaling plan from the spec
+        let plan = self.spec.plan(cap_scale::presets::Size {
+            w: input_size.w,
+            h: input_size.h,
+        });
 
         self.output_size = Size {
             w: plan.out.w,
@@ -1211,9 +1617,9 @@ impl FrameProcessor for ScalingProcessor {
 
     /// Process a frame with token-efficient scaling.
     ///
-    /// Applies the configured scaling preset to reduce image dimensions while
-    /// preserving aspect ratio and OCR accuracy. Uses SIMD acceleration for
-    /// high-performance processing.
+    /// Applies the configured scaling spec to reduce image dimensions with its
+    /// aspect handling and resize quality. Padding is filled with the pad
+    /// color. Uses SIMD acceleration for high-performance processing.
     ///
     /// # Parameters
     ///
@@ -1238,14 +1644,10 @@ impl FrameProcessor for ScalingProcessor {
     /// with zero-copy buffer management and SIMD acceleration.
     async fn process_frame(&mut self, frame: BgraFrame) -> Result<Option<BgraFrame>> {
         // Build scaling plan for this frame
-        let plan = cap_scale::presets::build_plan(
-            cap_scale::presets::Size {
-                w: frame.width,
-                h: frame.height,
-            },
-            self.preset.to_target(),
-            cap_scale::presets::AspectMode::Preserve,
-        );
+        let plan = self.spec.plan(cap_scale::presets::Size {
+            w: frame.width,
+            h: frame.height,
+        });
 
         // Scale the frame using cap_scale
         cap_scale::cpu::scale_bgra_cpu(
@@ -1261,6 +1663,9 @@ impl FrameProcessor for ScalingProcessor {
             Some(&mut self.staging),
         )?;
 
+        let mut meta = frame.meta;
+        meta.push_transform(Transform::from_plan(&plan));
+
         // Create output frame
         let scaled_frame = BgraFrame {
             data: Arc::new(self.output_buffer.clone()),
@@ -1268,8 +1673,306 @@ impl FrameProcessor for ScalingProcessor {
             height: plan.out.h,
             stride: (plan.out.w * 4) as usize,
             pts_ns: frame.pts_ns,
+            cursor: frame.cursor.map(|cursor| scale_cursor(cursor, &plan)),
+            meta,
         };
 
         Ok(Some(scaled_frame))
     }
 }
+
+/// Maps the pointer position to output pixels, including the offset of
+/// padded content; the shape stays unscaled.
+///
+/// A pointer outside the input is moved to the nearest edge of the content
+/// and hidden.
+#[cfg(feature = "rtsp-streaming")]
+fn scale_cursor(cursor: CursorInfo, plan: &cap_scale::presets::ScalePlan) -> CursorInfo {
+    let p = cap_scale::presets::Point {
+        x: (cursor.x as f64).clamp(0.0, plan.input.w as f64),
+        y: (cursor.y as f64).clamp(0.0, plan.input.h as f64),
+    };
+    let inside = p.x == cursor.x as f64 && p.y == cursor.y as f64;
+    match plan.map_to_output(p) {
+        Some(out) => CursorInfo {
+            x: out.x as i32,
+            y: out.y as i32,
+            visible: cursor.visible && inside,
+            ..cursor
+        },
+        None => CursorInfo {
+            visible: false,
+            ..cursor
+        },
+    }
+}
+
+/// Processor cropping every frame to a fixed rectangle.
+///
+/// Used by sources that cannot capture a region natively (recordings, image
+/// sequences, test patterns) so they crop like live capture: the pixels are
+/// copied with [`Rect::crop_bgra`], a [`Transform::Crop`] is recorded in the
+/// frame's metadata, and the cursor is moved into the region.
+#[cfg(feature = "rtsp-streaming")]
+#[derive(Debug, Clone, Copy)]
+pub struct CropProcessor {
+    region: Rect,
+}
+
+#[cfg(feature = "rtsp-streaming")]
+impl CropProcessor {
+    /// Processor keeping only `region` of each frame.
+    pub fn new(region: Rect) -> Self {
+        Self { region }
+    }
+
+    /// The rectangle kept.
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    /// Size of cropped `input_size` frames, or a validation error if the
+    /// region does not fit within them.
+    pub fn output_size(&self, input_size: Size) -> Result<Size> {
+        if !self.region.fits_within(input_size) {
+            return Err(crate::error::CaptureError::validation(
+                "region",
+                format!(
+                    "must fit within the {}x{} input",
+                    input_size.w, input_size.h
+                ),
+                format!("{:?}", self.region),
+            )
+            .into());
+        }
+        Ok(self.region.size())
+    }
+
+    /// Crops `frame`, which must contain the region.
+    ///
+    /// **Time complexity**: O(region width × height).
+    pub fn crop(&self, frame: BgraFrame) -> Result<BgraFrame> {
+        let region = self.region;
+        self.output_size(Size {
+            w: frame.width,
+            h: frame.height,
+        })?;
+        let bounds = Size {
+            w: frame.width,
+            h: frame.height,
+        };
+        let mut meta = frame.meta;
+        if region.size() != bounds {
+            meta.push_transform(crop_transform(bounds, region));
+        }
+        let cursor = frame.cursor.map(|cursor| {
+            let (x, y) = (cursor.x - region.x as i32, cursor.y - region.y as i32);
+            CursorInfo {
+                x,
+                y,
+                visible: cursor.visible
+                    && (0..region.w as i32).contains(&x)
+                    && (0..region.h as i32).contains(&y),
+                ..cursor
+            }
+        });
+
+        Ok(BgraFrame {
+            data: Arc::new(region.crop_bgra(&frame.data, frame.stride)),
+            width: region.w,
+            height: region.h,
+            stride: region.w as usize * 4,
+            pts_ns: frame.pts_ns,
+            cursor,
+            meta,
+        })
+    }
+}
+
+/// Transform recording that `region` of a `bounds`-sized image was kept.
+#[cfg(feature = "rtsp-streaming")]
+pub(crate) fn crop_transform(bounds: Size, region: Rect) -> Transform {
+    Transform::Crop {
+        input: cap_scale::presets::Size {
+            w: bounds.w,
+            h: bounds.h,
+        },
+        rect: cap_scale::gundam::Rect {
+            x: region.x,
+            y: region.y,
+            w: region.w,
+            h: region.h,
+        },
+    }
+}
+
+#[cfg(feature = "rtsp-streaming")]
+#[async_trait]
+impl FrameProcessor for CropProcessor {
+    async fn initialize(&mut self, input_size: Size) -> Result<Size> {
+        self.output_size(input_size)
+    }
+
+    async fn process_frame(&mut self, frame: BgraFrame) -> Result<Option<BgraFrame>> {
+        self.crop(frame).map(Some)
+    }
+}
+
+/// Scaling processor that keeps frames within a vision token budget.
+///
+/// Each frame is scaled with the largest candidate plan whose cost for
+/// `model` fits the allowance `budget` gives it, so a per-minute budget can
+/// be spent on larger frames when earlier ones were cheap. Candidates default
+/// to [`LONG_SIDE_LADDER`](cap_scale::tokens::LONG_SIDE_LADDER).
+///
+/// When the chosen size changes, the resizer and staging buffers are set up
+/// again and the frame comes out at the new size; [`ProcessingPipeline`]
+/// re-initializes later processors and the session calls
+/// [`Stream::resize`] before sending it. Each frame records its cost in the
+/// `vision_tokens` attribute of its [`FrameMeta`](cap_rtsp::FrameMeta).
+#[cfg(feature = "rtsp-streaming")]
+pub struct AdaptiveScalingProcessor {
+    pub model: Box<dyn cap_scale::tokens::TokenModel + Send + Sync>,
+    pub budget: cap_scale::tokens::BudgetTracker,
+    pub candidates: Vec<cap_scale::presets::ScaleTarget>,
+    pub resizer: fast_image_resize::Resizer,
+    pub staging: cap_scale::cpu::Staging,
+    pub output_buffer: Vec<u8>,
+    pub output_size: Size,
+}
+
+#[cfg(feature = "rtsp-streaming")]
+impl std::fmt::Debug for AdaptiveScalingProcessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdaptiveScalingProcessor")
+            .field("model", &self.model.name())
+            .field("budget", &self.budget)
+            .field("candidates", &self.candidates.len())
+            .field("output_size", &self.output_size)
+            .finish()
+    }
+}
+
+#[cfg(feature = "rtsp-streaming")]
+impl AdaptiveScalingProcessor {
+    /// Processor keeping frames for `model` within `budget`.
+    ///
+    /// **Time complexity**: O(1).
+    pub fn new(
+        model: Box<dyn cap_scale::tokens::TokenModel + Send + Sync>,
+        budget: cap_scale::tokens::TokenBudget,
+    ) -> Self {
+        Self {
+            model,
+            budget: cap_scale::tokens::BudgetTracker::new(budget),
+            candidates: cap_scale::tokens::LONG_SIDE_LADDER
+                .map(cap_scale::presets::ScaleTarget::MaxLongSide)
+                .to_vec(),
+            resizer: fast_image_resize::Resizer::new(),
+            staging: cap_scale::cpu::Staging::with_capacity(0),
+            output_buffer: Vec::new(),
+            output_size: Size { w: 0, h: 0 },
+        }
+    }
+
+    /// Replaces the candidate targets the output size is chosen from.
+    pub fn with_candidates(mut self, candidates: Vec<cap_scale::presets::ScaleTarget>) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Plan for an `input`-sized frame within the current allowance, and its
+    /// token cost.
+    fn fit(&self, input: Size) -> Result<(cap_scale::presets::ScalePlan, u64)> {
+        cap_scale::tokens::fit_plan(
+            self.model.as_ref(),
+            cap_scale::presets::Size {
+                w: input.w,
+                h: input.h,
+            },
+            &self.candidates,
+            cap_scale::presets::AspectMode::Preserve,
+            self.budget.allowance(),
+        )
+        .ok_or_else(|| anyhow::anyhow!("adaptive scaling needs at least one candidate"))
+    }
+
+    /// Sets up the resizer and buffers for `input`-sized frames scaled to
+    /// `output`.
+    fn reconfigure(&mut self, input: Size, output: Size) {
+        self.resizer = fast_image_resize::Resizer::new();
+        let input_len = (input.w * input.h * 4) as usize;
+        self.staging = cap_scale::cpu::Staging::with_capacity(input_len);
+        self.staging.ensure_len(input_len);
+        self.output_buffer = vec![0; (output.w * output.h * 4) as usize];
+        self.output_size = output;
+    }
+}
+
+#[cfg(feature = "rtsp-streaming")]
+#[async_trait]
+impl FrameProcessor for AdaptiveScalingProcessor {
+    /// Picks the output size for the full allowance without spending it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are no candidate targets.
+    ///
+    /// **Time complexity**: O(candidates).
+    async fn initialize(&mut self, input_size: Size) -> Result<Size> {
+        let (plan, _) = self.fit(input_size)?;
+        let output = Size {
+            w: plan.out.w,
+            h: plan.out.h,
+        };
+        self.reconfigure(input_size, output);
+        Ok(output)
+    }
+
+    /// Scales `frame` with the largest plan the remaining budget allows and
+    /// charges its cost to the budget.
+    ///
+    /// **Time complexity**: O(candidates + width × height).
+    async fn process_frame(&mut self, frame: BgraFrame) -> Result<Option<BgraFrame>> {
+        let input = Size {
+            w: frame.width,
+            h: frame.height,
+        };
+        let (plan, tokens) = self.fit(input)?;
+        let output = Size {
+            w: plan.out.w,
+            h: plan.out.h,
+        };
+        if output != self.output_size {
+            self.reconfigure(input, output);
+        }
+        self.budget.spend(tokens);
+
+        cap_scale::cpu::scale_bgra_cpu(
+            &mut self.resizer,
+            &frame.data,
+            cap_scale::presets::Size {
+                w: frame.width,
+                h: frame.height,
+            },
+            Some(frame.stride),
+            &plan,
+            &mut self.output_buffer,
+            Some(&mut self.staging),
+        )?;
+
+        let mut meta = frame.meta;
+        meta.push_transform(Transform::from_plan(&plan));
+        meta.set_attribute("vision_tokens", tokens.to_string());
+
+        Ok(Some(BgraFrame {
+            data: Arc::new(self.output_buffer.clone()),
+            width: output.w,
+            height: output.h,
+            stride: output.w as usize * 4,
+            pts_ns: frame.pts_ns,
+            cursor: frame.cursor.map(|cursor| scale_cursor(cursor, &plan)),
+            meta,
+        }))
+    }
+}
@@ -38,7 +38,9 @@ use async_trait::async_trait;
 #[cfg(feature = "rtsp-streaming")]
 use cap_rtsp::BgraFrame;
 #[cfg(feature = "rtsp-streaming")]
-use cap_scale::presets::TokenPreset;
+use cap_scale::presets::ScaleSpec;
+#[cfg(feature = "rtsp-streaming")]
+use cap_scale::tokens::{TokenBudget, TokenModel};
 #[cfg(feature = "rtsp-streaming")]
 use tokio::sync::watch;
 
@@ -47,7 +49,8 @@ use tokio::sync::watch;
 use crate::processing::processing::GundamProcessor;
 #[cfg(feature = "rtsp-streaming")]
 use crate::processing::{
-    FrameProcessor, ProcessingPipeline, Size, Stream, StreamConfig, StreamFormat, StreamMultiplexer,
+    CursorOverlayProcessor, DamageTracker, FrameProcessor, ProcessingPipeline, Rect, Size, Stream,
+    StreamConfig, StreamFormat, StreamMultiplexer,
 };
 
 /// Abstract interface for frame capture sources.
@@ -82,6 +85,77 @@ pub trait CaptureSource: Send {
     ///
     /// A `Result` indicating success or failure of shutdown.
     async fn shutdown(&mut self) -> Result<()>;
+
+    /// Returns `true` once a finite source (e.g. a file replay) has no more
+    /// frames.
+    ///
+    /// When [`capture_frame`](CaptureSource::capture_frame) fails and this
+    /// returns `true`, the session treats it as end of input and shuts down
+    /// gracefully instead of returning the error. Live sources never finish.
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    /// Regions of the last captured frame that changed since the frame before,
+    /// in frame coordinates.
+    ///
+    /// Sources with native damage reporting (XDamage) return `Some`, with an
+    /// empty slice when nothing changed. The default `None` means unknown; the
+    /// session then compares tile hashes itself when it needs to know (see
+    /// [`CaptureSessionBuilder::with_unchanged_frames`]).
+    fn changed_regions(&self) -> Option<&[Rect]> {
+        None
+    }
+}
+
+/// Lets runtime-selected sources (`Box<dyn CaptureSource>`) be passed to
+/// [`CaptureSessionBuilder::with_capture_source`].
+#[cfg(feature = "rtsp-streaming")]
+#[async_trait]
+impl<S: CaptureSource + ?Sized> CaptureSource for Box<S> {
+    async fn capture_frame(&mut self) -> Result<BgraFrame> {
+        (**self).capture_frame().await
+    }
+
+    fn input_size(&self) -> Size {
+        (**self).input_size()
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        (**self).initialize().await
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        (**self).shutdown().await
+    }
+
+    fn is_finished(&self) -> bool {
+        (**self).is_finished()
+    }
+
+    fn changed_regions(&self) -> Option<&[Rect]> {
+        (**self).changed_regions()
+    }
+}
+
+/// What a session does with captured frames in which nothing changed.
+///
+/// Change detection uses [`CaptureSource::changed_regions`] when the source
+/// reports it, and a [`DamageTracker`] tile-hash comparison otherwise. A
+/// change in [`BgraFrame::cursor`] also counts, since the cursor may be drawn
+/// into the output later. With [`UnchangedFrames::Process`] no detection runs
+/// at all.
+#[cfg(feature = "rtsp-streaming")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnchangedFrames {
+    /// Process and stream every frame (the default)
+    #[default]
+    Process,
+    /// Drop unchanged frames; streams only receive frames with changes
+    Skip,
+    /// Re-send the previous output frame with the new timestamp instead of
+    /// processing again, keeping the output frame rate constant
+    Duplicate,
 }
 
 /// High-level capture session that orchestrates everything.
@@ -91,6 +165,7 @@ pub struct CaptureSession {
     pipeline: ProcessingPipeline,
     multiplexer: StreamMultiplexer,
     capture_source: Box<dyn CaptureSource>,
+    unchanged_frames: UnchangedFrames,
     shutdown_rx: watch::Receiver<bool>,
     shutdown_tx: watch::Sender<bool>,
 }
@@ -102,6 +177,7 @@ impl std::fmt::Debug for CaptureSession {
             .field("pipeline", &self.pipeline)
             .field("multiplexer", &self.multiplexer)
             .field("has_capture_source", &true)
+            .field("unchanged_frames", &self.unchanged_frames)
             .field("shutdown_signaled", &*self.shutdown_rx.borrow())
             .finish()
     }
@@ -170,6 +246,7 @@ impl CaptureSession {
     /// The session will properly clean up all resources when shutdown is requested.
     pub async fn run(mut self) -> Result<()> {
         // Initialize everything
+        self.capture_source.initialize().await?;
         let input_size = self.capture_source.input_size();
         let output_size = self.pipeline.initialize(input_size).await?;
         self.multiplexer.initialize().await?;
@@ -179,6 +256,16 @@ impl CaptureSession {
         println!("  Output: {}x{}", output_size.w, output_size.h);
         println!("  Streams: {}", self.multiplexer.stream_count());
 
+        // Change detection only runs when unchanged frames are skipped or duplicated
+        let mut damage = DamageTracker::default();
+        let mut last_output: Option<BgraFrame> = None;
+        let mut last_cursor = None;
+        let mut unchanged_count = 0u64;
+        let mut frame_index = 0u64;
+        // Streams are set up for the initial output size and told about each
+        // later size change before its first frame
+        let mut stream_size = output_size;
+
         // Main capture loop - zero-copy, non-branching execution
         loop {
             // Check for shutdown signal
@@ -188,13 +275,80 @@ impl CaptureSession {
             }
 
             // Capture frame
-            let raw_frame = self.capture_source.capture_frame().await?;
+            let mut raw_frame = match self.capture_source.capture_frame().await {
+                Ok(frame) => frame,
+                Err(_) if self.capture_source.is_finished() => {
+                    println!("Capture source finished, cleaning up...");
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            // Fill in metadata the source left out; skipped frames count too
+            let meta = &mut raw_frame.meta;
+            meta.frame_index.get_or_insert(frame_index);
+            meta.capture_size
+                .get_or_insert((raw_frame.width, raw_frame.height));
+            frame_index += 1;
+
+            if self.unchanged_frames != UnchangedFrames::Process {
+                let changed = match self.capture_source.changed_regions() {
+                    Some(regions) => !regions.is_empty(),
+                    None => !damage
+                        .update(
+                            &raw_frame.data,
+                            raw_frame.width,
+                            raw_frame.height,
+                            raw_frame.stride,
+                        )
+                        .is_empty(),
+                };
+                // The cursor is not part of the image but may be drawn later
+                let cursor_moved = raw_frame.cursor != last_cursor;
+                last_cursor = raw_frame.cursor.clone();
+                if !changed
+                    && !cursor_moved
+                    && let Some(last) = &last_output
+                {
+                    unchanged_count += 1;
+                    if self.unchanged_frames == UnchangedFrames::Duplicate {
+                        let mut duplicate = BgraFrame {
+                            pts_ns: raw_frame.pts_ns,
+                            ..last.clone()
+                        };
+                        duplicate.meta.frame_index = raw_frame.meta.frame_index;
+                        self.multiplexer.send_frame(duplicate).await?;
+                    }
+                    continue;
+                }
+            }
+
             // Process through pipeline
             let processed_frame = self.pipeline.process_frame(raw_frame).await?;
+            let size = Size {
+                w: processed_frame.width,
+                h: processed_frame.height,
+            };
+            if stream_size != size {
+                self.multiplexer.resize(size).await?;
+                stream_size = size;
+            }
+            if self.unchanged_frames != UnchangedFrames::Process {
+                // Cheap: frames share their pixel buffer
+                last_output = Some(processed_frame.clone());
+            }
             // Send to all streams
             self.multiplexer.send_frame(processed_frame).await?;
         }
 
+        if unchanged_count > 0 {
+            let action = match self.unchanged_frames {
+                UnchangedFrames::Duplicate => "duplicated",
+                _ => "skipped",
+            };
+            println!("Unchanged frames {}: {}", action, unchanged_count);
+        }
+
         // Graceful cleanup
         self.cleanup().await?;
         println!("Capture session shut down gracefully");
@@ -302,6 +456,7 @@ pub struct CaptureSessionBuilder {
     processors: Vec<Box<dyn FrameProcessor>>,
     streams: Vec<Box<dyn Stream>>,
     capture_source: Option<Box<dyn CaptureSource>>,
+    unchanged_frames: UnchangedFrames,
 }
 
 #[cfg(feature = "rtsp-streaming")]
@@ -330,6 +485,7 @@ impl CaptureSessionBuilder {
             processors: Vec::new(),
             streams: Vec::new(),
             capture_source: None,
+            unchanged_frames: UnchangedFrames::default(),
         }
     }
 
@@ -361,28 +517,53 @@ impl CaptureSessionBuilder {
     ///
     /// **Missing functionality**: None - fully implements Gundam processor addition,
     /// though the processor itself may have TODOs for buffer allocation.
-    pub fn with_gundam(mut self) -> Self {
-        self.processors.push(Box::new(GundamProcessor {
-            cfg: cap_scale::gundam::GundamCfg::default(),
-            tile_buffers: Vec::new(),
-            global_buffer: Vec::new(),
-            output_size: Size { w: 0, h: 0 },
-        }));
+    pub fn with_gundam(self) -> Self {
+        self.with_gundam_cfg(cap_scale::gundam::GundamCfg::default())
+    }
+
+    /// Add a Gundam tiling processor with a custom configuration, e.g. tile
+    /// sizes or a [`ResizeQuality`](cap_scale::presets::ResizeQuality) for
+    /// the tiles.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cap_scale::gundam::GundamCfg;
+    /// use cap_scale::presets::ResizeQuality;
+    /// use hybrid_screen_capture::session::CaptureSession;
+    ///
+    /// let session = CaptureSession::builder()
+    ///     .with_gundam_cfg(GundamCfg {
+    ///         quality: ResizeQuality::ocr(),
+    ///         ..Default::default()
+    ///     })
+    ///     .with_rtsp_stream(8554, 1920, 1080, 30)
+    ///     .build();
+    /// ```
+    pub fn with_gundam_cfg(self, cfg: cap_scale::gundam::GundamCfg) -> Self {
+        self.with_gundam_processor(GundamProcessor::new(cfg))
+    }
+
+    /// Add a configured Gundam tiling processor, e.g. one packing tiles on
+    /// several threads with [`GundamProcessor::with_threads`].
+    pub fn with_gundam_processor(mut self, processor: GundamProcessor) -> Self {
+        self.processors.push(Box::new(processor));
         self
     }
 
-    /// Add scaling processor with the specified preset.
+    /// Add scaling processor with the specified preset or spec.
     ///
     /// Configures the session to scale captured frames according to a token-efficient
     /// preset. This reduces the resolution while maintaining aspect ratio, making
     /// the output more suitable for vision-language models that have token limits.
     ///
     /// Different presets optimize for different model input requirements and
-    /// token budgets.
+    /// token budgets. For full control pass a [`ScaleSpec`]: any target size,
+    /// stretching or letterboxing with a pad color, and the resize quality.
     ///
     /// # Parameters
     ///
-    /// * `preset` - The scaling preset to use for token-efficient resizing.
+    /// * `spec` - A [`TokenPreset`], a bare [`ScaleTarget`] or a [`ScaleSpec`].
     ///
     /// # Returns
     ///
@@ -392,26 +573,60 @@ impl CaptureSessionBuilder {
     ///
     /// ```rust,no_run
     /// use hybrid_screen_capture::session::CaptureSession;
-    /// use cap_scale::presets::TokenPreset;
+    /// use cap_scale::presets::{ResizeFilter, ScaleSpec, TokenPreset};
     ///
     /// let session = CaptureSession::builder()
     ///     .with_scaling(TokenPreset::P4_Long640)  // Scale for GPT-4V
     ///     .with_file_output("output.mp4".to_string(), 1920, 1080, 30)
     ///     // ... other configuration
     ///     .build();
+    ///
+    /// // 896px square with black bars for a fixed-size model
+    /// let letterboxed = CaptureSession::builder()
+    ///     .with_scaling(ScaleSpec::letterbox(896, [0, 0, 0, 255]).with_filter(ResizeFilter::CatmullRom))
+    ///     .with_file_output("output.mp4".to_string(), 896, 896, 30)
+    ///     .build();
     /// ```
     ///
+    /// [`ScaleTarget`]: cap_scale::presets::ScaleTarget
+    /// [`TokenPreset`]: cap_scale::presets::TokenPreset
+    ///
     /// **Missing functionality**: None - fully implements scaling processor addition
     /// with preset-based configuration and SIMD acceleration.
-    pub fn with_scaling(mut self, preset: TokenPreset) -> Self {
+    pub fn with_scaling(mut self, spec: impl Into<ScaleSpec>) -> Self {
         use crate::processing::processing::ScalingProcessor;
-        self.processors.push(Box::new(ScalingProcessor {
-            preset,
-            resizer: fast_image_resize::Resizer::new(),
-            staging: cap_scale::cpu::Staging::with_capacity(1920 * 1080 * 4), // Pre-allocate for HD
-            output_buffer: Vec::new(),
-            output_size: Size { w: 0, h: 0 },
-        }));
+        self.processors.push(Box::new(ScalingProcessor::new(spec)));
+        self
+    }
+
+    /// Add a scaling processor that keeps frames within a token budget.
+    ///
+    /// Each frame is scaled to the largest size whose cost for `model` fits
+    /// `budget`; see [`AdaptiveScalingProcessor`]. When the size changes the
+    /// session tells every stream through [`Stream::resize`] before sending
+    /// the first frame of the new size.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use cap_scale::tokens::{TokenBudget, QWEN};
+    /// use hybrid_screen_capture::session::CaptureSession;
+    ///
+    /// let session = CaptureSession::builder()
+    ///     .with_adaptive_scaling(Box::new(QWEN), TokenBudget::PerFrame(400))
+    ///     .with_file_output("output.mp4".to_string(), 1024, 576, 30)
+    ///     .build();
+    /// ```
+    ///
+    /// [`AdaptiveScalingProcessor`]: crate::processing::AdaptiveScalingProcessor
+    pub fn with_adaptive_scaling(
+        mut self,
+        model: Box<dyn TokenModel + Send + Sync>,
+        budget: TokenBudget,
+    ) -> Self {
+        use crate::processing::processing::AdaptiveScalingProcessor;
+        self.processors
+            .push(Box::new(AdaptiveScalingProcessor::new(model, budget)));
         self
     }
 
@@ -599,6 +814,67 @@ impl CaptureSessionBuilder {
         self
     }
 
+    /// Choose what happens to frames in which nothing changed.
+    ///
+    /// Static screens produce long runs of identical frames. Skipping them
+    /// saves processing and encoding and lets VLM consumers see only frames
+    /// with changes; duplicating them saves processing while keeping a
+    /// constant output frame rate for players and encoders.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hybrid_screen_capture::session::{CaptureSession, UnchangedFrames};
+    /// use hybrid_screen_capture::capture::session_sources::X11ShmCaptureSource;
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session = CaptureSession::builder()
+    ///     .with_capture_source(X11ShmCaptureSource::new(":0")?)
+    ///     .with_unchanged_frames(UnchangedFrames::Skip)
+    ///     .with_file_output("output.mp4".to_string(), 1920, 1080, 30)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// **Time complexity**: O(1) to configure; at run time, sources without
+    /// damage reporting add one O(width * height) hashing pass per frame.
+    pub fn with_unchanged_frames(mut self, policy: UnchangedFrames) -> Self {
+        self.unchanged_frames = policy;
+        self
+    }
+
+    /// Draw the cursor reported by the capture source into each frame.
+    ///
+    /// The overlay always runs first in the pipeline, before scaling or
+    /// tiling, so the cursor is drawn at capture resolution regardless of the
+    /// order of builder calls. Only sources that report
+    /// [`BgraFrame::cursor`] are affected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use hybrid_screen_capture::session::CaptureSession;
+    /// use hybrid_screen_capture::processing::{ClickHighlight, CursorOverlayProcessor};
+    /// use hybrid_screen_capture::capture::session_sources::X11ShmCaptureSource;
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let overlay = CursorOverlayProcessor::new().with_click_highlight(ClickHighlight::default());
+    /// let session = CaptureSession::builder()
+    ///     .with_capture_source(X11ShmCaptureSource::new(":0")?)
+    ///     .with_cursor_overlay(overlay)
+    ///     .with_file_output("output.mp4".to_string(), 1920, 1080, 30)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// **Time complexity**: O(1) to configure; O(cursor area) per frame.
+    pub fn with_cursor_overlay(mut self, overlay: CursorOverlayProcessor) -> Self {
+        self.processors.insert(0, Box::new(overlay));
+        self
+    }
+
     /// Build the capture session with the configured components.
     ///
     /// Assembles all the configured processors, streams, and capture source into
@@ -676,6 +952,7 @@ impl CaptureSessionBuilder {
             pipeline,
             multiplexer,
             capture_source,
+            unchanged_frames: self.unchanged_frames,
             shutdown_tx,
             shutdown_rx,
         })
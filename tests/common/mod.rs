@@ -131,28 +131,6 @@ pub mod test_frames {
             size,
         }
     }
-
-    /// Create a gradient frame for testing scaling
-    pub fn create_gradient_frame(size: Size) -> BgraFrame {
-        let pixel_count = (size.width * size.height) as usize;
-        let mut data = vec![0u8; pixel_count * 4];
-
-        for y in 0..size.height {
-            for x in 0..size.width {
-                let pixel_index = ((y * size.width + x) * 4) as usize;
-                let r = ((x as f32 / size.width as f32) * 255.0) as u8;
-                let g = ((y as f32 / size.height as f32) * 255.0) as u8;
-                let b = 128u8; // Constant blue channel
-
-                data[pixel_index..pixel_index + 4].copy_from_slice(&[b, g, r, 255]);
-            }
-        }
-
-        BgraFrame {
-            data: Arc::new(data),
-            size,
-        }
-    }
 }
 
 /// Custom assertions for testing
@@ -82,6 +82,8 @@ mod mock_components {
                 height: self.height,
                 stride: self.width as usize * 4,
                 pts_ns: Some(*count as u64 * 1000000),
+                cursor: None,
+                meta: cap_rtsp::FrameMeta::default(),
             })
         }
 
@@ -110,6 +110,8 @@ impl hybrid_screen_capture::session::CaptureSource for FaultyCaptureSource {
             data: std::sync::Arc::new(data),
             stride: 4,       // 4 bytes per pixel (BGRA)
             pts_ns: Some(0), // Presentation timestamp
+            cursor: None,
+            meta: cap_rtsp::FrameMeta::default(),
         };
 
         // Stop after enough frames to test error recovery
@@ -107,6 +107,8 @@ impl hybrid_screen_capture::session::CaptureSource for MockCaptureSource {
             data: std::sync::Arc::new(data),
             stride: 4,       // 4 bytes per pixel (BGRA)
             pts_ns: Some(0), // Presentation timestamp
+            cursor: None,
+            meta: cap_rtsp::FrameMeta::default(),
         };
 
         // Stop after a few frames to prevent infinite loop in tests
@@ -104,6 +104,8 @@ impl CaptureSource for MockCaptureSource {
             height: self.height,
             stride: (self.width * 4) as usize,
             pts_ns: Some(*count as u64 * 1000000), // 1ms per frame
+            cursor: None,
+            meta: cap_rtsp::FrameMeta::default(),
         })
     }
 
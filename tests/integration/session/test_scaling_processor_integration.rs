@@ -24,7 +24,7 @@ async fn test_scaling_processor_creation() {
     for preset in presets {
         // Create a scaling processor (this mirrors the implementation in session.rs)
         let mut processor = hybrid_screen_capture::processing::ScalingProcessor {
-            preset,
+            spec: preset.into(),
             resizer: Resizer::new(),
             staging: Staging::with_capacity(1920 * 1080 * 4),
             output_buffer: Vec::new(),
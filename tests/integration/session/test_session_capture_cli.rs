@@ -50,7 +50,7 @@
 //! - Resources are properly cleaned up after session completion
 
 #[cfg(feature = "rtsp-streaming")]
-use cap_rtsp::BgraFrame;
+use cap_rtsp::{BgraFrame, FrameMeta};
 use hybrid_screen_capture::processing::Size;
 #[cfg(feature = "rtsp-streaming")]
 use hybrid_screen_capture::session::*;
@@ -144,74 +144,6 @@ async fn test_scrap_capture_source() {
         .expect("Failed to shutdown ScrapCaptureSource");
 }
 
-// Test FFmpegCaptureSource implementation for Linux
-#[cfg(target_os = "linux")]
-#[test]
-async fn test_ffmpeg_capture_source() {
-    #[cfg(feature = "rtsp-streaming")]
-    use hybrid_screen_capture::capture::session_sources::FFmpegCaptureSource;
-
-    // Test FFmpegCaptureSource creation
-    let mut source =
-        FFmpegCaptureSource::new(":0.0").expect("Failed to create FFmpegCaptureSource");
-
-    // Test input size reporting
-    let input_size = source.input_size();
-    assert!(input_size.w > 0, "Input width should be positive");
-    assert!(input_size.h > 0, "Input height should be positive");
-
-    // Test initialization
-    source
-        .initialize()
-        .await
-        .expect("Failed to initialize FFmpegCaptureSource");
-
-    // Test frame capture (synthetic implementation)
-    let frame = source
-        .capture_frame()
-        .await
-        .expect("Failed to capture frame");
-    assert_eq!(
-        frame.width as u32, input_size.w,
-        "Frame width should match input size"
-    );
-    assert_eq!(
-        frame.height as u32, input_size.h,
-        "Frame height should match input size"
-    );
-    assert!(!frame.data.is_empty(), "Frame data should not be empty");
-
-    // Verify synthetic gradient pattern (check corners have different colors)
-    let data = &frame.data;
-    let w = frame.width as usize;
-    let h = frame.height as usize;
-
-    // Top-left corner (should be dark red, light green)
-    let tl_idx = 0;
-    let tl_red = data[tl_idx + 2];
-    let tl_green = data[tl_idx + 1];
-
-    // Bottom-right corner (should be light red, dark green)
-    let br_idx = ((h - 1) * w + (w - 1)) * 4;
-    let br_red = data[br_idx + 2];
-    let br_green = data[br_idx + 1];
-
-    assert_ne!(
-        tl_red, br_red,
-        "Gradient pattern should create different red values"
-    );
-    assert_ne!(
-        tl_green, br_green,
-        "Gradient pattern should create different green values"
-    );
-
-    // Test shutdown
-    source
-        .shutdown()
-        .await
-        .expect("Failed to shutdown FFmpegCaptureSource");
-}
-
 // Test session builder integration with capture sources
 #[cfg(feature = "rtsp-streaming")]
 #[test]
@@ -250,6 +182,8 @@ async fn test_session_builder_with_capture_source() {
                 height: self.size.h,
                 stride: self.size.w as usize * 4,
                 pts_ns: None,
+                cursor: None,
+                meta: FrameMeta::default(),
             })
         }
 
@@ -310,6 +244,8 @@ async fn test_session_builder_with_gundam_processing() {
                 height: self.size.h,
                 stride: self.size.w as usize * 4,
                 pts_ns: None,
+                cursor: None,
+                meta: FrameMeta::default(),
             })
         }
 
@@ -381,6 +317,8 @@ async fn test_session_builder_with_scaling_processing() {
                 height: self.size.h,
                 stride: self.size.w as usize * 4,
                 pts_ns: None,
+                cursor: None,
+                meta: FrameMeta::default(),
             })
         }
 
@@ -467,6 +405,8 @@ async fn test_session_builder_invalid_configuration() {
                 height: self.size.h,
                 stride: self.size.w as usize * 4,
                 pts_ns: None,
+                cursor: None,
+                meta: FrameMeta::default(),
             })
         }
 
@@ -507,8 +447,15 @@ async fn test_platform_capture_source_selection() {
         // Should use FFmpegCaptureSource
         #[cfg(feature = "rtsp-streaming")]
         use hybrid_screen_capture::capture::session_sources::FFmpegCaptureSource;
-        let _source =
-            FFmpegCaptureSource::new(":0.0").expect("Should create FFmpegCaptureSource on Linux");
+        // Construction connects to the display, so headless hosts report an
+        // initialization error instead of a source.
+        if let Err(e) = FFmpegCaptureSource::new(":0.0") {
+            assert!(
+                matches!(e, hybrid_screen_capture::error::CaptureError::CaptureInit { .. }),
+                "unexpected error: {}",
+                e
+            );
+        }
     }
 }
 
@@ -581,6 +528,8 @@ async fn test_session_configuration_validation() {
                 height: self.size.h,
                 stride: self.size.w as usize * 4,
                 pts_ns: None,
+                cursor: None,
+                meta: FrameMeta::default(),
             })
         }
 
@@ -0,0 +1,204 @@
+//! Tests for budget-driven adaptive scaling and frame size changes.
+
+#![cfg(feature = "rtsp-streaming")]
+
+use anyhow::Result;
+use async_trait::async_trait;
+use cap_rtsp::{BgraFrame, FrameMeta};
+use cap_scale::presets::ScaleTarget;
+use cap_scale::tokens::{QWEN, TokenBudget, TokenModel};
+use hybrid_screen_capture::processing::{
+    AdaptiveScalingProcessor, ProcessingPipeline, ScalingProcessor, Size, Stream, StreamConfig,
+    StreamFormat, StreamMultiplexer,
+};
+use std::sync::Arc;
+
+fn frame(w: u32, h: u32) -> BgraFrame {
+    BgraFrame {
+        data: Arc::new(vec![128; (w * h * 4) as usize]),
+        width: w,
+        height: h,
+        stride: w as usize * 4,
+        pts_ns: None,
+        cursor: None,
+        meta: FrameMeta::default(),
+    }
+}
+
+fn tokens(frame: &BgraFrame) -> u64 {
+    frame
+        .meta
+        .attribute("vision_tokens")
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn frames_stay_within_per_frame_budget() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline
+        .processors
+        .push(Box::new(AdaptiveScalingProcessor::new(
+            Box::new(QWEN),
+            TokenBudget::PerFrame(300),
+        )));
+    let size = pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+
+    let out = pipeline.process_frame(frame(1920, 1080)).await?;
+    assert_eq!((out.width, out.height), (size.w, size.h));
+    assert!(tokens(&out) <= 300);
+    assert_eq!(
+        QWEN.image_tokens(cap_scale::presets::Size {
+            w: out.width,
+            h: out.height
+        }),
+        tokens(&out)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn size_change_reinitializes_downstream() -> Result<()> {
+    let input = cap_scale::presets::Size { w: 1920, h: 1080 };
+    let plan = |side| {
+        cap_scale::presets::build_plan(
+            input,
+            ScaleTarget::MaxLongSide(side),
+            cap_scale::presets::AspectMode::Preserve,
+        )
+    };
+    let (big, small) = (QWEN.plan_tokens(&plan(1280)), QWEN.plan_tokens(&plan(256)));
+
+    // Enough for 59 small frames and one big one: the big frame only fits
+    // once the rest of the minute has been spent on small ones
+    let adaptive = AdaptiveScalingProcessor::new(
+        Box::new(QWEN),
+        TokenBudget::PerMinute {
+            tokens: 59 * small + big,
+            fps: 1,
+        },
+    )
+    .with_candidates(vec![
+        ScaleTarget::MaxLongSide(1280),
+        ScaleTarget::MaxLongSide(256),
+    ]);
+
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline.processors.push(Box::new(adaptive));
+    pipeline.processors.push(Box::new(ScalingProcessor {
+        spec: cap_scale::presets::TokenPreset::P4_Long640.into(),
+        resizer: fast_image_resize::Resizer::new(),
+        staging: cap_scale::cpu::Staging::with_capacity(0),
+        output_buffer: Vec::new(),
+        output_size: Size { w: 0, h: 0 },
+    }));
+    pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+    assert_eq!(pipeline.output_size(), Some(Size { w: 256, h: 144 }));
+
+    let mut spent = 0;
+    for _ in 0..59 {
+        let out = pipeline.process_frame(frame(1920, 1080)).await?;
+        assert_eq!((out.width, out.height), (256, 144));
+        spent += tokens(&out);
+    }
+    let out = pipeline.process_frame(frame(1920, 1080)).await?;
+    spent += tokens(&out);
+
+    // The downstream scaler was set up again for 1280x720 input
+    assert_eq!((out.width, out.height), (640, 360));
+    assert_eq!(pipeline.output_size(), Some(Size { w: 640, h: 360 }));
+    assert_eq!(spent, 59 * small + big);
+    Ok(())
+}
+
+/// Stream that accepts any size and checks frames against its config.
+struct ResizableStream {
+    config: StreamConfig,
+}
+
+#[async_trait]
+impl Stream for ResizableStream {
+    async fn send_frame(&mut self, frame: BgraFrame) -> Result<()> {
+        assert_eq!(
+            (frame.width, frame.height),
+            (self.config.width, self.config.height)
+        );
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn config(&self) -> &StreamConfig {
+        &self.config
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn resize(&mut self, size: Size) -> Result<()> {
+        self.config.width = size.w;
+        self.config.height = size.h;
+        Ok(())
+    }
+}
+
+fn config(width: u32, height: u32) -> StreamConfig {
+    StreamConfig {
+        width,
+        height,
+        fps: 30,
+        format: StreamFormat::File {
+            path: "unused.mp4".to_string(),
+        },
+    }
+}
+
+#[tokio::test]
+async fn multiplexer_resizes_mismatched_streams() -> Result<()> {
+    let mut multiplexer = StreamMultiplexer::new();
+    multiplexer.streams.push(Box::new(ResizableStream {
+        config: config(640, 360),
+    }));
+    multiplexer.resize(Size { w: 640, h: 360 }).await?;
+    multiplexer.resize(Size { w: 320, h: 180 }).await?;
+    multiplexer.send_frame(frame(320, 180)).await?;
+    assert_eq!(multiplexer.streams[0].config().width, 320);
+    Ok(())
+}
+
+/// Stream using the default [`Stream::resize`].
+struct FixedStream(StreamConfig);
+
+#[async_trait]
+impl Stream for FixedStream {
+    async fn send_frame(&mut self, _frame: BgraFrame) -> Result<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn config(&self) -> &StreamConfig {
+        &self.0
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn streams_refuse_sizes_they_cannot_accept() -> Result<()> {
+    let mut multiplexer = StreamMultiplexer::new();
+    multiplexer
+        .streams
+        .push(Box::new(FixedStream(config(640, 360))));
+    multiplexer.resize(Size { w: 640, h: 360 }).await?;
+    assert!(multiplexer.resize(Size { w: 320, h: 180 }).await.is_err());
+    Ok(())
+}
@@ -0,0 +1,202 @@
+//! Tests for compositing several capture sources into one frame.
+
+#![cfg(all(feature = "rtsp-streaming", feature = "screen-capture"))]
+
+use anyhow::Result;
+use async_trait::async_trait;
+use cap_rtsp::{BgraFrame, FrameMeta, Transform};
+use cap_scale::gundam::Rect as GRect;
+use cap_scale::presets::{Point, Size as ScaleSize};
+use cap_scale::transform::ViewKind;
+use hybrid_screen_capture::capture::session_sources::CompositeCaptureSource;
+use hybrid_screen_capture::processing::{Rect, Size};
+use hybrid_screen_capture::session::CaptureSource;
+use std::sync::Arc;
+
+/// Source producing solid frames of one BGRA color.
+struct SolidSource {
+    size: Size,
+    color: [u8; 4],
+    pts_ns: u64,
+    meta: FrameMeta,
+}
+
+#[async_trait]
+impl CaptureSource for SolidSource {
+    async fn capture_frame(&mut self) -> Result<BgraFrame> {
+        self.pts_ns += 1_000;
+        Ok(BgraFrame {
+            data: Arc::new(self.color.repeat((self.size.w * self.size.h) as usize)),
+            width: self.size.w,
+            height: self.size.h,
+            stride: self.size.w as usize * 4,
+            pts_ns: Some(self.pts_ns),
+            cursor: None,
+            meta: self.meta.clone(),
+        })
+    }
+
+    fn input_size(&self) -> Size {
+        self.size
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn solid(w: u32, h: u32, color: [u8; 4]) -> Box<dyn CaptureSource> {
+    solid_with_meta(w, h, color, FrameMeta::default())
+}
+
+fn solid_with_meta(w: u32, h: u32, color: [u8; 4], meta: FrameMeta) -> Box<dyn CaptureSource> {
+    Box::new(SolidSource {
+        size: Size { w, h },
+        color,
+        pts_ns: 0,
+        meta,
+    })
+}
+
+fn rect(x: u32, y: u32, w: u32, h: u32) -> cap_scale::gundam::Rect {
+    cap_scale::gundam::Rect { x, y, w, h }
+}
+
+/// Input rectangles of the single mosaic recorded for `frame`, in view order.
+fn mosaic_views(frame: &BgraFrame) -> (ScaleSize, Vec<(ViewKind, GRect, GRect)>) {
+    match frame.meta.transforms.as_slice() {
+        [Transform::Mosaic { input, views, .. }] => (
+            *input,
+            views
+                .iter()
+                .map(|view| (view.kind, view.source, view.dest))
+                .collect(),
+        ),
+        other => panic!("expected one mosaic, got {:?}", other),
+    }
+}
+
+fn pixel(frame: &BgraFrame, x: u32, y: u32) -> [u8; 4] {
+    let i = y as usize * frame.stride + x as usize * 4;
+    frame.data[i..i + 4].try_into().unwrap()
+}
+
+#[tokio::test]
+async fn places_sources_side_by_side() -> Result<()> {
+    let red = [0, 0, 255, 255];
+    let blue = [255, 0, 0, 255];
+    let mut composite =
+        CompositeCaptureSource::side_by_side(vec![solid(4, 2, red), solid(3, 3, blue)]);
+    composite.initialize().await?;
+
+    let size = composite.input_size();
+    assert_eq!((size.w, size.h), (7, 3));
+    assert_eq!(
+        composite.placements(),
+        &[
+            Rect {
+                x: 0,
+                y: 0,
+                w: 4,
+                h: 2
+            },
+            Rect {
+                x: 4,
+                y: 0,
+                w: 3,
+                h: 3
+            },
+        ]
+    );
+
+    let frame = composite.capture_frame().await?;
+    assert_eq!((frame.width, frame.height), (7, 3));
+    assert_eq!(pixel(&frame, 0, 0), red);
+    assert_eq!(pixel(&frame, 3, 1), red);
+    assert_eq!(pixel(&frame, 4, 0), blue);
+    assert_eq!(pixel(&frame, 6, 2), blue);
+    // Shorter inputs leave an opaque black background below them
+    assert_eq!(pixel(&frame, 0, 2), [0, 0, 0, 255]);
+    assert_eq!(frame.pts_ns, Some(1_000));
+
+    composite.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn records_a_mosaic_keeping_input_crops() -> Result<()> {
+    // The first input shows region 2,1,4,2 of a 10x6 screen
+    let mut cropped = FrameMeta::from_source("x11-shm");
+    cropped.push_transform(Transform::Crop {
+        input: ScaleSize { w: 10, h: 6 },
+        rect: rect(2, 1, 4, 2),
+    });
+    let mut composite = CompositeCaptureSource::side_by_side(vec![
+        solid_with_meta(4, 2, [0; 4], cropped),
+        solid(3, 3, [0; 4]),
+    ]);
+    composite.initialize().await?;
+
+    let frame = composite.capture_frame().await?;
+    let (input, views) = mosaic_views(&frame);
+    // Screens side by side: 10x6 then 3x3
+    assert_eq!(input, ScaleSize { w: 13, h: 6 });
+    assert_eq!(
+        views,
+        [
+            (ViewKind::Input(0), rect(2, 1, 4, 2), rect(0, 0, 4, 2)),
+            (ViewKind::Input(1), rect(10, 0, 3, 3), rect(4, 0, 3, 3)),
+        ]
+    );
+    assert_eq!(
+        frame.meta.map_to_screen(Point { x: 1.0, y: 1.0 }),
+        Some(Point { x: 3.0, y: 2.0 })
+    );
+    assert_eq!(frame.meta.attribute("input.0.source"), Some("x11-shm"));
+    assert_eq!(frame.meta.attribute("desktop_origin"), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn lays_portal_streams_out_on_the_desktop() -> Result<()> {
+    let desktop = |x: i32, y: i32, w: u32, h: u32| {
+        let mut meta = FrameMeta::from_source("pipewire");
+        meta.set_attribute("desktop_position", format!("{},{}", x, y));
+        meta.set_attribute("desktop_size", format!("{},{}", w, h));
+        meta
+    };
+    // A HiDPI monitor left of the origin (4x2 pixels for 8x4 logical) and
+    // a monitor below and right of it
+    let mut composite = CompositeCaptureSource::side_by_side(vec![
+        solid_with_meta(4, 2, [0; 4], desktop(-8, 0, 8, 4)),
+        solid_with_meta(3, 3, [0; 4], desktop(0, 2, 3, 3)),
+    ]);
+    composite.initialize().await?;
+
+    let frame = composite.capture_frame().await?;
+    let (input, views) = mosaic_views(&frame);
+    assert_eq!(input, ScaleSize { w: 11, h: 5 });
+    assert_eq!(
+        views,
+        [
+            (ViewKind::Input(0), rect(0, 0, 8, 4), rect(0, 0, 4, 2)),
+            (ViewKind::Input(1), rect(8, 2, 3, 3), rect(4, 0, 3, 3)),
+        ]
+    );
+    assert_eq!(frame.meta.attribute("desktop_origin"), Some("-8,0"));
+    assert_eq!(
+        frame.meta.attribute("input.1.desktop_position"),
+        Some("0,2")
+    );
+    // Composite pixel 5,1 is pixel 1,1 of the second monitor: desktop 1,3
+    // once shifted by the origin
+    assert_eq!(
+        frame.meta.map_to_screen(Point { x: 5.0, y: 1.0 }),
+        Some(Point { x: 9.0, y: 3.0 })
+    );
+    Ok(())
+}
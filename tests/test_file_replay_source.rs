@@ -0,0 +1,159 @@
+//! Integration tests for replaying recorded video through a capture source.
+//!
+//! A short MP4 is generated with GStreamer (`videotestsrc ! x264enc ! mp4mux`),
+//! so these tests need the GStreamer base/good/ugly plugins installed. They
+//! are ignored by default:
+//!
+//! ```bash
+//! cargo test --features rtsp-streaming --test test_file_replay_source -- --ignored
+//! ```
+
+#![cfg(all(
+    feature = "rtsp-streaming",
+    feature = "screen-capture",
+    target_os = "linux"
+))]
+
+use anyhow::Result;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use hybrid_screen_capture::capture::session_sources::{FileReplayCaptureSource, ReplayPacing};
+use hybrid_screen_capture::processing::Rect;
+use hybrid_screen_capture::session::CaptureSource;
+use std::path::Path;
+use std::time::Duration;
+
+const FRAMES: u32 = 30;
+const FPS: u32 = 30;
+
+/// Encodes `FRAMES` frames of 160x120 test video into `path`.
+fn write_test_video(path: &Path) -> Result<()> {
+    gst::init()?;
+    let pipeline = gst::parse::launch(&format!(
+        "videotestsrc num-buffers={FRAMES} ! video/x-raw,width=160,height=120,framerate={FPS}/1 \
+         ! x264enc ! mp4mux ! filesink location={}",
+        path.display()
+    ))?;
+    pipeline.set_state(gst::State::Playing)?;
+    let bus = pipeline.bus().expect("pipeline has a bus");
+    for msg in bus.iter_timed(gst::ClockTime::from_seconds(30)) {
+        match msg.view() {
+            gst::MessageView::Eos(..) => break,
+            gst::MessageView::Error(err) => anyhow::bail!("encoding failed: {}", err.error()),
+            _ => {}
+        }
+    }
+    pipeline.set_state(gst::State::Null)?;
+    Ok(())
+}
+
+/// Captures until the source reports the end of the file.
+async fn drain(source: &mut FileReplayCaptureSource, limit: usize) -> Result<Vec<u64>> {
+    let mut pts = Vec::new();
+    while pts.len() < limit {
+        match source.capture_frame().await {
+            Ok(frame) => pts.push(frame.pts_ns.expect("decoded frames carry a timestamp")),
+            Err(_) if source.is_finished() => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(pts)
+}
+
+fn assert_monotonic(pts: &[u64]) {
+    for pair in pts.windows(2) {
+        assert!(pair[1] > pair[0], "pts must be monotonic: {:?}", pair);
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires GStreamer with x264enc and mp4mux"]
+async fn replays_every_frame_with_original_timestamps() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("replay.mp4");
+    write_test_video(&path)?;
+
+    let mut source =
+        FileReplayCaptureSource::new(&path)?.with_pacing(ReplayPacing::AsFastAsPossible);
+    source.initialize().await?;
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (160, 120));
+
+    let pts = drain(&mut source, 1_000).await?;
+    assert_eq!(pts.len(), FRAMES as usize);
+    assert_eq!(pts[0], 0);
+    assert_monotonic(&pts);
+    assert!(source.is_finished());
+
+    source.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires GStreamer with x264enc and mp4mux"]
+async fn loops_from_start_offset_with_continuous_timestamps() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("replay.mp4");
+    write_test_video(&path)?;
+
+    // Skip the first half second (15 frames)
+    let offset = Duration::from_millis(500);
+    let mut source = FileReplayCaptureSource::new(&path)?
+        .with_pacing(ReplayPacing::AsFastAsPossible)
+        .with_start_offset(offset)
+        .with_looping(true);
+    source.initialize().await?;
+
+    let pts = drain(&mut source, 3 * FRAMES as usize).await?;
+    assert_eq!(pts.len(), 3 * FRAMES as usize, "looping never finishes");
+    assert!(!source.is_finished());
+    assert_eq!(pts[0], offset.as_nanos() as u64);
+    assert_monotonic(&pts);
+
+    source.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires GStreamer with x264enc and mp4mux"]
+async fn region_crops_replayed_frames() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("replay.mp4");
+    write_test_video(&path)?;
+
+    let region = Rect {
+        x: 16,
+        y: 8,
+        w: 64,
+        h: 48,
+    };
+    let mut source = FileReplayCaptureSource::new(&path)?
+        .with_pacing(ReplayPacing::AsFastAsPossible)
+        .with_region(region);
+    source.initialize().await?;
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (64, 48));
+
+    let frame = source.capture_frame().await?;
+    assert_eq!((frame.width, frame.height, frame.stride), (64, 48, 256));
+    assert_eq!(frame.meta.transforms.len(), 1);
+    assert!(frame.meta.attribute("uri").is_some());
+
+    source.shutdown().await?;
+
+    // The video size is only known after decoding, so misfits fail there
+    let mut source = FileReplayCaptureSource::new(&path)?.with_region(Rect {
+        x: 120,
+        y: 0,
+        w: 64,
+        h: 48,
+    });
+    assert!(source.initialize().await.is_err());
+    source.shutdown().await?;
+    Ok(())
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    assert!(FileReplayCaptureSource::new("/nonexistent/replay.mp4").is_err());
+}
@@ -0,0 +1,324 @@
+//! Tests for frame metadata travelling through the processing pipeline.
+
+#![cfg(feature = "rtsp-streaming")]
+
+use anyhow::Result;
+use async_trait::async_trait;
+use cap_rtsp::{
+    BgraFrame, CompositeLayout, CursorInfo, FrameMeta, GlobalPlacement, GundamCompositor,
+    GundamFrameSet, Point, Transform, ViewKind,
+};
+use cap_scale::cpu::Staging;
+use cap_scale::gundam::GundamCfg;
+use cap_scale::presets::{ScaleSpec, TokenPreset};
+use fast_image_resize::Resizer;
+use hybrid_screen_capture::processing::{
+    GundamProcessor, GundamRender, ProcessingPipeline, ScalingProcessor, Size, Stream,
+    StreamConfig, StreamFormat, StreamMultiplexer,
+};
+use std::sync::{Arc, Mutex};
+
+fn frame(w: u32, h: u32) -> BgraFrame {
+    let mut meta = FrameMeta::from_source("test");
+    meta.frame_index = Some(7);
+    BgraFrame {
+        data: Arc::new(vec![128; (w * h * 4) as usize]),
+        width: w,
+        height: h,
+        stride: w as usize * 4,
+        pts_ns: None,
+        cursor: None,
+        meta,
+    }
+}
+
+fn size(w: u32, h: u32) -> cap_scale::presets::Size {
+    cap_scale::presets::Size { w, h }
+}
+
+fn scaling() -> ScalingProcessor {
+    ScalingProcessor {
+        spec: TokenPreset::P4_Long640.into(),
+        resizer: Resizer::new(),
+        staging: Staging::with_capacity(1920 * 1080 * 4),
+        output_buffer: Vec::new(),
+        output_size: Size { w: 0, h: 0 },
+    }
+}
+
+fn gundam() -> GundamProcessor {
+    GundamProcessor {
+        cfg: GundamCfg::default(),
+        tile_buffers: Vec::new(),
+        global_buffer: Arc::default(),
+        output_size: Size { w: 0, h: 0 },
+        pool: None,
+        render: GundamRender::Mosaic,
+        compositor: GundamCompositor::default(),
+    }
+}
+
+#[tokio::test]
+async fn scaling_appends_scale_transform() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline.processors.push(Box::new(scaling()));
+    pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+
+    let out = pipeline.process_frame(frame(1920, 1080)).await?;
+    assert_eq!(out.meta.source.as_deref(), Some("test"));
+    assert_eq!(out.meta.frame_index, Some(7));
+    assert_eq!(out.meta.transforms.len(), 1);
+
+    let transform = &out.meta.transforms[0];
+    assert!(matches!(transform, Transform::Scale { .. }));
+    assert_eq!(transform.input_size(), size(1920, 1080));
+    assert_eq!(transform.output_size(), size(out.width, out.height));
+
+    let center = Point {
+        x: out.width as f64 / 2.0,
+        y: out.height as f64 / 2.0,
+    };
+    assert_eq!(
+        out.meta.map_to_screen(center),
+        Some(Point { x: 960.0, y: 540.0 })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn gundam_mosaic_describes_composite() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline.processors.push(Box::new(gundam()));
+    pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+
+    let out = pipeline.process_frame(frame(1920, 1080)).await?;
+    let [
+        Transform::Mosaic {
+            input,
+            output,
+            views,
+        },
+    ] = out.meta.transforms.as_slice()
+    else {
+        panic!("expected one mosaic, got {:?}", out.meta.transforms);
+    };
+    assert_eq!(*input, size(1920, 1080));
+    assert_eq!(*output, size(out.width, out.height));
+
+    let globals = views.iter().filter(|v| v.kind == ViewKind::Global).count();
+    assert_eq!(globals, 1);
+    // One score per tile view
+    let scores = out.meta.attribute("gundam_tile_scores").unwrap();
+    assert_eq!(scores.split(',').count(), views.len() - globals);
+    for view in views {
+        assert!(view.dest.x + view.dest.w <= output.w);
+        assert!(view.dest.y + view.dest.h <= output.h);
+        assert!(view.source.x + view.source.w <= input.w);
+        assert!(view.source.y + view.source.h <= input.h);
+    }
+
+    // The separate views travel along with the mosaic
+    let set = out.meta.gundam.as_ref().expect("frame set");
+    assert_eq!(set.tiles.len(), views.len() - globals);
+    assert_eq!(
+        set.rects(),
+        views[..set.tiles.len()]
+            .iter()
+            .map(|v| v.source)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(set.global.len(), 1024 * 1024 * 4);
+    assert!(set.tiles.iter().all(|t| t.data.len() == 640 * 640 * 4));
+    Ok(())
+}
+
+#[tokio::test]
+async fn gundam_global_render_shares_full_resolution_view() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline
+        .processors
+        .push(Box::new(gundam().with_render(GundamRender::Global)));
+    let size = pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+    assert_eq!(size, Size { w: 1024, h: 1024 });
+
+    let out = pipeline.process_frame(frame(1920, 1080)).await?;
+    assert_eq!((out.width, out.height), (1024, 1024));
+    let set = out.meta.gundam.clone().expect("frame set");
+    assert!(Arc::ptr_eq(&out.data, &set.global));
+    assert_eq!(
+        out.meta.map_to_screen(Point { x: 512.0, y: 512.0 }),
+        Some(Point { x: 960.0, y: 540.0 })
+    );
+
+    // A set still held by a consumer is not overwritten by the next frame
+    let next = pipeline.process_frame(frame(1920, 1080)).await?;
+    assert!(!Arc::ptr_eq(&next.data, &set.global));
+    Ok(())
+}
+
+#[tokio::test]
+async fn gundam_mosaic_follows_compositor_layout() -> Result<()> {
+    let layout = CompositeLayout {
+        global: GlobalPlacement::Beside,
+        bg_rgba: [0, 0, 0, 255],
+    };
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline
+        .processors
+        .push(Box::new(gundam().with_layout(layout)));
+    // 2x2 tiles with the full 1024 global view to their right
+    let size = pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+    assert_eq!(size, Size { w: 2304, h: 1280 });
+
+    let out = pipeline.process_frame(frame(1920, 1080)).await?;
+    assert_eq!((out.width, out.height), (2304, 1280));
+    assert_eq!(
+        out.meta.map_to_screen(Point {
+            x: 1280.0 + 512.0,
+            y: 512.0
+        }),
+        Some(Point { x: 960.0, y: 540.0 })
+    );
+    let at = |x: usize, y: usize| &out.data[(y * 2304 + x) * 4..][..4];
+    assert_eq!(at(1792, 512), &[128; 4]);
+    assert_eq!(at(1792, 1200), &[0, 0, 0, 255]);
+    Ok(())
+}
+
+/// Stream recording how frames reached it: the tile count of each frame set,
+/// or `None` for a plain frame.
+struct GundamSink {
+    config: StreamConfig,
+    received: Arc<Mutex<Vec<Option<usize>>>>,
+}
+
+#[async_trait]
+impl Stream for GundamSink {
+    async fn send_frame(&mut self, _frame: BgraFrame) -> Result<()> {
+        self.received.lock().unwrap().push(None);
+        Ok(())
+    }
+
+    async fn send_gundam(&mut self, _frame: BgraFrame, set: Arc<GundamFrameSet>) -> Result<()> {
+        self.received.lock().unwrap().push(Some(set.tiles.len()));
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn config(&self) -> &StreamConfig {
+        &self.config
+    }
+
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn multiplexer_hands_frame_sets_to_streams() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline.processors.push(Box::new(gundam()));
+    pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let mut multiplexer = StreamMultiplexer::new();
+    multiplexer.streams.push(Box::new(GundamSink {
+        config: StreamConfig {
+            width: 1920,
+            height: 1080,
+            fps: 30,
+            format: StreamFormat::File {
+                path: "unused.mp4".to_string(),
+            },
+        },
+        received: Arc::clone(&received),
+    }));
+    let tiled = pipeline.process_frame(frame(1920, 1080)).await?;
+    let tiles = tiled.meta.gundam.as_ref().unwrap().tiles.len();
+    multiplexer.send_frame(tiled).await?;
+    multiplexer.send_frame(frame(1920, 1080)).await?;
+
+    assert_eq!(*received.lock().unwrap(), [Some(tiles), None]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn transforms_accumulate_in_order() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline.processors.push(Box::new(scaling()));
+    pipeline.processors.push(Box::new(gundam()));
+    pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+
+    let input = frame(1920, 1080);
+    let shared = input.meta.clone();
+    let out = pipeline.process_frame(input).await?;
+
+    let transforms = &out.meta.transforms;
+    assert_eq!(transforms.len(), 2);
+    assert_eq!(transforms[0].output_size(), transforms[1].input_size());
+    assert_eq!(transforms[1].output_size(), size(out.width, out.height));
+    // Appending copied the shared list instead of changing the original
+    assert!(shared.transforms.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn letterboxed_scaling_maps_padding_to_nothing() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline
+        .processors
+        .push(Box::new(ScalingProcessor::new(ScaleSpec::letterbox(
+            512,
+            [255, 0, 0, 255],
+        ))));
+    pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+
+    let out = pipeline.process_frame(frame(1920, 1080)).await?;
+    assert_eq!((out.width, out.height), (512, 512));
+    // Red bar at the top in BGRA byte order, content in the middle
+    assert_eq!(&out.data[..4], &[0, 0, 255, 255]);
+    let middle = (256 * 512 + 256) * 4;
+    assert_eq!(&out.data[middle..middle + 4], &[128; 4]);
+
+    assert_eq!(out.meta.map_to_screen(Point { x: 256.0, y: 10.0 }), None);
+    assert_eq!(
+        out.meta.map_to_screen(Point { x: 256.0, y: 256.0 }),
+        Some(Point { x: 960.0, y: 540.0 })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn letterboxed_scaling_offsets_cursor() -> Result<()> {
+    let mut pipeline = ProcessingPipeline::new();
+    pipeline
+        .processors
+        .push(Box::new(ScalingProcessor::new(ScaleSpec::letterbox(
+            512,
+            [0, 0, 0, 255],
+        ))));
+    pipeline.initialize(Size { w: 1920, h: 1080 }).await?;
+
+    let cursor_at = |x, y| CursorInfo {
+        x,
+        y,
+        visible: true,
+        pressed: false,
+        image: None,
+    };
+    // Content is 512x288, centred below a 112px bar
+    for ((x, y), expected) in [
+        ((960, 540), (256, 256, true)),
+        ((0, 0), (0, 112, true)),
+        ((-50, 2000), (0, 400, false)),
+    ] {
+        let mut input = frame(1920, 1080);
+        input.cursor = Some(cursor_at(x, y));
+        let cursor = pipeline.process_frame(input).await?.cursor.unwrap();
+        assert_eq!((cursor.x, cursor.y, cursor.visible), expected);
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Tests for Gundam mosaic layouts and output buffer reuse.
+
+#![cfg(feature = "rtsp-streaming")]
+
+use cap_rtsp::{CompositeLayout, GlobalPlacement, GundamCompositor, arrange_gundam_composite};
+use std::sync::Arc;
+
+const TILE: u32 = 4;
+const GLOBAL: u32 = 8;
+
+/// Square BGRA buffer of `side` filled with one pixel value.
+fn solid(side: u32, pixel: [u8; 4]) -> Vec<u8> {
+    pixel.repeat((side * side) as usize)
+}
+
+fn pixel(data: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+    let i = ((y * width + x) * 4) as usize;
+    data[i..i + 4].try_into().unwrap()
+}
+
+fn compositor(global: GlobalPlacement) -> GundamCompositor {
+    GundamCompositor::new(CompositeLayout {
+        global,
+        bg_rgba: [0, 0, 255, 255], // Blue
+    })
+}
+
+const BLUE: [u8; 4] = [255, 0, 0, 255];
+const GREY: [u8; 4] = [90, 90, 90, 255];
+
+#[test]
+fn last_layout_matches_arrange_gundam_composite() {
+    let tiles: Vec<Vec<u8>> = (1..=3).map(|i| solid(TILE, [i * 10; 4])).collect();
+    let global = solid(GLOBAL, GREY);
+
+    let (data, width, height) = GundamCompositor::default()
+        .compose(&tiles, tiles.len(), &global, TILE, GLOBAL)
+        .unwrap();
+    assert_eq!((width, height), (8, 8));
+    assert_eq!(
+        arrange_gundam_composite(&tiles, &global, TILE, GLOBAL).unwrap(),
+        (data.to_vec(), width, height)
+    );
+    // Tiles in reading order, the shrunk global view in the fourth cell
+    assert_eq!(pixel(&data, width, 0, 0), [10; 4]);
+    assert_eq!(pixel(&data, width, 4, 0), [20; 4]);
+    assert_eq!(pixel(&data, width, 0, 4), [30; 4]);
+    assert_eq!(pixel(&data, width, 5, 5), GREY);
+}
+
+#[test]
+fn first_layout_puts_global_before_tiles() {
+    let tiles: Vec<Vec<u8>> = (1..=3).map(|i| solid(TILE, [i * 10; 4])).collect();
+    let (data, width, height) = compositor(GlobalPlacement::First)
+        .compose(&tiles, 3, &solid(GLOBAL, GREY), TILE, GLOBAL)
+        .unwrap();
+    assert_eq!((width, height), (8, 8));
+    assert_eq!(pixel(&data, width, 1, 1), GREY);
+    assert_eq!(pixel(&data, width, 4, 0), [10; 4]);
+    assert_eq!(pixel(&data, width, 0, 4), [20; 4]);
+    assert_eq!(pixel(&data, width, 7, 7), [30; 4]);
+}
+
+#[test]
+fn beside_layout_keeps_global_at_full_size() {
+    let tiles = vec![solid(TILE, [10; 4]), solid(TILE, [20; 4])];
+    // Global view with a distinct value in every pixel
+    let global: Vec<u8> = (0..GLOBAL * GLOBAL)
+        .flat_map(|i| [i as u8, 0, 0, 255])
+        .collect();
+    let layout = CompositeLayout {
+        global: GlobalPlacement::Beside,
+        bg_rgba: [0, 0, 255, 255],
+    };
+    let (data, width, height) = GundamCompositor::new(layout)
+        .compose(&tiles, 2, &global, TILE, GLOBAL)
+        .unwrap();
+
+    assert_eq!((width, height), (16, 8));
+    assert_eq!(layout.size(2, TILE, GLOBAL), (16, 8));
+    for y in 0..GLOBAL {
+        let row = (y * width + 8) as usize * 4;
+        let src = (y * GLOBAL) as usize * 4;
+        assert_eq!(data[row..row + 32], global[src..src + 32]);
+    }
+    // Below the single row of tiles is background, in BGRA byte order
+    assert_eq!(pixel(&data, width, 4, 0), [20; 4]);
+    assert_eq!(pixel(&data, width, 3, 6), BLUE);
+}
+
+#[test]
+fn empty_slots_show_background_and_buffers_are_reused() {
+    let mut compositor = compositor(GlobalPlacement::Last);
+    let tiles = [solid(TILE, [10; 4])];
+    let global = solid(GLOBAL, GREY);
+
+    let (first, width, _) = compositor
+        .compose(&tiles, 3, &global, TILE, GLOBAL)
+        .unwrap();
+    assert_eq!(pixel(&first, width, 4, 0), BLUE);
+    assert_eq!(pixel(&first, width, 0, 4), BLUE);
+    let address = Arc::as_ptr(&first);
+
+    // A frame still held keeps its buffer; a dropped one is written again
+    let (second, ..) = compositor
+        .compose(&tiles, 3, &global, TILE, GLOBAL)
+        .unwrap();
+    assert_ne!(Arc::as_ptr(&second), address);
+    drop(first);
+    let (third, ..) = compositor
+        .compose(&tiles, 3, &global, TILE, GLOBAL)
+        .unwrap();
+    assert_eq!(Arc::as_ptr(&third), address);
+    assert_eq!(second, third);
+}
+
+#[test]
+fn short_views_are_errors() {
+    let mut compositor = GundamCompositor::default();
+    let tiles = [solid(TILE, [10; 4])];
+    let global = solid(GLOBAL, GREY);
+
+    assert!(
+        compositor
+            .compose(&tiles, 1, &global[..global.len() - 4], TILE, GLOBAL)
+            .is_err()
+    );
+    assert!(
+        compositor
+            .compose(&[&tiles[0][..4]], 1, &global, TILE, GLOBAL)
+            .is_err()
+    );
+    assert!(compositor.compose(&tiles, 1, &global, TILE, GLOBAL).is_ok());
+}
@@ -0,0 +1,179 @@
+//! Tests for playing back folders of screenshots as a capture source.
+
+#![cfg(all(feature = "rtsp-streaming", feature = "screen-capture"))]
+
+use anyhow::Result;
+use cap_rtsp::Transform;
+use hybrid_screen_capture::capture::session_sources::ImageSequenceCaptureSource;
+use hybrid_screen_capture::processing::Rect;
+use hybrid_screen_capture::session::CaptureSource;
+use image::{Rgba, RgbaImage};
+use std::path::Path;
+use std::time::Duration;
+
+/// Writes a solid `w`x`h` image in RGBA `color`; the format follows the extension.
+fn write_image(path: &Path, w: u32, h: u32, color: [u8; 4]) {
+    let image = RgbaImage::from_pixel(w, h, Rgba(color));
+    if path.extension().is_some_and(|ext| ext == "jpg") {
+        image::DynamicImage::ImageRgba8(image)
+            .to_rgb8()
+            .save(path)
+            .unwrap();
+    } else {
+        image.save(path).unwrap();
+    }
+}
+
+fn file_names(source: &ImageSequenceCaptureSource) -> Vec<String> {
+    source
+        .paths()
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[tokio::test]
+async fn plays_directory_in_natural_order_as_bgra() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_image(&dir.path().join("shot-10.png"), 4, 2, [0, 0, 255, 255]);
+    write_image(&dir.path().join("shot-2.png"), 4, 2, [255, 0, 0, 128]);
+    write_image(&dir.path().join("shot-1.jpg"), 4, 2, [255, 255, 255, 255]);
+    std::fs::write(dir.path().join("notes.txt"), "not an image")?;
+
+    let mut source = ImageSequenceCaptureSource::new(dir.path())?;
+    assert_eq!(
+        file_names(&source),
+        ["shot-1.jpg", "shot-2.png", "shot-10.png"]
+    );
+
+    source.initialize().await?;
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (4, 2));
+
+    let white = source.capture_frame().await?;
+    assert_eq!(white.stride, 16);
+    assert!(
+        white.data.iter().all(|&b| b > 250),
+        "JPEG decodes near white"
+    );
+
+    // RGBA red with half alpha becomes BGRA [0, 0, 255, 128]
+    let red = source.capture_frame().await?;
+    assert_eq!(&red.data[..4], &[0, 0, 255, 128]);
+    let blue = source.capture_frame().await?;
+    assert_eq!(&blue.data[..4], &[255, 0, 0, 255]);
+    assert!(blue.pts_ns > red.pts_ns);
+
+    assert!(!source.is_finished());
+    assert!(source.capture_frame().await.is_err());
+    assert!(source.is_finished());
+
+    source.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn glob_selects_matching_files() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    for name in ["page-1.png", "page-2.png", "cover.png", "page-3.jpg"] {
+        write_image(&dir.path().join(name), 2, 2, [0, 0, 0, 255]);
+    }
+
+    let source = ImageSequenceCaptureSource::new(dir.path().join("page-?.png"))?;
+    assert_eq!(file_names(&source), ["page-1.png", "page-2.png"]);
+
+    let source = ImageSequenceCaptureSource::new(dir.path().join("*.jpg"))?;
+    assert_eq!(file_names(&source), ["page-3.jpg"]);
+
+    assert!(ImageSequenceCaptureSource::new(dir.path().join("*.gif")).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn looping_screenshot_uses_fixed_interval_timestamps() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("screenshot.png");
+    write_image(&path, 3, 3, [10, 20, 30, 255]);
+
+    let interval = Duration::from_millis(5);
+    let mut source = ImageSequenceCaptureSource::new(&path)?
+        .with_frame_interval(interval)
+        .with_looping(true);
+    source.initialize().await?;
+
+    for n in 0..4u64 {
+        let frame = source.capture_frame().await?;
+        assert_eq!(frame.pts_ns, Some(n * interval.as_nanos() as u64));
+        assert_eq!(&frame.data[..4], &[30, 20, 10, 255]);
+    }
+    assert!(!source.is_finished());
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_images_of_different_sizes() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_image(&dir.path().join("a.png"), 4, 4, [0, 0, 0, 255]);
+    write_image(&dir.path().join("b.png"), 8, 8, [0, 0, 0, 255]);
+
+    let mut source = ImageSequenceCaptureSource::new(dir.path())?;
+    source.initialize().await?;
+    source.capture_frame().await?;
+    assert!(source.capture_frame().await.is_err());
+    assert!(!source.is_finished());
+    Ok(())
+}
+
+#[tokio::test]
+async fn region_crops_each_image() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut image = RgbaImage::from_pixel(8, 6, Rgba([0, 0, 0, 255]));
+    image.put_pixel(3, 2, Rgba([255, 0, 0, 255]));
+    image.save(dir.path().join("shot.png"))?;
+
+    let region = Rect {
+        x: 2,
+        y: 1,
+        w: 4,
+        h: 3,
+    };
+    let mut source = ImageSequenceCaptureSource::new(dir.path())?.with_region(region);
+    source.initialize().await?;
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (4, 3));
+
+    let frame = source.capture_frame().await?;
+    assert_eq!((frame.width, frame.height, frame.stride), (4, 3, 16));
+    // Pixel (3, 2) of the image is (1, 1) of the region
+    assert_eq!(&frame.data[20..24], &[0, 0, 255, 255]);
+    assert_eq!(
+        frame.meta.transforms.as_slice(),
+        [Transform::Crop {
+            input: cap_scale::presets::Size { w: 8, h: 6 },
+            rect: cap_scale::gundam::Rect {
+                x: 2,
+                y: 1,
+                w: 4,
+                h: 3
+            },
+        }]
+    );
+    assert!(frame.meta.attribute("path").is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn region_outside_images_fails_initialization() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    write_image(&dir.path().join("shot.png"), 4, 4, [0, 0, 0, 255]);
+
+    let region = Rect {
+        x: 2,
+        y: 2,
+        w: 4,
+        h: 4,
+    };
+    let mut source = ImageSequenceCaptureSource::new(dir.path())?.with_region(region);
+    assert!(source.initialize().await.is_err());
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Tests for the `videotestsrc`-backed synthetic capture source.
+//!
+//! These need the GStreamer base plugins and are ignored by default:
+//!
+//! ```bash
+//! cargo test --features rtsp-streaming --test test_synthetic_capture_source -- --ignored
+//! ```
+
+#![cfg(all(feature = "rtsp-streaming", feature = "screen-capture"))]
+
+use anyhow::Result;
+use hybrid_screen_capture::capture::session_sources::{SyntheticCaptureSource, TestPattern};
+use hybrid_screen_capture::processing::Rect;
+use hybrid_screen_capture::session::CaptureSource;
+
+#[tokio::test]
+#[ignore = "requires GStreamer base plugins"]
+async fn generates_frames_at_configured_size_and_rate() -> Result<()> {
+    let mut source = SyntheticCaptureSource::new(320, 240)
+        .with_pattern(TestPattern::Ball)
+        .with_framerate(25)
+        .with_live(false);
+    source.initialize().await?;
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (320, 240));
+
+    let mut frames = Vec::new();
+    for _ in 0..3 {
+        frames.push(source.capture_frame().await?);
+    }
+    for (n, frame) in frames.iter().enumerate() {
+        assert_eq!((frame.width, frame.height), (320, 240));
+        assert_eq!(frame.data.len(), frame.stride * 240);
+        assert_eq!(frame.pts_ns, Some(n as u64 * 40_000_000));
+    }
+    // The ball moves between frames
+    assert_ne!(frames[0].data, frames[2].data);
+
+    source.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires GStreamer base plugins"]
+async fn timestamp_overlay_makes_static_patterns_change() -> Result<()> {
+    let mut source = SyntheticCaptureSource::new(640, 360)
+        .with_pattern(TestPattern::Smpte)
+        .with_live(false);
+    source.initialize().await?;
+    let first = source.capture_frame().await?;
+    let mut last = source.capture_frame().await?;
+    for _ in 0..30 {
+        last = source.capture_frame().await?;
+    }
+    assert_ne!(first.data, last.data);
+
+    let mut plain = SyntheticCaptureSource::new(640, 360)
+        .with_timestamp_overlay(false)
+        .with_live(false);
+    plain.initialize().await?;
+    let a = plain.capture_frame().await?;
+    let b = plain.capture_frame().await?;
+    assert_eq!(a.data, b.data);
+    Ok(())
+}
+
+#[test]
+fn region_must_fit_the_pattern() {
+    let region = |x, y, w, h| Rect { x, y, w, h };
+    let source = SyntheticCaptureSource::new(320, 240)
+        .with_region(region(40, 20, 160, 120))
+        .unwrap();
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (160, 120));
+
+    assert!(
+        SyntheticCaptureSource::new(320, 240)
+            .with_region(region(200, 0, 160, 120))
+            .is_err()
+    );
+}
+
+#[test]
+fn pattern_names_round_trip() {
+    for pattern in TestPattern::ALL {
+        assert_eq!(pattern.name().parse::<TestPattern>().unwrap(), pattern);
+    }
+    assert!("plasma".parse::<TestPattern>().is_err());
+}
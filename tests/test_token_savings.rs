@@ -1,7 +1,11 @@
 use cap_scale::cpu::{Staging, scale_bgra_cpu};
 use cap_scale::presets::{AspectMode, ScalePlan, Size, TokenPreset, build_plan};
+use cap_scale::tokens::{PatchTokens, QWEN, TokenModel};
 use fast_image_resize::Resizer;
 
+/// Token counts follow Qwen2.5-VL, which bills one token per 28px patch
+const MODEL: PatchTokens = QWEN;
+
 /// Test token savings from different scaling presets
 /// This verifies that our presets actually reduce token usage for VLMs
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -11,6 +15,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   - P2_56, P4, P9, P10_24: max 640px on longest side");
     println!("   - P6_9: max 512px on longest side");
     println!("🔧 Using actual scaling functions from cap-scale crate");
+    println!("🔢 Token counts from cap_scale::tokens::{}", MODEL.name());
     println!();
 
     // Create a test image (1920x1080 - common desktop resolution)
@@ -39,7 +44,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         w: test_width,
         h: test_height,
     };
-    let control_tokens = calculate_vision_tokens(control_size);
+    let control_tokens = MODEL.image_tokens(control_size);
     println!(
         "🎯 Control (no scaling): {}x{} = {} tokens",
         control_size.w, control_size.h, control_tokens
@@ -70,7 +75,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             h: scaled_result.height,
         };
 
-        let tokens = calculate_vision_tokens(actual_output_size);
+        let tokens = MODEL.image_tokens(actual_output_size);
         let savings = control_tokens.saturating_sub(tokens);
         let ratio = control_tokens as f64 / tokens as f64;
 
@@ -90,7 +95,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         w: test_4k_width,
         h: test_4k_height,
     };
-    let control_4k_tokens = calculate_vision_tokens(control_4k_size);
+    let control_4k_tokens = MODEL.image_tokens(control_4k_size);
     println!(
         "🎯 Control (no scaling): {}x{} = {} tokens",
         control_4k_size.w, control_4k_size.h, control_4k_tokens
@@ -121,7 +126,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             h: scaled_result.height,
         };
 
-        let tokens = calculate_vision_tokens(actual_output_size);
+        let tokens = MODEL.image_tokens(actual_output_size);
         let savings = control_4k_tokens.saturating_sub(tokens);
         let ratio = control_4k_tokens as f64 / tokens as f64;
 
@@ -148,7 +153,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             w: width,
             h: height,
         };
-        let control_tokens = calculate_vision_tokens(control_size);
+        let control_tokens = MODEL.image_tokens(control_size);
         println!(
             "  Control: {}x{} = {} tokens",
             control_size.w, control_size.h, control_tokens
@@ -173,7 +178,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 h: scaled_result.height,
             };
 
-            let tokens = calculate_vision_tokens(actual_output_size);
+            let tokens = MODEL.image_tokens(actual_output_size);
             let ratio = control_tokens as f64 / tokens as f64;
 
             println!(
@@ -190,27 +195,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// Calculate approximate vision tokens for an image
-/// This is a simplified calculation based on typical VLM tokenization:
-/// - Images are divided into patches (typically 14x14 or 16x16 pixels)
-/// - Each patch becomes a fixed number of tokens
-/// - Total tokens = (height / patch_size) * (width / patch_size) * tokens_per_patch
-fn calculate_vision_tokens(size: Size) -> u64 {
-    // Typical VLM parameters (similar to CLIP/SigLIP)
-    const PATCH_SIZE: u32 = 14; // Common patch size
-    const TOKENS_PER_PATCH: u64 = 4; // Approximate tokens per patch
-
-    // Calculate number of patches in each dimension
-    let patches_h = (size.h + PATCH_SIZE - 1) / PATCH_SIZE; // Ceiling division
-    let patches_w = (size.w + PATCH_SIZE - 1) / PATCH_SIZE;
-
-    // Total patches
-    let total_patches = patches_h as u64 * patches_w as u64;
-
-    // Total tokens (patches * tokens_per_patch + some overhead)
-    total_patches * TOKENS_PER_PATCH + 85 // +85 for special tokens (CLS, etc.)
-}
-
 /// Create a test image with some content (BGRA format)
 fn create_test_image_bgra(width: u32, height: u32) -> Vec<u8> {
     // Create a simple gradient image in BGRA format
@@ -0,0 +1,384 @@
+//! Integration tests for X11 session capture sources.
+//!
+//! These tests start a private `Xvfb` server and capture from it, so they need
+//! `Xvfb` and `ffmpeg` on PATH. They are ignored by default:
+//!
+//! ```bash
+//! cargo test --features rtsp-streaming --test test_x11_capture_sources -- --ignored
+//! ```
+
+#![cfg(all(
+    feature = "rtsp-streaming",
+    feature = "screen-capture",
+    target_os = "linux"
+))]
+
+use anyhow::Result;
+use hybrid_screen_capture::capture::session_sources::{
+    FFmpegCaptureSource, X11CaptureTarget, X11ShmCaptureSource,
+};
+use hybrid_screen_capture::processing::Rect;
+use hybrid_screen_capture::session::CaptureSource;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+const XVFB_WIDTH: u32 = 320;
+const XVFB_HEIGHT: u32 = 240;
+
+/// Running `Xvfb` instance, killed on drop.
+struct Xvfb {
+    display: String,
+    child: Child,
+}
+
+impl Xvfb {
+    fn start(display_num: u32) -> Result<Self> {
+        let display = format!(":{}", display_num);
+        let child = Command::new("Xvfb")
+            .args([
+                display.as_str(),
+                "-screen",
+                "0",
+                &format!("{}x{}x24", XVFB_WIDTH, XVFB_HEIGHT),
+                "-nolisten",
+                "tcp",
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // Wait for the server to accept connections
+        for _ in 0..50 {
+            if x11rb::connect(Some(&display)).is_ok() {
+                return Ok(Self { display, child });
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        anyhow::bail!("Xvfb did not come up on {}", display)
+    }
+}
+
+impl Drop for Xvfb {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[tokio::test]
+#[ignore = "requires Xvfb and ffmpeg"]
+async fn ffmpeg_source_captures_xvfb_display() -> Result<()> {
+    let xvfb = Xvfb::start(97)?;
+
+    let mut source = FFmpegCaptureSource::new(&xvfb.display)?.with_framerate(10);
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (XVFB_WIDTH, XVFB_HEIGHT));
+
+    source.initialize().await?;
+
+    let mut last_pts = None;
+    for _ in 0..3 {
+        let frame = source.capture_frame().await?;
+        assert_eq!((frame.width, frame.height), (XVFB_WIDTH, XVFB_HEIGHT));
+        assert_eq!(frame.stride, XVFB_WIDTH as usize * 4);
+        assert_eq!(frame.data.len(), frame.stride * XVFB_HEIGHT as usize);
+
+        let pts = frame.pts_ns.expect("x11grab frames carry a timestamp");
+        if let Some(last) = last_pts {
+            assert!(pts > last, "pts must be monotonic: {} <= {}", pts, last);
+        }
+        last_pts = Some(pts);
+    }
+
+    source.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires Xvfb and ffmpeg"]
+async fn ffmpeg_source_captures_region() -> Result<()> {
+    let xvfb = Xvfb::start(96)?;
+
+    let region = Rect {
+        x: 16,
+        y: 8,
+        w: 128,
+        h: 64,
+    };
+    let mut source = FFmpegCaptureSource::new(&xvfb.display)?.with_region(region)?;
+    let size = source.input_size();
+    assert_eq!((size.w, size.h), (128, 64));
+
+    source.initialize().await?;
+    let frame = source.capture_frame().await?;
+    assert_eq!((frame.width, frame.height), (128, 64));
+    assert_eq!(frame.data.len(), 128 * 64 * 4);
+    source.shutdown().await?;
+
+    let too_wide = Rect {
+        x: XVFB_WIDTH - 10,
+        ..region
+    };
+    assert!(
+        FFmpegCaptureSource::new(&xvfb.display)?
+            .with_region(too_wide)
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires Xvfb"]
+async fn shm_source_captures_root_and_region() -> Result<()> {
+    let xvfb = Xvfb::start(98)?;
+
+    let mut source = X11ShmCaptureSource::new(&xvfb.display)?.with_framerate(60);
+    source.initialize().await?;
+    assert!(source.uses_shm(), "Xvfb supports MIT-SHM");
+
+    let frame = source.capture_frame().await?;
+    assert_eq!((frame.width, frame.height), (XVFB_WIDTH, XVFB_HEIGHT));
+    assert_eq!(frame.data.len(), frame.stride * XVFB_HEIGHT as usize);
+    assert!(frame.data.chunks_exact(4).all(|px| px[3] == 255));
+
+    // Xvfb starts with the pointer centered and an X-shaped default cursor
+    assert!(source.tracks_cursor(), "Xvfb supports XFixes");
+    let cursor = frame.cursor.expect("XFixes reports the cursor");
+    assert_eq!(
+        (cursor.x, cursor.y),
+        (XVFB_WIDTH as i32 / 2, XVFB_HEIGHT as i32 / 2)
+    );
+    assert!(cursor.visible && !cursor.pressed);
+    assert!(cursor.image.is_some_and(|image| image.width > 0));
+    source.shutdown().await?;
+
+    let region = Rect {
+        x: 10,
+        y: 20,
+        w: 100,
+        h: 50,
+    };
+    let mut source = X11ShmCaptureSource::new(&xvfb.display)?.with_region(region)?;
+    source.initialize().await?;
+    let first = source.capture_frame().await?;
+    let second = source.capture_frame().await?;
+    assert_eq!((first.width, first.height), (100, 50));
+    assert!(second.pts_ns > first.pts_ns);
+    source.shutdown().await?;
+
+    let out_of_bounds = Rect {
+        x: XVFB_WIDTH - 10,
+        y: 0,
+        w: 20,
+        h: 20,
+    };
+    assert!(
+        X11ShmCaptureSource::new(&xvfb.display)?
+            .with_region(out_of_bounds)
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires Xvfb"]
+async fn shm_source_captures_window() -> Result<()> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ConnectionExt, CreateWindowAux, WindowClass};
+
+    let xvfb = Xvfb::start(99)?;
+
+    let (conn, screen_num) = x11rb::connect(Some(&xvfb.display))?;
+    let screen = &conn.setup().roots[screen_num];
+    let window = conn.generate_id()?;
+    conn.create_window(
+        screen.root_depth,
+        window,
+        screen.root,
+        5,
+        5,
+        64,
+        48,
+        0,
+        WindowClass::INPUT_OUTPUT,
+        screen.root_visual,
+        &CreateWindowAux::new().background_pixel(screen.white_pixel),
+    )?;
+    conn.map_window(window)?;
+    // Round-trip so the window is mapped before capture starts
+    conn.get_input_focus()?.reply()?;
+
+    let mut source =
+        X11ShmCaptureSource::with_target(&xvfb.display, X11CaptureTarget::Window(window))?;
+    source.initialize().await?;
+    let frame = source.capture_frame().await?;
+    assert_eq!((frame.width, frame.height), (64, 48));
+    assert!(frame.data.iter().all(|&b| b == 255), "window is white");
+
+    source.shutdown().await?;
+    Ok(())
+}
+
+#[test]
+#[ignore = "requires Xvfb"]
+fn window_selector_resolves_by_title_and_class() -> Result<()> {
+    use hybrid_screen_capture::capture::window_select::WindowSelector;
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{
+        AtomEnum, ConnectionExt, CreateWindowAux, PropMode, WindowClass,
+    };
+    use x11rb::wrapper::ConnectionExt as _;
+
+    let xvfb = Xvfb::start(95)?;
+
+    // No window manager runs, so candidates come from the mapped root children
+    let (conn, screen_num) = x11rb::connect(Some(&xvfb.display))?;
+    let screen = &conn.setup().roots[screen_num];
+    let mut windows = Vec::new();
+    for (x, title, class) in [
+        (0, "editor", b"ed\0Editor\0"),
+        (100, "viewer", b"vw\0Viewer\0"),
+    ] {
+        let window = conn.generate_id()?;
+        conn.create_window(
+            screen.root_depth,
+            window,
+            screen.root,
+            x,
+            10,
+            80,
+            60,
+            0,
+            WindowClass::INPUT_OUTPUT,
+            screen.root_visual,
+            &CreateWindowAux::new(),
+        )?;
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_NAME,
+            AtomEnum::STRING,
+            title.as_bytes(),
+        )?;
+        conn.change_property8(
+            PropMode::REPLACE,
+            window,
+            AtomEnum::WM_CLASS,
+            AtomEnum::STRING,
+            class,
+        )?;
+        conn.map_window(window)?;
+        windows.push(window);
+    }
+    conn.get_input_focus()?.reply()?;
+
+    let viewer = WindowSelector::new()
+        .with_title("^view")?
+        .resolve_x11(&xvfb.display)?;
+    assert_eq!(viewer.id, windows[1] as u64);
+    assert_eq!(viewer.class.as_deref(), Some("Viewer"));
+    assert_eq!(
+        viewer.geometry,
+        Rect {
+            x: 100,
+            y: 10,
+            w: 80,
+            h: 60
+        }
+    );
+
+    let editor = WindowSelector::new()
+        .with_class("ED")
+        .resolve_x11(&xvfb.display)?;
+    assert_eq!(editor.id, windows[0] as u64);
+
+    // Both windows match an empty selector, so selection is ambiguous
+    assert!(WindowSelector::new().resolve_x11(&xvfb.display).is_err());
+    Ok(())
+}
+
+#[test]
+#[ignore = "requires Xvfb"]
+fn enumerates_xvfb_displays_and_windows() -> Result<()> {
+    use hybrid_screen_capture::capture::enumerate::{
+        enumerate_x11_displays, enumerate_x11_windows,
+    };
+
+    let xvfb = Xvfb::start(94)?;
+
+    let displays = enumerate_x11_displays(&xvfb.display)?;
+    assert_eq!(displays.len(), 1);
+    assert!(displays[0].primary);
+    assert_eq!(
+        displays[0].geometry,
+        Rect {
+            x: 0,
+            y: 0,
+            w: XVFB_WIDTH,
+            h: XVFB_HEIGHT
+        }
+    );
+    assert_eq!(displays[0].scale_factor, 1.0);
+
+    // A bare server has no top-level windows yet
+    assert!(enumerate_x11_windows(&xvfb.display)?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires Xvfb"]
+async fn shm_source_reports_damage() -> Result<()> {
+    use x11rb::connection::Connection;
+    use x11rb::protocol::xproto::{ConnectionExt, CreateWindowAux, WindowClass};
+
+    let xvfb = Xvfb::start(93)?;
+
+    let mut source = X11ShmCaptureSource::new(&xvfb.display)?.with_framerate(60);
+    source.initialize().await?;
+    assert!(source.uses_damage(), "Xvfb supports DAMAGE");
+
+    let full = Rect {
+        x: 0,
+        y: 0,
+        w: XVFB_WIDTH,
+        h: XVFB_HEIGHT,
+    };
+    let first = source.capture_frame().await?;
+    assert_eq!(source.changed_regions(), Some(&[full][..]));
+
+    // Nothing was drawn: the previous pixels are reused
+    let second = source.capture_frame().await?;
+    assert_eq!(source.changed_regions(), Some(&[][..]));
+    assert!(std::sync::Arc::ptr_eq(&first.data, &second.data));
+
+    let (conn, screen_num) = x11rb::connect(Some(&xvfb.display))?;
+    let screen = &conn.setup().roots[screen_num];
+    let window = conn.generate_id()?;
+    conn.create_window(
+        screen.root_depth,
+        window,
+        screen.root,
+        40,
+        30,
+        16,
+        8,
+        0,
+        WindowClass::INPUT_OUTPUT,
+        screen.root_visual,
+        &CreateWindowAux::new().background_pixel(screen.white_pixel),
+    )?;
+    conn.map_window(window)?;
+    conn.get_input_focus()?.reply()?;
+
+    source.capture_frame().await?;
+    let changed = source.changed_regions().expect("damage is tracked");
+    assert!(changed.contains(&Rect {
+        x: 40,
+        y: 30,
+        w: 16,
+        h: 8
+    }));
+
+    source.shutdown().await?;
+    Ok(())
+}
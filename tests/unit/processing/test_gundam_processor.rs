@@ -107,7 +107,7 @@ impl FrameProcessor for GundamProcessor {
             &self.global_buffer,
             self.cfg.tile_side,
             self.cfg.global_side,
-        );
+        )?;
 
         let composite_frame = BgraFrame {
             data: Arc::new(composite),